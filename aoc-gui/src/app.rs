@@ -0,0 +1,235 @@
+use crate::days::{Animation, Day, Part, Timing};
+use crate::grid_view::GridView;
+use eframe::egui::{self, Color32, Ui};
+use egui_plot::{Bar, BarChart, Legend, Plot};
+use std::fs;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Tab {
+    Day(Day),
+    Timings,
+}
+
+/// Everything shown on one day's tab
+struct DayView {
+    day: Day,
+    input_path: String,
+    part: Part,
+    animation: Option<Result<Animation, String>>,
+    step: usize,
+    playing: bool,
+    /// How many steps a second to play
+    speed: f64,
+    /// When playing last moved on a step, in seconds since the viewer started
+    last_step_time: f64,
+    grid: GridView,
+}
+
+impl DayView {
+    fn new(day: Day) -> Self {
+        Self {
+            day,
+            input_path: day.input_path().to_string(),
+            part: Part::One,
+            animation: None,
+            step: 0,
+            playing: false,
+            speed: 10.0,
+            last_step_time: 0.0,
+            grid: GridView::new(),
+        }
+    }
+
+    /// Reads the input and works out the frames for the chosen part, starting back at the first
+    fn load(&mut self) {
+        let animation = fs::read_to_string(&self.input_path)
+            .map_err(|err| format!("failed to read {}: {err}", self.input_path))
+            .and_then(|input| self.day.animate(&input, self.part));
+        self.animation = Some(animation);
+        self.step = 0;
+        self.playing = false;
+        self.grid.reset_view();
+    }
+
+    fn show(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Input");
+            ui.text_edit_singleline(&mut self.input_path);
+            if ui.button("Load").clicked() {
+                self.load();
+            }
+        });
+        ui.horizontal(|ui| {
+            let mut part = self.part;
+            for (value, text) in [(Part::One, "Part 1"), (Part::Two, "Part 2")] {
+                ui.radio_value(&mut part, value, text);
+            }
+            ui.label(self.day.describe(part));
+            if part != self.part {
+                self.part = part;
+                if self.animation.is_some() {
+                    self.load();
+                }
+            }
+        });
+
+        let animation = match &self.animation {
+            None => return,
+            Some(Err(err)) => {
+                ui.colored_label(Color32::LIGHT_RED, err);
+                return;
+            }
+            Some(Ok(animation)) => animation,
+        };
+        let last = animation.frames.len().saturating_sub(1);
+        ui.label(&animation.summary);
+        ui.horizontal(|ui| {
+            if ui.button("⏮").clicked() {
+                self.step = 0;
+            }
+            if ui.button(if self.playing { "⏸" } else { "▶" }).clicked() {
+                // Playing from the end starts again from the beginning
+                if !self.playing && self.step == last {
+                    self.step = 0;
+                }
+                self.playing = !self.playing;
+            }
+            ui.add(egui::Slider::new(&mut self.step, 0..=last).text("step"));
+            ui.add(
+                egui::Slider::new(&mut self.speed, 1.0..=120.0)
+                    .logarithmic(true)
+                    .suffix(" steps/s"),
+            );
+            if ui.button("Reset view").clicked() {
+                self.grid.reset_view();
+            }
+        });
+
+        if self.playing {
+            let now = ui.input(|input| input.time);
+            if now - self.last_step_time >= 1.0 / self.speed {
+                self.step = (self.step + 1).min(last);
+                self.playing = self.step < last;
+                self.last_step_time = now;
+            }
+            ui.ctx().request_repaint();
+        }
+
+        if let Some(frame) = animation.frames.get(self.step) {
+            self.grid.show(ui, frame, &animation.palette);
+        }
+    }
+}
+
+/// Steps through the days' visualizations and compares how long their solvers take
+pub struct App {
+    tab: Tab,
+    days: Vec<DayView>,
+    /// The last time each day was solved, from the timings tab
+    timings: Vec<(Day, Result<[Timing; 2], String>)>,
+}
+
+impl App {
+    pub fn new() -> Self {
+        Self {
+            tab: Tab::Day(Day::ALL[0]),
+            days: Day::ALL.into_iter().map(DayView::new).collect(),
+            timings: vec![],
+        }
+    }
+
+    /// Solves every day from the input its tab points at. This runs on the UI thread, so the
+    /// window stops responding until they're all done.
+    fn solve_all(&mut self) {
+        self.timings = self
+            .days
+            .iter()
+            .map(|view| {
+                let timings = fs::read_to_string(&view.input_path)
+                    .map_err(|err| format!("failed to read {}: {err}", view.input_path))
+                    .and_then(|input| view.day.solve(&input));
+                (view.day, timings)
+            })
+            .collect();
+    }
+
+    fn show_timings(&mut self, ui: &mut Ui) {
+        if ui.button("Solve all").clicked() {
+            self.solve_all();
+        }
+
+        egui::Grid::new("timings").striped(true).show(ui, |ui| {
+            for heading in ["", "Part 1", "Time", "Part 2", "Time"] {
+                ui.strong(heading);
+            }
+            ui.end_row();
+            for (day, timings) in &self.timings {
+                ui.label(day.title());
+                match timings {
+                    Ok(timings) => {
+                        for timing in timings {
+                            ui.label(&timing.answer);
+                            ui.label(format!("{:.2?}", timing.time));
+                        }
+                    }
+                    Err(err) => {
+                        ui.colored_label(Color32::LIGHT_RED, err);
+                    }
+                }
+                ui.end_row();
+            }
+        });
+
+        // Each day gets a pair of bars, one for each part
+        let bars = |part: usize| {
+            self.timings
+                .iter()
+                .enumerate()
+                .filter_map(|(i, (day, timings))| {
+                    let timing = &timings.as_ref().ok()?[part];
+                    let x = i as f64 + if part == 0 { -0.2 } else { 0.2 };
+                    let millis = timing.time.as_secs_f64() * 1000.0;
+                    Some(Bar::new(x, millis).width(0.4).name(day.title()))
+                })
+                .collect::<Vec<_>>()
+        };
+        let days = self.timings.iter().map(|(day, _)| *day).collect::<Vec<_>>();
+        Plot::new("timings_chart")
+            .legend(Legend::default())
+            .y_axis_label("ms")
+            .x_axis_formatter(move |mark, _| {
+                let i = mark.value.round();
+                match days.get(i as usize) {
+                    Some(day) if (mark.value - i).abs() < 1e-6 => day.title().to_string(),
+                    _ => String::new(),
+                }
+            })
+            .allow_zoom(false)
+            .allow_drag(false)
+            .show(ui, |plot_ui| {
+                plot_ui.bar_chart(BarChart::new("Part 1", bars(0)));
+                plot_ui.bar_chart(BarChart::new("Part 2", bars(1)));
+            });
+    }
+}
+
+impl eframe::App for App {
+    fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
+        egui::TopBottomPanel::top("tabs").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                for day in Day::ALL {
+                    ui.selectable_value(&mut self.tab, Tab::Day(day), day.title());
+                }
+                ui.selectable_value(&mut self.tab, Tab::Timings, "Timings");
+            });
+        });
+        egui::CentralPanel::default().show(ctx, |ui| match self.tab {
+            Tab::Day(day) => {
+                if let Some(view) = self.days.iter_mut().find(|view| view.day == day) {
+                    view.show(ui);
+                }
+            }
+            Tab::Timings => self.show_timings(ui),
+        });
+    }
+}
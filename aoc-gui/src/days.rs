@@ -0,0 +1,270 @@
+//! The days the viewer can show. Each one turns its puzzle input into frames to step through,
+//! using the same frames its own `--animate` or `--render` options draw, and can time its
+//! solvers.
+
+use aoc_viz::{Frame, Palette, Recording};
+use day_14::{find_cycle, Platform, SPIN_CYCLE};
+use day_16::{BeamCache, Contraption};
+use day_17::{HeatMap, CRUCIBLE, ULTRA_CRUCIBLE};
+use std::time::{Duration, Instant};
+
+/// How many spin cycles day 14's part 2 runs
+const SPIN_CYCLES: usize = 1_000_000_000;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Day {
+    ReflectorDish,
+    LavaFloor,
+    ClumsyCrucible,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Part {
+    One,
+    Two,
+}
+
+/// The frames of one part of a day, along with what they come to
+pub struct Animation {
+    pub frames: Vec<Frame>,
+    pub palette: Palette,
+    pub summary: String,
+}
+
+/// How long a part took to solve and what it came to
+#[derive(Debug, Clone)]
+pub struct Timing {
+    pub answer: String,
+    pub time: Duration,
+}
+
+impl Day {
+    pub const ALL: [Day; 3] = [Day::ReflectorDish, Day::LavaFloor, Day::ClumsyCrucible];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Day::ReflectorDish => "Day 14: Parabolic Reflector Dish",
+            Day::LavaFloor => "Day 16: The Floor Will Be Lava",
+            Day::ClumsyCrucible => "Day 17: Clumsy Crucible",
+        }
+    }
+
+    /// Where the day's own binary reads its input from, run from this crate's directory
+    pub fn input_path(self) -> &'static str {
+        match self {
+            Day::ReflectorDish => "../day_14/input.txt",
+            Day::LavaFloor => "../day_16/input.txt",
+            Day::ClumsyCrucible => "../day_17/input.txt",
+        }
+    }
+
+    /// What stepping through `part` shows
+    pub fn describe(self, part: Part) -> &'static str {
+        match (self, part) {
+            (Day::ReflectorDish, Part::One) => "The platform tilted north",
+            (Day::ReflectorDish, Part::Two) => "Every tilt up to the end of the first loop",
+            (Day::LavaFloor, Part::One) => "The beam spreading from the top left",
+            (Day::LavaFloor, Part::Two) => "The beam from the best entry point",
+            (Day::ClumsyCrucible, Part::One) => "The crucible's path",
+            (Day::ClumsyCrucible, Part::Two) => "The ultra crucible's path",
+        }
+    }
+
+    /// Works out the frames for `part` from the puzzle `input`
+    pub fn animate(self, input: &str, part: Part) -> Result<Animation, String> {
+        match self {
+            Day::ReflectorDish => animate_platform(&parse_platform(input)?, part),
+            Day::LavaFloor => animate_beam(&parse_contraption(input)?, part),
+            Day::ClumsyCrucible => animate_path(&parse_heat_map(input)?, part),
+        }
+    }
+
+    /// Solves both parts from the puzzle `input`, timing each one. Parsing isn't counted.
+    pub fn solve(self, input: &str) -> Result<[Timing; 2], String> {
+        Ok(match self {
+            Day::ReflectorDish => {
+                let platform = parse_platform(input)?;
+                [
+                    timed(|| {
+                        let mut tilted = platform.clone();
+                        tilted.tilt(day_14::Direction::North);
+                        tilted.north_load()
+                    }),
+                    timed(|| platform.after_spin_cycles(SPIN_CYCLES).north_load()),
+                ]
+            }
+            Day::LavaFloor => {
+                let contraption = parse_contraption(input)?;
+                [
+                    timed(|| {
+                        day_16::energized_tiles(&contraption, (0, 0), day_16::Direction::Right)
+                    }),
+                    timed(|| BeamCache::new(&contraption).max_energized_tiles()),
+                ]
+            }
+            Day::ClumsyCrucible => {
+                let heat_map = parse_heat_map(input)?;
+                let answer = |heat_loss: Option<u32>| {
+                    heat_loss.map_or("no path".to_string(), |heat_loss| heat_loss.to_string())
+                };
+                [
+                    timed(|| answer(heat_map.min_heat_loss(CRUCIBLE))),
+                    timed(|| answer(heat_map.min_heat_loss(ULTRA_CRUCIBLE))),
+                ]
+            }
+        })
+    }
+}
+
+fn timed<T: ToString>(solve: impl FnOnce() -> T) -> Timing {
+    let start = Instant::now();
+    let answer = solve().to_string();
+    Timing {
+        answer,
+        time: start.elapsed(),
+    }
+}
+
+fn parse_platform(input: &str) -> Result<Platform, String> {
+    input
+        .trim_end()
+        .parse()
+        .map_err(|_| "failed to parse platform".to_string())
+}
+
+fn parse_contraption(input: &str) -> Result<Contraption, String> {
+    input
+        .trim_end()
+        .parse()
+        .map_err(|_| "failed to parse contraption".to_string())
+}
+
+fn parse_heat_map(input: &str) -> Result<HeatMap, String> {
+    input
+        .trim_end()
+        .parse()
+        .map_err(|_| "failed to parse heat map".to_string())
+}
+
+fn animate_platform(platform: &Platform, part: Part) -> Result<Animation, String> {
+    let frame =
+        |platform: &Platform, in_loop: bool| day_14::render::platform_frame(platform, in_loop);
+    let palette = day_14::render::palette();
+    if part == Part::One {
+        let mut tilted = platform.clone();
+        tilted.tilt(day_14::Direction::North);
+        return Ok(Animation {
+            frames: vec![frame(platform, false), frame(&tilted, false)],
+            palette,
+            summary: format!("North load: {}", tilted.north_load()),
+        });
+    }
+
+    // The same tilts as day 14's --animate, coloring the platform once it's in the loop
+    let (states, loop_start) = find_cycle(platform.clone(), |platform| {
+        let mut platform = platform.clone();
+        platform.spin_cycle();
+        platform
+    });
+    let mut frames = vec![frame(platform, loop_start == 0)];
+    for (cycle, state) in states.iter().enumerate() {
+        let mut platform = state.clone();
+        for (i, direction) in SPIN_CYCLE.into_iter().enumerate() {
+            platform.tilt(direction);
+            let finished = cycle + usize::from(i + 1 == SPIN_CYCLE.len());
+            frames.push(frame(&platform, finished >= loop_start));
+        }
+    }
+    Ok(Animation {
+        frames,
+        palette,
+        summary: format!(
+            "After {loop_start} spin cycles the platform repeats every {} cycles",
+            states.len() - loop_start
+        ),
+    })
+}
+
+fn animate_beam(contraption: &Contraption, part: Part) -> Result<Animation, String> {
+    let (start, direction) = match part {
+        Part::One => ((0, 0), day_16::Direction::Right),
+        Part::Two => BeamCache::new(contraption)
+            .best_entry_point()
+            .ok_or("the contraption has no entry points")?,
+    };
+    let mut recording = Recording::new();
+    day_16::render::play_beam(contraption, start, direction, &mut recording)
+        .map_err(|err| err.to_string())?;
+    Ok(Animation {
+        frames: recording.into_frames(),
+        palette: day_16::render::live_palette(),
+        summary: format!(
+            "Entering at {start:?} heading {direction:?} energizes {} tiles",
+            day_16::energized_tiles(contraption, start, direction)
+        ),
+    })
+}
+
+fn animate_path(heat_map: &HeatMap, part: Part) -> Result<Animation, String> {
+    let rules = match part {
+        Part::One => CRUCIBLE,
+        Part::Two => ULTRA_CRUCIBLE,
+    };
+    let (heat_loss, path) = heat_map
+        .min_heat_loss_path(rules)
+        .ok_or("there's no path to the factory")?;
+    // The path growing a block at a time
+    let frames = (0..=path.len())
+        .map(|length| day_17::render::path_frame(heat_map, &path[..length]))
+        .collect();
+    Ok(Animation {
+        frames,
+        palette: day_17::render::palette(),
+        summary: format!("Heat loss: {heat_loss}"),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PLATFORM: &str = "O....#....\n\
+                            O.OO#....#\n\
+                            .....##...\n\
+                            OO.#O....O\n\
+                            .O.....O#.\n\
+                            O.#..O.#.#\n\
+                            ..O..#O..O\n\
+                            .......O..\n\
+                            #....###..\n\
+                            #OO..#....\n";
+
+    #[test]
+    fn platform_frames() {
+        let tilted = Day::ReflectorDish.animate(PLATFORM, Part::One).unwrap();
+        assert_eq!(tilted.frames.len(), 2);
+        assert_eq!(tilted.summary, "North load: 136");
+
+        // The example starts looping after 3 spin cycles, and goes round every 7
+        let cycles = Day::ReflectorDish.animate(PLATFORM, Part::Two).unwrap();
+        assert_eq!(cycles.frames.len(), 1 + 4 * 10);
+        assert!(cycles.summary.starts_with("After 3 spin cycles"));
+
+        let timings = Day::ReflectorDish.solve(PLATFORM).unwrap();
+        assert_eq!(timings[0].answer, "136");
+        assert_eq!(timings[1].answer, "64");
+    }
+
+    #[test]
+    fn beam_and_path_frames() {
+        let contraption = ".\\\n.|";
+        let beam = Day::LavaFloor.animate(contraption, Part::One).unwrap();
+        assert_eq!(beam.frames.len(), 3);
+        assert!(beam.summary.ends_with("energizes 3 tiles"));
+
+        let path = Day::ClumsyCrucible.animate("123\n456", Part::One).unwrap();
+        assert_eq!(path.frames.len(), 5);
+        assert_eq!(path.summary, "Heat loss: 11");
+        assert!(Day::ClumsyCrucible.animate("123\n45", Part::One).is_err());
+    }
+}
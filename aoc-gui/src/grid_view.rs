@@ -0,0 +1,98 @@
+use aoc_viz::{Frame, Palette};
+use eframe::egui::{self, Color32, ColorImage, Rect, Scene, TextureHandle, TextureOptions, Ui};
+
+/// How many points across each cell is drawn before any zooming
+const CELL_POINTS: f32 = 4.0;
+
+/// Drawn for cells whose color isn't in the palette, so they stand out
+const MISSING_COLOR: Color32 = Color32::from_rgb(255, 0, 255);
+
+/// Shows a [Frame] that can be zoomed with the scroll wheel and panned by dragging
+pub struct GridView {
+    texture: Option<TextureHandle>,
+    /// The frame the texture was last made from, to only upload it again when it changes
+    shown: Option<Frame>,
+    /// The part of the frame in view, kept between repaints
+    scene_rect: Rect,
+}
+
+impl GridView {
+    pub fn new() -> Self {
+        Self {
+            texture: None,
+            shown: None,
+            scene_rect: Rect::ZERO,
+        }
+    }
+
+    /// Zooms back out to fit the whole frame in view
+    pub fn reset_view(&mut self) {
+        self.scene_rect = Rect::ZERO;
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, frame: &Frame, palette: &Palette) {
+        if self.shown.as_ref() != Some(frame) {
+            let image = frame_image(frame, palette);
+            match &mut self.texture {
+                Some(texture) => texture.set(image, TextureOptions::NEAREST),
+                None => {
+                    self.texture = Some(ui.ctx().load_texture(
+                        "grid",
+                        image,
+                        TextureOptions::NEAREST,
+                    ))
+                }
+            }
+            self.shown = Some(frame.clone());
+        }
+        let Some(texture) = &self.texture else {
+            return;
+        };
+
+        let size = egui::vec2(frame.width() as f32, frame.height() as f32) * CELL_POINTS;
+        Scene::new()
+            .zoom_range(0.1..=40.0)
+            .show(ui, &mut self.scene_rect, |ui| {
+                ui.add(egui::Image::new(texture).fit_to_exact_size(size));
+            });
+    }
+}
+
+/// Turns each cell of `frame` into a pixel of its palette color
+fn frame_image(frame: &Frame, palette: &Palette) -> ColorImage {
+    let pixels = frame
+        .rows()
+        .flatten()
+        .map(|&index| {
+            palette.get(index).map_or(MISSING_COLOR, |color| {
+                Color32::from_rgb(color.red, color.green, color.blue)
+            })
+        })
+        .collect();
+    ColorImage::new([frame.width(), frame.height()], pixels)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc_viz::Color;
+
+    #[test]
+    fn cells_to_pixels() {
+        let palette = Palette::new([Color::BLACK, Color::rgb(10, 20, 30)]);
+        let frame = Frame::from_fn(3, 2, |(row, col)| (row * 3 + col) as u8 % 3);
+        let image = frame_image(&frame, &palette);
+        assert_eq!(image.size, [3, 2]);
+        assert_eq!(
+            image.pixels,
+            vec![
+                Color32::BLACK,
+                Color32::from_rgb(10, 20, 30),
+                MISSING_COLOR,
+                Color32::BLACK,
+                Color32::from_rgb(10, 20, 30),
+                MISSING_COLOR,
+            ]
+        );
+    }
+}
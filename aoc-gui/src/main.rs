@@ -0,0 +1,19 @@
+//! A desktop viewer for the days' visualizations: loads a day's input, works out its frames and
+//! lets them be stepped through, zoomed and panned, and compares how long each day's solvers
+//! take.
+
+mod app;
+mod days;
+mod grid_view;
+
+fn main() -> eframe::Result {
+    let options = eframe::NativeOptions {
+        viewport: eframe::egui::ViewportBuilder::default().with_inner_size([1100.0, 800.0]),
+        ..Default::default()
+    };
+    eframe::run_native(
+        "Advent of Code 2023",
+        options,
+        Box::new(|_| Ok(Box::new(app::App::new()))),
+    )
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+/// A 24-bit color
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+
+    pub const fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+
+    /// The color `t` of the way from `self` to `other`, with `t` from 0 to 1
+    pub fn lerp(self, other: Color, t: f64) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * t).round() as u8;
+        Color::rgb(
+            mix(self.red, other.red),
+            mix(self.green, other.green),
+            mix(self.blue, other.blue),
+        )
+    }
+}
+
+impl From<u32> for Color {
+    /// Reads a color written like `0xF76806`
+    fn from(value: u32) -> Self {
+        let r = (value & 0x00FF0000) >> 16;
+        let g = (value & 0x0000FF00) >> 8;
+        let b = value & 0x000000FF;
+
+        Self {
+            red: r as u8,
+            green: g as u8,
+            blue: b as u8,
+        }
+    }
+}
+
+/// The colors a [crate::Frame]'s cells refer to by index. A frame only says what each cell is,
+/// and the palette decides how that looks, so the same frame can be drawn by any renderer.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Palette {
+    colors: Vec<Color>,
+}
+
+impl Palette {
+    /// A palette with `colors` at indices 0, 1, 2 and so on. Frames can only refer to the first
+    /// 256 colors.
+    pub fn new(colors: impl IntoIterator<Item = Color>) -> Self {
+        Self {
+            colors: colors.into_iter().collect(),
+        }
+    }
+
+    /// `steps` colors evenly spread from `from` to `to`, for shading by how much of something
+    /// there is
+    pub fn gradient(from: Color, to: Color, steps: usize) -> Self {
+        let last = steps.saturating_sub(1).max(1) as f64;
+        Self::new((0..steps).map(|step| from.lerp(to, step as f64 / last)))
+    }
+
+    /// Adds `color` after the ones already there, returning the index it can be used with
+    pub fn push(&mut self, color: Color) -> u8 {
+        self.colors.push(color);
+        (self.colors.len() - 1) as u8
+    }
+
+    /// Adds `colors` after the ones already there, returning the index each one can be used with,
+    /// in the same order. If there are more different colors than the palette has room for, low
+    /// bits are dropped from each channel until they fit, and each group of colors that end up
+    /// the same is drawn as their average. Returns `None` if the palette is already full.
+    pub fn push_quantized(&mut self, colors: &[Color]) -> Option<Vec<u8>> {
+        let room = 256usize.saturating_sub(self.colors.len());
+        if room == 0 {
+            return None;
+        }
+        for bits in (0..=8).rev() {
+            let key = |color: &Color| {
+                [color.red, color.green, color.blue].map(|channel| u32::from(channel) >> (8 - bits))
+            };
+            // Which group each color falls in, with the groups in the order they first come up
+            let mut groups = HashMap::new();
+            let mut sums: Vec<([u32; 3], u32)> = vec![];
+            let group_of: Vec<usize> = colors
+                .iter()
+                .map(|color| {
+                    let group = *groups.entry(key(color)).or_insert_with(|| {
+                        sums.push(([0; 3], 0));
+                        sums.len() - 1
+                    });
+                    let (sum, count) = &mut sums[group];
+                    for (total, channel) in sum.iter_mut().zip([color.red, color.green, color.blue])
+                    {
+                        *total += u32::from(channel);
+                    }
+                    *count += 1;
+                    group
+                })
+                .collect();
+            if sums.len() > room {
+                continue;
+            }
+
+            let first = self.colors.len();
+            for ([red, green, blue], count) in sums {
+                let average = |total: u32| ((total + count / 2) / count) as u8;
+                self.colors
+                    .push(Color::rgb(average(red), average(green), average(blue)));
+            }
+            return Some(
+                group_of
+                    .into_iter()
+                    .map(|group| (first + group) as u8)
+                    .collect(),
+            );
+        }
+        unreachable!("dropping every bit leaves a single color, which there's room for")
+    }
+
+    pub fn get(&self, index: u8) -> Option<Color> {
+        self.colors.get(index as usize).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hex_colors() {
+        assert_eq!(Color::from(0xF76806), Color::rgb(0xF7, 0x68, 0x06));
+        assert_eq!(Color::from(0xFFFFFF), Color::WHITE);
+    }
+
+    #[test]
+    fn gradient() {
+        let palette = Palette::gradient(Color::BLACK, Color::rgb(200, 100, 0), 5);
+        assert_eq!(palette.len(), 5);
+        assert_eq!(palette.get(0), Some(Color::BLACK));
+        assert_eq!(palette.get(2), Some(Color::rgb(100, 50, 0)));
+        assert_eq!(palette.get(4), Some(Color::rgb(200, 100, 0)));
+        assert_eq!(palette.get(5), None);
+
+        assert_eq!(
+            Palette::gradient(Color::BLACK, Color::WHITE, 1),
+            Palette::new([Color::BLACK])
+        );
+    }
+
+    #[test]
+    fn quantized_colors() {
+        let mut palette = Palette::new([Color::BLACK]);
+        let colors = [Color::rgb(1, 2, 3), Color::WHITE, Color::rgb(1, 2, 3)];
+        assert_eq!(palette.push_quantized(&colors), Some(vec![1, 2, 1]));
+        assert_eq!(palette.len(), 3);
+        assert_eq!(palette.get(1), Some(Color::rgb(1, 2, 3)));
+
+        // 300 reds don't fit in the 254 places left, so neighbouring ones are merged
+        let reds: Vec<Color> = (0..300)
+            .map(|i| Color::rgb((i % 256) as u8, 0, 0))
+            .collect();
+        let indices = palette.push_quantized(&reds).unwrap();
+        assert_eq!(palette.len(), 3 + 128);
+        assert_eq!(indices[0], indices[1]);
+        assert_ne!(indices[1], indices[2]);
+        assert_eq!(indices[256], indices[0]);
+        assert_eq!(palette.get(indices[0]), Some(Color::rgb(1, 0, 0)));
+
+        let mut full = Palette::gradient(Color::BLACK, Color::WHITE, 256);
+        assert_eq!(full.push_quantized(&colors), None);
+        assert_eq!(full.push_quantized(&[]), None);
+    }
+}
@@ -0,0 +1,114 @@
+/// A position in a [Frame], as `(row, col)` counting from the top left. That's the same way the
+/// days' grids number their tiles, so their positions can be used as they are.
+pub type Point = (usize, usize);
+
+/// One picture to draw: a rectangle of cells, each holding the index of its color in the
+/// renderer's [crate::Palette]. Stored row by row in one flat list.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+}
+
+impl Frame {
+    /// A `width` by `height` frame with every cell set to palette index `fill`
+    pub fn new(width: usize, height: usize, fill: u8) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// A `width` by `height` frame where `cell` picks the palette index at each point, so a grid
+    /// can be turned into a frame without copying it into one by hand
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(Point) -> u8) -> Self {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(&mut cell)
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (row, col): Point) -> Option<u8> {
+        (row < self.height && col < self.width).then(|| self.cells[row * self.width + col])
+    }
+
+    /// Sets the cell at `point`, ignoring points outside the frame so overlays don't have to
+    /// check
+    pub fn set(&mut self, (row, col): Point, index: u8) {
+        if row < self.height && col < self.width {
+            self.cells[row * self.width + col] = index;
+        }
+    }
+
+    /// The cells one row at a time, top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        // A frame with no columns still has its rows, they're just empty
+        (0..self.height).map(|row| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    /// The frame `factor` times smaller each way, rounding up, with each cell taking the highest
+    /// palette index in the block of cells it covers. Palettes with their most important colors
+    /// last keep those showing, however thin they are.
+    pub fn shrink(&self, factor: usize) -> Frame {
+        let factor = factor.max(1);
+        let mut shrunk = Frame::new(self.width.div_ceil(factor), self.height.div_ceil(factor), 0);
+        for (row, cells) in self.rows().enumerate() {
+            for (col, &index) in cells.iter().enumerate() {
+                let cell = &mut shrunk.cells[row / factor * shrunk.width + col / factor];
+                *cell = (*cell).max(index);
+            }
+        }
+        shrunk
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn build_frames() {
+        let frame = Frame::from_fn(3, 2, |(row, col)| (row * 3 + col) as u8);
+        assert_eq!(frame.get((1, 2)), Some(5));
+        assert_eq!(frame.get((2, 0)), None);
+        assert_eq!(
+            frame.rows().collect::<Vec<_>>(),
+            vec![&[0, 1, 2], &[3, 4, 5]]
+        );
+
+        let mut frame = Frame::new(2, 2, 7);
+        frame.set((0, 1), 1);
+        frame.set((5, 5), 1);
+        assert_eq!(
+            frame,
+            Frame::from_fn(2, 2, |pos| if pos == (0, 1) { 1 } else { 7 })
+        );
+    }
+
+    #[test]
+    fn shrink_frames() {
+        let frame = Frame::from_fn(5, 3, |(row, col)| u8::from(row == 1 && col == 3) * 4);
+        let shrunk = frame.shrink(2);
+        assert_eq!(
+            shrunk.rows().collect::<Vec<_>>(),
+            vec![&[0, 4, 0], &[0, 0, 0]]
+        );
+        assert_eq!(frame.shrink(1), frame);
+        assert_eq!(frame.shrink(10).rows().collect::<Vec<_>>(), vec![&[4]]);
+    }
+}
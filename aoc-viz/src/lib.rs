@@ -0,0 +1,100 @@
+//! Drawing grids for the days' visualizations. A day turns whatever it wants to show into a
+//! [Frame] of palette indices, and a [Renderer] draws it, either in the terminal or as images.
+//! The [Player] plays frames in the terminal as they come, with keys to pause and step through,
+//! and [Video] encodes them into a clip with ffmpeg.
+
+mod color;
+mod frame;
+#[cfg(feature = "player")]
+mod player;
+#[cfg(feature = "png")]
+mod png;
+mod recording;
+mod terminal;
+mod video;
+
+pub use color::{Color, Palette};
+pub use frame::{Frame, Point};
+#[cfg(feature = "player")]
+pub use player::Player;
+#[cfg(feature = "png")]
+pub use png::Png;
+pub use recording::Recording;
+use std::fmt::{Display, Formatter};
+use std::io;
+pub use terminal::Terminal;
+pub use video::Video;
+
+#[derive(Debug)]
+pub enum RenderError {
+    /// A cell refers to a color the palette doesn't have
+    MissingColor(u8),
+    /// A frame isn't the same width and height as the ones before, which a video can't change
+    SizeChanged {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    Io(io::Error),
+    #[cfg(feature = "png")]
+    Image(image::ImageError),
+}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::MissingColor(index) => write!(f, "no color {index} in the palette"),
+            RenderError::SizeChanged { expected, found } => write!(
+                f,
+                "the frame is {}x{} but the ones before were {}x{}",
+                found.0, found.1, expected.0, expected.1
+            ),
+            RenderError::Io(err) => write!(f, "failed to write the frame: {err}"),
+            #[cfg(feature = "png")]
+            RenderError::Image(err) => write!(f, "failed to write the image: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+impl From<io::Error> for RenderError {
+    fn from(value: io::Error) -> Self {
+        RenderError::Io(value)
+    }
+}
+
+#[cfg(feature = "png")]
+impl From<image::ImageError> for RenderError {
+    fn from(value: image::ImageError) -> Self {
+        RenderError::Image(value)
+    }
+}
+
+/// Something frames can be drawn to. Animations draw one frame after another to the same
+/// renderer.
+pub trait Renderer {
+    fn render(&mut self, frame: &Frame) -> Result<(), RenderError>;
+
+    /// Whether there's no point drawing any more frames, like when someone watching a [Player]
+    /// quits it. Animations should check this to stop working out frames no one will see.
+    fn is_stopped(&self) -> bool {
+        false
+    }
+
+    /// Draws every frame in `frames` in order
+    fn render_all<'a>(
+        &mut self,
+        frames: impl IntoIterator<Item = &'a Frame>,
+    ) -> Result<(), RenderError>
+    where
+        Self: Sized,
+    {
+        for frame in frames {
+            if self.is_stopped() {
+                break;
+            }
+            self.render(frame)?;
+        }
+        Ok(())
+    }
+}
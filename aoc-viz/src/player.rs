@@ -0,0 +1,363 @@
+use crate::terminal::push_cells;
+use crate::{Frame, Palette, RenderError, Renderer};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use std::io::{self, IsTerminal, Stdout, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Plays frames in a terminal that understands 24-bit ANSI colors, holding each one up for a set
+/// time. Only the cells that changed since the last frame are redrawn, so big grids that change
+/// a little at a time still play smoothly.
+///
+/// When it's interactive, keys control the playback while it waits between frames: space pauses
+/// and resumes, the right arrow or `.` steps one frame, `+` and `-` change the speed and `q` or
+/// escape quit, after which [Renderer::is_stopped] is true and frames are ignored.
+pub struct Player<W: Write> {
+    out: W,
+    palette: Palette,
+    /// How many characters wide each cell is drawn
+    cell_width: usize,
+    /// How long each frame stays up
+    frame_time: Duration,
+    /// The most columns and rows of cells that fit, with bigger frames shrunk down to fit
+    max_size: Option<(usize, usize)>,
+    interactive: bool,
+    /// Whether the terminal has been put into raw mode to read single keys, and has to be put
+    /// back when the player's done
+    raw_mode: bool,
+    paused: bool,
+    stopped: bool,
+    frames_shown: usize,
+    /// The last frame drawn, as it was drawn after any shrinking, to compare the next one with
+    previous: Option<Frame>,
+}
+
+impl Player<Stdout> {
+    /// A player drawing to stdout, shrinking frames to fit the terminal. It's interactive if
+    /// both stdin and stdout are terminals.
+    pub fn stdout(palette: Palette) -> Self {
+        let interactive = io::stdin().is_terminal() && io::stdout().is_terminal();
+        let mut player = Self::new(io::stdout(), palette);
+        player.interactive = interactive;
+        if let Ok((columns, rows)) = terminal::size() {
+            // Leaving a line for the status under the frame
+            player.max_size = Some((usize::from(columns), usize::from(rows).saturating_sub(1)));
+        }
+        player
+    }
+}
+
+impl<W: Write> Player<W> {
+    /// A player writing to `out` at 10 frames a second, without reading keys or shrinking frames
+    pub fn new(out: W, palette: Palette) -> Self {
+        Self {
+            out,
+            palette,
+            cell_width: 2,
+            frame_time: Duration::from_millis(100),
+            max_size: None,
+            interactive: false,
+            raw_mode: false,
+            paused: false,
+            stopped: false,
+            frames_shown: 0,
+            previous: None,
+        }
+    }
+
+    /// Draws each cell `cell_width` characters wide instead
+    pub fn cell_width(mut self, cell_width: usize) -> Self {
+        self.cell_width = cell_width.max(1);
+        self
+    }
+
+    /// Plays `fps` frames a second instead, or as fast as they come if it's 0
+    pub fn fps(mut self, fps: f64) -> Self {
+        self.frame_time = if fps > 0.0 {
+            Duration::from_secs_f64(1.0 / fps)
+        } else {
+            Duration::ZERO
+        };
+        self
+    }
+
+    /// Starts out paused, so the first frame waits for a key
+    pub fn paused(mut self) -> Self {
+        self.paused = true;
+        self
+    }
+
+    /// How many times smaller `frame` has to be drawn to fit in the terminal
+    fn shrink_factor(&self, frame: &Frame) -> usize {
+        let Some((columns, rows)) = self.max_size else {
+            return 1;
+        };
+        let across = (frame.width() * self.cell_width).div_ceil(columns.max(1));
+        let down = frame.height().div_ceil(rows.max(1));
+        across.max(down).max(1)
+    }
+
+    /// Draws `frame` over the last one, only touching the cells that changed. The cursor is left
+    /// at the start of the line under the frame.
+    fn draw(&mut self, frame: Frame) -> Result<(), RenderError> {
+        let mut text = String::new();
+        match &self.previous {
+            Some(previous)
+                if previous.width() == frame.width() && previous.height() == frame.height() =>
+            {
+                if *previous != frame {
+                    self.push_changes(&mut text, previous, &frame)?;
+                }
+            }
+            previous => {
+                // A different size, so the last frame is cleared away and this one drawn whole
+                if let Some(previous) = previous.as_ref().filter(|previous| previous.height() > 0) {
+                    text.push_str(&format!("\x1b[{}F\x1b[J", previous.height()));
+                }
+                for row in frame.rows() {
+                    push_cells(&mut text, &self.palette, row, &[], self.cell_width)?;
+                    // Raw mode doesn't go back to the start of the line by itself
+                    text.push_str("\x1b[0m\r\n");
+                }
+            }
+        }
+        self.out.write_all(text.as_bytes())?;
+        self.previous = Some(frame);
+        self.frames_shown += 1;
+        self.draw_status()
+    }
+
+    /// Adds the cells of `frame` that aren't the same in `previous` to `text`, going up to the
+    /// top line and then down to each run of changed cells in turn
+    fn push_changes(
+        &self,
+        text: &mut String,
+        previous: &Frame,
+        frame: &Frame,
+    ) -> Result<(), RenderError> {
+        text.push_str(&format!("\x1b[{}F", frame.height()));
+        let mut cursor_row = 0;
+        for (row, (cells, before)) in frame.rows().zip(previous.rows()).enumerate() {
+            let mut col = 0;
+            while col < cells.len() {
+                if cells[col] == before[col] {
+                    col += 1;
+                    continue;
+                }
+                let end = (col..cells.len())
+                    .find(|&end| cells[end] == before[end])
+                    .unwrap_or(cells.len());
+                if row > cursor_row {
+                    text.push_str(&format!("\x1b[{}E", row - cursor_row));
+                    cursor_row = row;
+                }
+                text.push_str(&format!("\x1b[{}G", col * self.cell_width + 1));
+                push_cells(text, &self.palette, &cells[col..end], &[], self.cell_width)?;
+                text.push_str("\x1b[0m");
+                col = end;
+            }
+        }
+        text.push_str(&format!("\x1b[{}E", frame.height() - cursor_row));
+        Ok(())
+    }
+
+    /// Rewrites the line under the frame with where the playback is and the keys to control it
+    fn draw_status(&mut self) -> Result<(), RenderError> {
+        if self.interactive {
+            let state = if self.paused {
+                "paused | space resume".to_string()
+            } else if self.frame_time.is_zero() {
+                "full speed | space pause".to_string()
+            } else {
+                format!(
+                    "{:.1} fps | space pause",
+                    1.0 / self.frame_time.as_secs_f64()
+                )
+            };
+            write!(
+                self.out,
+                "\x1b[2Kframe {} | {state} | -> step | +/- speed | q quit\r",
+                self.frames_shown
+            )?;
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+
+    /// Acts on a key, returning whether to move on to the next frame straight away
+    fn press(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char(' ') => {
+                self.paused = !self.paused;
+                false
+            }
+            KeyCode::Right | KeyCode::Char('.') => true,
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.frame_time /= 2;
+                false
+            }
+            KeyCode::Char('-') => {
+                self.frame_time = (self.frame_time * 2).max(Duration::from_millis(10));
+                false
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.stopped = true;
+                true
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.stopped = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Holds the frame up for the frame time, or until a key says otherwise
+    fn wait(&mut self) -> Result<(), RenderError> {
+        if !self.interactive {
+            thread::sleep(self.frame_time);
+            return Ok(());
+        }
+        if !self.raw_mode {
+            terminal::enable_raw_mode()?;
+            self.raw_mode = true;
+        }
+
+        let deadline = Instant::now() + self.frame_time;
+        loop {
+            if !self.paused {
+                let left = deadline.saturating_duration_since(Instant::now());
+                if !event::poll(left)? {
+                    return Ok(());
+                }
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    let next = self.press(key);
+                    self.draw_status()?;
+                    if next {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<W: Write> Renderer for Player<W> {
+    fn render(&mut self, frame: &Frame) -> Result<(), RenderError> {
+        if self.stopped {
+            return Ok(());
+        }
+        self.draw(frame.shrink(self.shrink_factor(frame)))?;
+        self.wait()
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+}
+
+impl<W: Write> Drop for Player<W> {
+    /// Puts the terminal back the way it was and clears the status line
+    fn drop(&mut self) {
+        if self.raw_mode {
+            let _ = terminal::disable_raw_mode();
+        }
+        if self.interactive {
+            let _ = self.out.write_all(b"\x1b[2K");
+            let _ = self.out.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Color;
+
+    fn player(out: &mut Vec<u8>) -> Player<&mut Vec<u8>> {
+        let palette = Palette::new([Color::BLACK, Color::WHITE, Color::rgb(255, 0, 10)]);
+        Player::new(out, palette).cell_width(1).fps(0.0)
+    }
+
+    #[test]
+    fn only_changes_redrawn() {
+        let mut out = vec![];
+        let mut player = player(&mut out);
+        let mut frame = Frame::new(4, 3, 0);
+        player.render(&frame).unwrap();
+        // Nothing changes, so nothing's drawn
+        player.render(&frame).unwrap();
+        frame.set((1, 1), 1);
+        frame.set((1, 2), 2);
+        frame.set((2, 0), 1);
+        player.render(&frame).unwrap();
+        drop(player);
+
+        let black_row = "\x1b[48;2;0;0;0m    \x1b[0m\r\n";
+        let changes = "\x1b[3F\
+                       \x1b[1E\x1b[2G\x1b[48;2;255;255;255m \x1b[48;2;255;0;10m \x1b[0m\
+                       \x1b[1E\x1b[1G\x1b[48;2;255;255;255m \x1b[0m\
+                       \x1b[1E";
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!("{black_row}{black_row}{black_row}{changes}")
+        );
+    }
+
+    #[test]
+    fn resized_frames_redrawn() {
+        let mut out = vec![];
+        let mut player = player(&mut out);
+        player.render(&Frame::new(1, 2, 0)).unwrap();
+        player.render(&Frame::new(2, 1, 1)).unwrap();
+        drop(player);
+
+        let first = "\x1b[48;2;0;0;0m \x1b[0m\r\n";
+        let second = "\x1b[48;2;255;255;255m  \x1b[0m\r\n";
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!("{first}{first}\x1b[2F\x1b[J{second}")
+        );
+    }
+
+    #[test]
+    fn shrunk_to_fit() {
+        let mut out = vec![];
+        let mut player = player(&mut out).cell_width(2);
+        player.max_size = Some((10, 4));
+        // Twice too wide with two characters a cell, and a bit too tall
+        let frame = Frame::from_fn(10, 6, |pos| u8::from(pos == (5, 9)));
+        assert_eq!(player.shrink_factor(&frame), 2);
+        player.render(&frame).unwrap();
+        assert_eq!(player.previous, Some(frame.shrink(2)));
+    }
+
+    #[test]
+    fn keys() {
+        let mut out = vec![];
+        let mut player = player(&mut out).fps(10.0);
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        assert!(!player.press(key(KeyCode::Char(' '))));
+        assert!(player.paused);
+        assert!(player.press(key(KeyCode::Right)));
+        assert!(player.paused);
+        assert!(!player.press(key(KeyCode::Char(' '))));
+        assert!(!player.paused);
+
+        player.press(key(KeyCode::Char('+')));
+        assert_eq!(player.frame_time, Duration::from_millis(50));
+        player.press(key(KeyCode::Char('-')));
+        player.press(key(KeyCode::Char('-')));
+        assert_eq!(player.frame_time, Duration::from_millis(200));
+
+        assert!(player.press(key(KeyCode::Char('q'))));
+        assert!(player.is_stopped());
+        // Once it's stopped, frames aren't drawn
+        player.render(&Frame::new(1, 1, 0)).unwrap();
+        assert_eq!(player.frames_shown, 0);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{Frame, Palette, RenderError, Renderer};
+use image::{ImageBuffer, Rgb, RgbImage};
+use std::fs;
+use std::path::PathBuf;
+
+enum Target {
+    /// Every frame is written to the same file, so it ends up holding the last one
+    Single(PathBuf),
+    /// Every frame is written to its own numbered file in a directory
+    Sequence(PathBuf),
+}
+
+/// Draws frames as PNG images, with each cell a square of `scale` by `scale` pixels
+pub struct Png {
+    target: Target,
+    palette: Palette,
+    scale: u32,
+    frames_written: usize,
+}
+
+impl Png {
+    /// Writes frames to the image at `path`
+    pub fn single(path: impl Into<PathBuf>, palette: Palette) -> Self {
+        Self::with_target(Target::Single(path.into()), palette)
+    }
+
+    /// Writes each frame to `frame_00000.png`, `frame_00001.png` and so on in `dir`, creating it
+    /// if it isn't there
+    pub fn sequence(dir: impl Into<PathBuf>, palette: Palette) -> Self {
+        Self::with_target(Target::Sequence(dir.into()), palette)
+    }
+
+    fn with_target(target: Target, palette: Palette) -> Self {
+        Self {
+            target,
+            palette,
+            scale: 1,
+            frames_written: 0,
+        }
+    }
+
+    /// Draws each cell `scale` pixels across instead
+    pub fn scale(mut self, scale: u32) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Turns `frame` into an image without saving it
+    pub fn draw(&self, frame: &Frame) -> Result<RgbImage, RenderError> {
+        let mut img: RgbImage = ImageBuffer::new(
+            frame.width() as u32 * self.scale,
+            frame.height() as u32 * self.scale,
+        );
+        for (row, cells) in frame.rows().enumerate() {
+            for (col, &index) in cells.iter().enumerate() {
+                let color = self
+                    .palette
+                    .get(index)
+                    .ok_or(RenderError::MissingColor(index))?;
+                let pixel = Rgb([color.red, color.green, color.blue]);
+                for y in 0..self.scale {
+                    for x in 0..self.scale {
+                        let x = col as u32 * self.scale + x;
+                        let y = row as u32 * self.scale + y;
+                        img.put_pixel(x, y, pixel);
+                    }
+                }
+            }
+        }
+        Ok(img)
+    }
+}
+
+impl Renderer for Png {
+    fn render(&mut self, frame: &Frame) -> Result<(), RenderError> {
+        let img = self.draw(frame)?;
+        let path = match &self.target {
+            Target::Single(path) => path.clone(),
+            Target::Sequence(dir) => {
+                fs::create_dir_all(dir)?;
+                dir.join(format!("frame_{:05}.png", self.frames_written))
+            }
+        };
+        img.save(path)?;
+        self.frames_written += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Color;
+    use std::env;
+
+    #[test]
+    fn scaled_image() {
+        let palette = Palette::new([Color::WHITE, Color::from(0xF76806)]);
+        let png = Png::single("unused.png", palette).scale(3);
+        let img = png
+            .draw(&Frame::from_fn(2, 1, |(_, col)| col as u8))
+            .unwrap();
+        assert_eq!(img.dimensions(), (6, 3));
+        assert_eq!(img.get_pixel(2, 2), &Rgb([255, 255, 255]));
+        assert_eq!(img.get_pixel(3, 0), &Rgb([0xF7, 0x68, 0x06]));
+    }
+
+    #[test]
+    fn numbered_frames() {
+        let dir = env::temp_dir().join(format!("aoc-viz-test-{}", std::process::id()));
+        let palette = Palette::new([Color::BLACK, Color::WHITE]);
+        let mut png = Png::sequence(&dir, palette);
+        png.render(&Frame::new(2, 2, 0)).unwrap();
+        png.render(&Frame::new(2, 2, 1)).unwrap();
+
+        let second = image::open(dir.join("frame_00001.png")).unwrap().to_rgb8();
+        assert!(dir.join("frame_00000.png").exists());
+        assert_eq!(second.get_pixel(1, 1), &Rgb([255, 255, 255]));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{Frame, RenderError, Renderer};
+
+/// Keeps every frame it's given, for going back over an animation after it's been worked out,
+/// like stepping through it in a viewer or checking it in a test
+#[derive(Debug, Default, Clone)]
+pub struct Recording {
+    frames: Vec<Frame>,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn into_frames(self) -> Vec<Frame> {
+        self.frames
+    }
+}
+
+impl Renderer for Recording {
+    fn render(&mut self, frame: &Frame) -> Result<(), RenderError> {
+        self.frames.push(frame.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn record_frames() {
+        let frames = [Frame::new(1, 1, 0), Frame::new(2, 1, 1)];
+        let mut recording = Recording::new();
+        recording.render_all(&frames).unwrap();
+        assert_eq!(recording.frames(), &frames);
+        assert_eq!(recording.into_frames(), frames.to_vec());
+    }
+}
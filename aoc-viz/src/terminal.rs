@@ -0,0 +1,143 @@
+use crate::{Frame, Palette, RenderError, Renderer};
+use std::io::Write;
+
+/// Draws frames as blocks of background color in a terminal that understands 24-bit ANSI colors.
+/// Every frame after the first is drawn over the one before, so a run of frames plays as an
+/// animation.
+pub struct Terminal<W: Write> {
+    out: W,
+    palette: Palette,
+    /// How many characters wide each cell is drawn. Characters are about twice as tall as they
+    /// are wide, so 2 keeps cells roughly square.
+    cell_width: usize,
+    /// How many lines the last frame took up, to move back over before drawing the next one
+    drawn_rows: usize,
+    /// Characters drawn over the cells, one line per row
+    text: Vec<Vec<char>>,
+}
+
+impl<W: Write> Terminal<W> {
+    pub fn new(out: W, palette: Palette) -> Self {
+        Self {
+            out,
+            palette,
+            cell_width: 2,
+            drawn_rows: 0,
+            text: vec![],
+        }
+    }
+
+    /// Draws each cell `cell_width` characters wide instead
+    pub fn cell_width(mut self, cell_width: usize) -> Self {
+        self.cell_width = cell_width.max(1);
+        self
+    }
+
+    /// Writes `text` over the cells, a character at the start of each cell, so a frame made from
+    /// a puzzle input can still show what the input said. Cells past the end of the text are
+    /// left blank.
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.lines().map(|line| line.chars().collect()).collect();
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> Renderer for Terminal<W> {
+    fn render(&mut self, frame: &Frame) -> Result<(), RenderError> {
+        let mut text = String::new();
+        if self.drawn_rows > 0 {
+            // Back to the start of the line the last frame started on
+            text.push_str(&format!("\x1b[{}F", self.drawn_rows));
+        }
+        for (row, cells) in frame.rows().enumerate() {
+            let glyphs = self.text.get(row).map_or(&[][..], Vec::as_slice);
+            push_cells(&mut text, &self.palette, cells, glyphs, self.cell_width)?;
+            text.push_str("\x1b[0m\n");
+        }
+
+        self.out.write_all(text.as_bytes())?;
+        self.out.flush()?;
+        self.drawn_rows = frame.height();
+        Ok(())
+    }
+}
+
+/// Adds `cells` to `text` as blocks of background color `cell_width` characters wide, only
+/// switching colors where they change. Each cell starts with its character from `glyphs`, if it
+/// has one.
+pub(crate) fn push_cells(
+    text: &mut String,
+    palette: &Palette,
+    cells: &[u8],
+    glyphs: &[char],
+    cell_width: usize,
+) -> Result<(), RenderError> {
+    let mut current = None;
+    for (col, &index) in cells.iter().enumerate() {
+        if current != Some(index) {
+            let color = palette.get(index).ok_or(RenderError::MissingColor(index))?;
+            text.push_str(&format!(
+                "\x1b[48;2;{};{};{}m",
+                color.red, color.green, color.blue
+            ));
+            current = Some(index);
+        }
+        match glyphs.get(col) {
+            Some(&glyph) => {
+                text.push(glyph);
+                text.extend(std::iter::repeat_n(' ', cell_width - 1));
+            }
+            None => text.extend(std::iter::repeat_n(' ', cell_width)),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Color;
+
+    #[test]
+    fn draw_frames() {
+        let palette = Palette::new([Color::BLACK, Color::rgb(255, 0, 10)]);
+        let mut terminal = Terminal::new(vec![], palette).cell_width(1);
+        let frame = Frame::from_fn(3, 2, |(row, col)| u8::from(row == 1 && col > 0));
+        terminal.render(&frame).unwrap();
+        terminal.render(&frame).unwrap();
+
+        let black_row = "\x1b[48;2;0;0;0m   \x1b[0m\n";
+        let mixed_row = "\x1b[48;2;0;0;0m \x1b[48;2;255;0;10m  \x1b[0m\n";
+        let drawn = format!("{black_row}{mixed_row}");
+        assert_eq!(
+            String::from_utf8(terminal.into_inner()).unwrap(),
+            format!("{drawn}\x1b[2F{drawn}")
+        );
+    }
+
+    #[test]
+    fn text_over_cells() {
+        let palette = Palette::new([Color::BLACK, Color::WHITE]);
+        let mut terminal = Terminal::new(vec![], palette).text("ab\nc");
+        terminal
+            .render(&Frame::from_fn(2, 2, |(_, col)| col as u8))
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(terminal.into_inner()).unwrap(),
+            "\x1b[48;2;0;0;0ma \x1b[48;2;255;255;255mb \x1b[0m\n\
+             \x1b[48;2;0;0;0mc \x1b[48;2;255;255;255m  \x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn missing_colors() {
+        let mut terminal = Terminal::new(vec![], Palette::new([Color::BLACK]));
+        let result = terminal.render(&Frame::new(1, 1, 3));
+        assert!(matches!(result, Err(RenderError::MissingColor(3))));
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{Frame, Palette, RenderError, Renderer};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+/// ffmpeg run to encode the frames
+struct Encoder {
+    child: Child,
+    stdin: ChildStdin,
+    /// The width and height in cells every frame has to be, since the video can't change size
+    size: (usize, usize),
+}
+
+/// Encodes frames into a video by piping them to `ffmpeg` as raw RGB pixels, with each cell a
+/// square of `scale` by `scale` pixels. ffmpeg works out the format from the file extension, so
+/// anything it can write works, like `.mp4`, `.webm` or `.gif`. It's started on the first frame,
+/// and every frame after has to be the same size.
+///
+/// Call [Video::finish] after the last frame to wait for ffmpeg to finish writing the file.
+pub struct Video {
+    path: PathBuf,
+    palette: Palette,
+    scale: u32,
+    fps: f64,
+    /// The width and height in pixels to fit the frames into, if not the frames' own size
+    resolution: Option<(u32, u32)>,
+    program: String,
+    encoder: Option<Encoder>,
+}
+
+impl Video {
+    /// Encodes frames into the video at `path`, at 30 frames a second
+    pub fn new(path: impl Into<PathBuf>, palette: Palette) -> Self {
+        Self {
+            path: path.into(),
+            palette,
+            scale: 1,
+            fps: 30.0,
+            resolution: None,
+            program: "ffmpeg".to_string(),
+            encoder: None,
+        }
+    }
+
+    /// Draws each cell `scale` pixels across instead
+    pub fn scale(mut self, scale: u32) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Plays `fps` frames a second instead
+    pub fn fps(mut self, fps: f64) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    /// Makes the video `width` by `height` pixels, scaling frames up or down to fit without
+    /// blurring the cells and filling in any space left around them with black
+    pub fn resolution(mut self, width: u32, height: u32) -> Self {
+        self.resolution = Some((width, height));
+        self
+    }
+
+    /// The arguments telling ffmpeg to read `width` by `height` pixel frames from stdin
+    fn args(&self, width: u32, height: u32) -> Vec<String> {
+        // Most players want yuv420p, which needs an even width and height
+        let filter = match self.resolution {
+            Some((width, height)) => {
+                let (width, height) = (width.next_multiple_of(2), height.next_multiple_of(2));
+                format!(
+                    "scale={width}:{height}:force_original_aspect_ratio=decrease:flags=neighbor,\
+                     pad={width}:{height}:(ow-iw)/2:(oh-ih)/2"
+                )
+            }
+            None => "pad=ceil(iw/2)*2:ceil(ih/2)*2".to_string(),
+        };
+        [
+            "-loglevel",
+            "error",
+            "-y",
+            "-f",
+            "rawvideo",
+            "-pixel_format",
+            "rgb24",
+            "-video_size",
+            &format!("{width}x{height}"),
+            "-framerate",
+            &self.fps.to_string(),
+            "-i",
+            "-",
+            "-vf",
+            &filter,
+            "-pix_fmt",
+            "yuv420p",
+        ]
+        .into_iter()
+        .map(String::from)
+        .chain([self.path.to_string_lossy().into_owned()])
+        .collect()
+    }
+
+    /// Turns `frame` into rows of RGB pixels, top to bottom
+    fn pixels(&self, frame: &Frame) -> Result<Vec<u8>, RenderError> {
+        let scale = self.scale as usize;
+        let mut pixels = Vec::with_capacity(frame.width() * frame.height() * scale * scale * 3);
+        for cells in frame.rows() {
+            let mut row = Vec::with_capacity(cells.len() * scale * 3);
+            for &index in cells {
+                let color = self
+                    .palette
+                    .get(index)
+                    .ok_or(RenderError::MissingColor(index))?;
+                for _ in 0..scale {
+                    row.extend([color.red, color.green, color.blue]);
+                }
+            }
+            for _ in 0..scale {
+                pixels.extend_from_slice(&row);
+            }
+        }
+        Ok(pixels)
+    }
+
+    fn start(&self, frame: &Frame) -> Result<Encoder, RenderError> {
+        let width = frame.width() as u32 * self.scale;
+        let height = frame.height() as u32 * self.scale;
+        let mut child = Command::new(&self.program)
+            .args(self.args(width, height))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .map_err(|err| {
+                io::Error::new(err.kind(), format!("couldn't run {}: {err}", self.program))
+            })?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        Ok(Encoder {
+            child,
+            stdin,
+            size: (frame.width(), frame.height()),
+        })
+    }
+
+    /// Waits for ffmpeg to finish writing the video, failing if it didn't manage to
+    pub fn finish(mut self) -> Result<(), RenderError> {
+        let Some(Encoder {
+            mut child, stdin, ..
+        }) = self.encoder.take()
+        else {
+            return Ok(());
+        };
+        // Closing its stdin is what tells ffmpeg there are no more frames
+        drop(stdin);
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(io::Error::other(format!("{} exited with {status}", self.program)).into());
+        }
+        Ok(())
+    }
+}
+
+impl Renderer for Video {
+    fn render(&mut self, frame: &Frame) -> Result<(), RenderError> {
+        let pixels = self.pixels(frame)?;
+        if self.encoder.is_none() {
+            self.encoder = Some(self.start(frame)?);
+        }
+        let encoder = self.encoder.as_mut().expect("the encoder was just started");
+        let size = (frame.width(), frame.height());
+        if size != encoder.size {
+            return Err(RenderError::SizeChanged {
+                expected: encoder.size,
+                found: size,
+            });
+        }
+        encoder.stdin.write_all(&pixels)?;
+        Ok(())
+    }
+}
+
+impl Drop for Video {
+    /// Lets ffmpeg finish off whatever it's been given if [Video::finish] wasn't called
+    fn drop(&mut self) {
+        if let Some(Encoder {
+            mut child, stdin, ..
+        }) = self.encoder.take()
+        {
+            drop(stdin);
+            let _ = child.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Color;
+
+    fn palette() -> Palette {
+        Palette::new([Color::BLACK, Color::rgb(1, 2, 3)])
+    }
+
+    #[test]
+    fn scaled_pixels() {
+        let video = Video::new("unused.mp4", palette()).scale(2);
+        let pixels = video
+            .pixels(&Frame::from_fn(2, 1, |(_, col)| col as u8))
+            .unwrap();
+        let row = [0, 0, 0, 0, 0, 0, 1, 2, 3, 1, 2, 3];
+        assert_eq!(pixels, [row, row].concat());
+        assert!(matches!(
+            video.pixels(&Frame::new(1, 1, 2)),
+            Err(RenderError::MissingColor(2))
+        ));
+    }
+
+    #[test]
+    fn ffmpeg_args() {
+        let video = Video::new("spin.mp4", palette()).fps(12.5);
+        let args = video.args(7, 5);
+        let after = |flag: &str| {
+            let i = args.iter().position(|arg| arg == flag).unwrap();
+            args[i + 1].as_str()
+        };
+        assert_eq!(after("-video_size"), "7x5");
+        assert_eq!(after("-framerate"), "12.5");
+        assert_eq!(after("-vf"), "pad=ceil(iw/2)*2:ceil(ih/2)*2");
+        assert_eq!(args.last().unwrap(), "spin.mp4");
+
+        let args = video.resolution(1279, 720).args(7, 5);
+        assert!(args.iter().any(|arg| arg.starts_with("scale=1280:720:")));
+    }
+
+    #[test]
+    fn missing_encoder() {
+        let mut video = Video::new("unused.mp4", palette());
+        video.program = "aoc-viz-no-such-encoder".to_string();
+        let err = video.render(&Frame::new(1, 1, 0)).unwrap_err();
+        assert!(matches!(err, RenderError::Io(err) if err.kind() == io::ErrorKind::NotFound));
+        video.finish().unwrap();
+    }
+}
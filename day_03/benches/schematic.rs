@@ -0,0 +1,57 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day_3::{solve_single_pass, Schematic};
+
+const SYMBOLS: &[u8] = b"*#+$/@=%&-";
+
+/// Builds a square schematic with a deterministic scattering of numbers and symbols.
+fn generate_schematic(size: usize) -> String {
+    let mut rng = fastrand::Rng::with_seed(0x2545F4914F6CDD1D);
+
+    let mut out_str = String::with_capacity(size * (size + 1));
+    for _ in 0..size {
+        let mut col = 0;
+        while col < size {
+            let roll = rng.u64(..10);
+            if roll < 2 && col + 3 <= size {
+                let value = 1 + rng.u64(..999);
+                let digits = format!("{value:<3}").replace(' ', ".");
+                out_str.push_str(&digits);
+                col += 3;
+            } else {
+                if roll == 2 {
+                    out_str.push(SYMBOLS[rng.usize(..SYMBOLS.len())] as char);
+                } else {
+                    out_str.push('.');
+                }
+                col += 1;
+            }
+        }
+        out_str.push('\n');
+    }
+
+    out_str
+}
+
+fn schematic_benchmark(c: &mut Criterion) {
+    let input = generate_schematic(140);
+    let schematic = Schematic::from_input_str(&input);
+    assert_eq!(
+        solve_single_pass(&input),
+        Some((schematic.sum_part_numbers(), schematic.sum_gear_ratios()))
+    );
+
+    let mut group = c.benchmark_group("schematic_140x140");
+    group.bench_function("parse_then_solve", |b| {
+        b.iter(|| {
+            let schematic = Schematic::from_input_str(black_box(&input));
+            (schematic.sum_part_numbers(), schematic.sum_gear_ratios())
+        })
+    });
+    group.bench_function("single_pass", |b| {
+        b.iter(|| solve_single_pass(black_box(&input)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, schematic_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,427 @@
+#[cfg(feature = "render")]
+pub mod render;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::ops::RangeInclusive;
+
+lazy_static! {
+    static ref SYMBOL_REGEX: Regex = Regex::new(r"[\W&&[^.\n]]").unwrap();
+    static ref NUMBER_REGEX: Regex = Regex::new(r"\d+").unwrap();
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SchematicValue {
+    pub value: u64,
+    pub bounding_box: (RangeInclusive<usize>, RangeInclusive<usize>),
+}
+
+impl SchematicValue {
+    pub fn new(value: u64, line_num: usize, line_range: RangeInclusive<usize>) -> Self {
+        let x_bounds = if *line_range.start() > 0 {
+            (*line_range.start() - 1)..=(*line_range.end() + 1)
+        } else {
+            0..=(*line_range.end() + 1)
+        };
+        let y_bounds = if line_num > 0 {
+            (line_num - 1)..=(line_num + 1)
+        } else {
+            0..=(line_num + 1)
+        };
+
+        Self {
+            value,
+            bounding_box: (x_bounds, y_bounds),
+        }
+    }
+
+    pub fn is_part_number(&self, symbols: &[PartSymbol]) -> bool {
+        for symbol in symbols {
+            let (x_pos, y_pos) = symbol.location;
+            if self.bounding_box.0.contains(&x_pos) && self.bounding_box.1.contains(&y_pos) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartSymbol {
+    pub symbol: String,
+    pub location: (usize, usize),
+}
+
+impl PartSymbol {
+    pub fn gear_ratio(&self, values: &[SchematicValue]) -> Option<u64> {
+        if self.symbol != "*" {
+            return None;
+        }
+
+        // Check overlap with each SchematicValue
+        let adjacent_values: Vec<&SchematicValue> = values
+            .iter()
+            .filter(|value| self.does_overlap(value))
+            .collect();
+        if adjacent_values.len() != 2 {
+            return None;
+        }
+
+        Some(adjacent_values[0].value * adjacent_values[1].value)
+    }
+
+    fn does_overlap(&self, value: &SchematicValue) -> bool {
+        let (x, y) = self.location;
+        let (x_bound, y_bound) = &value.bounding_box;
+        x_bound.contains(&x) && y_bound.contains(&y)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Schematic {
+    pub values: Vec<SchematicValue>,
+    pub part_symbols: Vec<PartSymbol>,
+}
+
+impl Schematic {
+    pub fn from_input_str(input_str: &str) -> Self {
+        let mut values = vec![];
+        let mut part_symbols = vec![];
+
+        input_str.lines().enumerate().for_each(|(line_num, line)| {
+            // Check for symbols
+            part_symbols.extend(
+                SYMBOL_REGEX
+                    .captures_iter(line)
+                    .filter_map(|c| c.get(0))
+                    .map(|m| PartSymbol {
+                        symbol: m.as_str().parse().unwrap(),
+                        location: (m.start(), line_num),
+                    }),
+            );
+
+            // Check for values
+            values.extend(
+                NUMBER_REGEX
+                    .captures_iter(line)
+                    .filter_map(|c| c.get(0))
+                    .map(|m| {
+                        let line_range = m.start()..=(m.end() - 1);
+                        let value: u64 = m
+                            .as_str()
+                            .parse()
+                            .expect("matched value should be a base-10 number");
+                        SchematicValue::new(value, line_num, line_range)
+                    }),
+            );
+        });
+
+        Self {
+            values,
+            part_symbols,
+        }
+    }
+
+    pub fn sum_part_numbers(&self) -> u64 {
+        self.values
+            .iter()
+            .filter(|&value| value.is_part_number(&self.part_symbols))
+            .fold(0, |acc, part_num| acc + part_num.value)
+    }
+
+    pub fn sum_gear_ratios(&self) -> u64 {
+        self.part_symbols
+            .iter()
+            .filter_map(|part_symbol| part_symbol.gear_ratio(&self.values))
+            .sum()
+    }
+}
+
+/// Returns `true` for bytes that [SYMBOL_REGEX] would treat as a part symbol
+fn is_symbol_byte(b: u8) -> bool {
+    !(b.is_ascii_alphanumeric() || b == b'_' || b == b'.' || b == b'\n' || b == b'\r')
+}
+
+/// Reads the full number containing the digit at `idx`, scanning left to its first digit.
+fn read_number_at(line: &[u8], idx: usize) -> u64 {
+    let start = line[..idx]
+        .iter()
+        .rposition(|b| !b.is_ascii_digit())
+        .map_or(0, |pos| pos + 1);
+    line[start..]
+        .iter()
+        .take_while(|b| b.is_ascii_digit())
+        .fold(0, |acc, b| acc * 10 + (b - b'0') as u64)
+}
+
+/// Solves both parts in a single pass over the raw bytes of the schematic without building
+/// intermediate [SchematicValue] or [PartSymbol] lists.
+///
+/// Returns the part number sum and the gear ratio sum, or `None` if the lines aren't all the
+/// same length.
+pub fn solve_single_pass(input_str: &str) -> Option<(u64, u64)> {
+    let lines: Vec<&[u8]> = input_str.lines().map(str::as_bytes).collect();
+    if lines.iter().any(|line| line.len() != lines[0].len()) {
+        return None;
+    }
+    let neighbor_rows = |y: usize| y.saturating_sub(1)..=(y + 1).min(lines.len() - 1);
+
+    let mut part_num_sum = 0;
+    let mut gear_ratio_sum = 0;
+    for (y, line) in lines.iter().enumerate() {
+        let mut x = 0;
+        while x < line.len() {
+            let b = line[x];
+            if b.is_ascii_digit() {
+                // Number starts here. Read it and check its neighborhood for a symbol.
+                let end = line[x..]
+                    .iter()
+                    .position(|b| !b.is_ascii_digit())
+                    .map_or(line.len(), |len| x + len);
+                let cols = x.saturating_sub(1)..(end + 1).min(line.len());
+                let is_part = neighbor_rows(y).any(|row| {
+                    lines[row]
+                        .get(cols.clone())
+                        .is_some_and(|bytes| bytes.iter().any(|&b| is_symbol_byte(b)))
+                });
+                if is_part {
+                    part_num_sum += read_number_at(line, x);
+                }
+                x = end;
+                continue;
+            }
+
+            if b == b'*' {
+                // Collect each distinct number touching the gear candidate
+                let mut count = 0;
+                let mut ratio = 1;
+                for row in neighbor_rows(y) {
+                    let neighbor_line = lines[row];
+                    let max_col = (x + 1).min(neighbor_line.len().saturating_sub(1));
+                    for col in x.saturating_sub(1)..=max_col {
+                        let starts_number = neighbor_line[col].is_ascii_digit()
+                            && (col == x.saturating_sub(1)
+                                || !neighbor_line[col - 1].is_ascii_digit());
+                        if starts_number {
+                            count += 1;
+                            ratio *= read_number_at(neighbor_line, col);
+                        }
+                    }
+                }
+                if count == 2 {
+                    gear_ratio_sum += ratio;
+                }
+            }
+            x += 1;
+        }
+    }
+
+    Some((part_num_sum, gear_ratio_sum))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    pub(crate) const INPUT_STR: &str = "467..114..\n\
+                                        ...*......\n\
+                                        ..35..633.\n\
+                                        ......#...\n\
+                                        617*......\n\
+                                        .....+.58.\n\
+                                        ..592.....\n\
+                                        ......755.\n\
+                                        ...$.*....\n\
+                                        .664.598..";
+
+    lazy_static! {
+        pub(crate) static ref TEST_SCHEMATIC: Schematic = Schematic {
+            values: vec![
+                SchematicValue {
+                    value: 467,
+                    bounding_box: (0..=3, 0..=1),
+                },
+                SchematicValue {
+                    value: 114,
+                    bounding_box: (4..=8, 0..=1),
+                },
+                SchematicValue {
+                    value: 35,
+                    bounding_box: (1..=4, 1..=3),
+                },
+                SchematicValue {
+                    value: 633,
+                    bounding_box: (5..=9, 1..=3),
+                },
+                SchematicValue {
+                    value: 617,
+                    bounding_box: (0..=3, 3..=5),
+                },
+                SchematicValue {
+                    value: 58,
+                    bounding_box: (6..=9, 4..=6),
+                },
+                SchematicValue {
+                    value: 592,
+                    bounding_box: (1..=5, 5..=7),
+                },
+                SchematicValue {
+                    value: 755,
+                    bounding_box: (5..=9, 6..=8),
+                },
+                SchematicValue {
+                    value: 664,
+                    bounding_box: (0..=4, 8..=10),
+                },
+                SchematicValue {
+                    value: 598,
+                    bounding_box: (4..=8, 8..=10),
+                },
+            ],
+            part_symbols: vec![
+                PartSymbol {
+                    symbol: String::from('*'),
+                    location: (3, 1)
+                },
+                PartSymbol {
+                    symbol: String::from('#'),
+                    location: (6, 3)
+                },
+                PartSymbol {
+                    symbol: String::from('*'),
+                    location: (3, 4)
+                },
+                PartSymbol {
+                    symbol: String::from('+'),
+                    location: (5, 5)
+                },
+                PartSymbol {
+                    symbol: String::from('$'),
+                    location: (3, 8)
+                },
+                PartSymbol {
+                    symbol: String::from('*'),
+                    location: (5, 8)
+                },
+            ],
+        };
+    }
+
+    #[test]
+    fn standard_new_schematic_value_test() {
+        let value = 35;
+        let line_num = 2;
+        let line_range = 2..=3;
+
+        assert_eq!(
+            SchematicValue::new(value, line_num, line_range),
+            SchematicValue {
+                value,
+                bounding_box: (1..=4, 1..=3)
+            }
+        )
+    }
+
+    #[test]
+    fn schematic_value_is_part_number_test() {
+        let schematic_value = SchematicValue {
+            value: 35,
+            bounding_box: (1..=4, 1..=3),
+        };
+        let symbol = PartSymbol {
+            symbol: String::from('+'),
+            location: (4, 2),
+        };
+        assert!(schematic_value.is_part_number(&[symbol]));
+
+        let symbol = PartSymbol {
+            symbol: String::from('+'),
+            location: (4, 6),
+        };
+        assert!(!schematic_value.is_part_number(&[symbol]));
+    }
+
+    #[test]
+    fn part_symbol_does_overlap_test() {
+        let schematic_value = SchematicValue {
+            value: 35,
+            bounding_box: (1..=4, 1..=3),
+        };
+        let symbol = PartSymbol {
+            symbol: String::from('+'),
+            location: (4, 2),
+        };
+        assert!(symbol.does_overlap(&schematic_value));
+
+        let symbol = PartSymbol {
+            symbol: String::from('+'),
+            location: (6, 8),
+        };
+        assert!(!symbol.does_overlap(&schematic_value));
+    }
+
+    #[test]
+    fn part_symbol_gear_ratio_test() {
+        let values = &TEST_SCHEMATIC.values[0..3];
+        let gear_ratio = TEST_SCHEMATIC.part_symbols[0].gear_ratio(values);
+        assert_eq!(gear_ratio, Some(16345));
+    }
+
+    #[test]
+    fn schematic_from_str_test() {
+        let schematic = Schematic::from_input_str(INPUT_STR);
+        assert_eq!(schematic.values, TEST_SCHEMATIC.values);
+        assert_eq!(schematic.part_symbols, TEST_SCHEMATIC.part_symbols);
+    }
+
+    #[test]
+    fn schematic_sum_part_numbers_test() {
+        assert_eq!(TEST_SCHEMATIC.sum_part_numbers(), 4361);
+    }
+
+    #[test]
+    fn schematic_sum_gear_ratios_test() {
+        assert_eq!(TEST_SCHEMATIC.sum_gear_ratios(), 467835);
+    }
+
+    #[test]
+    fn solve_single_pass_test() {
+        assert_eq!(solve_single_pass(INPUT_STR), Some((4361, 467835)));
+
+        // Numbers touching the edges of the grid
+        let input = "12*3\n\
+                     ....\n\
+                     5..*\n\
+                     #.10";
+        let schematic = Schematic::from_input_str(input);
+        let expected = (schematic.sum_part_numbers(), schematic.sum_gear_ratios());
+        assert_eq!(solve_single_pass(input), Some(expected));
+    }
+
+    #[test]
+    fn solve_single_pass_ragged_lines() {
+        // The neighbors of a number are only checked as far as its own line goes, so lines of
+        // different lengths would hide symbols
+        assert_eq!(solve_single_pass("12..\n..*"), None);
+        assert_eq!(solve_single_pass("1*2\n\n3.."), None);
+        assert_eq!(solve_single_pass(""), Some((0, 0)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let json = serde_json::to_value(&*TEST_SCHEMATIC).unwrap();
+        assert_eq!(
+            json["values"][0],
+            serde_json::json!({
+                "value": 467,
+                "bounding_box": [{"start": 0, "end": 3}, {"start": 0, "end": 1}],
+            })
+        );
+        let schematic: Schematic = serde_json::from_value(json).unwrap();
+        assert_eq!(schematic, *TEST_SCHEMATIC);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{Schematic, SchematicValue, NUMBER_REGEX};
+use aoc_viz::{Color, Frame, Palette, Png, RenderError, Renderer, Terminal};
+use std::io::Write;
+use std::path::Path;
+
+/// How many pixels across each character of the schematic is drawn
+const CHAR_PIXELS: u32 = 6;
+
+/// What each character of the schematic is. Symbols come after numbers so they still show when
+/// a frame is shrunk.
+const BLANK: u8 = 0;
+const NON_PART_NUMBER: u8 = 1;
+const PART_NUMBER: u8 = 2;
+const SYMBOL: u8 = 3;
+const GEAR: u8 = 4;
+
+pub fn palette() -> Palette {
+    Palette::new([
+        Color::from(0x1E1E28),
+        Color::from(0x8C2F39),
+        Color::from(0x3FA34D),
+        Color::from(0x2E9CCA),
+        Color::from(0xF2C14E),
+    ])
+}
+
+/// Marks out the part numbers, the numbers that aren't parts, the gears and every other symbol in
+/// the schematic read from `input_str`, one cell per character
+pub fn schematic_frame(schematic: &Schematic, input_str: &str) -> Frame {
+    let width = input_str.lines().map(str::len).max().unwrap_or(0);
+    let height = input_str.lines().count();
+    let mut frame = Frame::new(width, height, BLANK);
+
+    for (line_num, line) in input_str.lines().enumerate() {
+        for m in NUMBER_REGEX.find_iter(line) {
+            let value = SchematicValue::new(0, line_num, m.start()..=(m.end() - 1));
+            let index = if value.is_part_number(&schematic.part_symbols) {
+                PART_NUMBER
+            } else {
+                NON_PART_NUMBER
+            };
+            for col in m.range() {
+                frame.set((line_num, col), index);
+            }
+        }
+    }
+    for symbol in &schematic.part_symbols {
+        let index = match symbol.gear_ratio(&schematic.values) {
+            Some(_) => GEAR,
+            None => SYMBOL,
+        };
+        let (col, row) = symbol.location;
+        frame.set((row, col), index);
+    }
+    frame
+}
+
+/// Draws the schematic to `out` with its characters over the colors
+pub fn render_terminal(
+    schematic: &Schematic,
+    input_str: &str,
+    out: impl Write,
+) -> Result<(), RenderError> {
+    Terminal::new(out, palette())
+        .cell_width(1)
+        .text(input_str)
+        .render(&schematic_frame(schematic, input_str))
+}
+
+/// Draws the schematic as an image and saves it to `out`
+pub fn render_png(schematic: &Schematic, input_str: &str, out: &Path) -> Result<(), RenderError> {
+    Png::single(out, palette())
+        .scale(CHAR_PIXELS)
+        .render(&schematic_frame(schematic, input_str))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::{INPUT_STR, TEST_SCHEMATIC};
+
+    #[test]
+    fn schematic_cells() {
+        let frame = schematic_frame(&TEST_SCHEMATIC, INPUT_STR);
+        assert_eq!((frame.width(), frame.height()), (10, 10));
+
+        // 467 is a part number, 114 is not
+        assert_eq!(frame.get((0, 0)), Some(PART_NUMBER));
+        assert_eq!(frame.get((0, 5)), Some(NON_PART_NUMBER));
+        assert_eq!(frame.get((0, 3)), Some(BLANK));
+        // The first '*' is a gear, the '#' is a plain symbol
+        assert_eq!(frame.get((1, 3)), Some(GEAR));
+        assert_eq!(frame.get((3, 6)), Some(SYMBOL));
+        // The '*' next to 617 only touches one number, so it isn't a gear
+        assert_eq!(frame.get((4, 3)), Some(SYMBOL));
+    }
+
+    #[test]
+    fn terminal_keeps_the_characters() {
+        let mut out = vec![];
+        render_terminal(&TEST_SCHEMATIC, INPUT_STR, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 10);
+
+        let without_colors = |line: &str| {
+            line.split("\x1b[")
+                .map(|part| part.split_once('m').map_or(part, |(_, text)| text))
+                .collect::<String>()
+        };
+        for (line, input_line) in lines.iter().zip(INPUT_STR.lines()) {
+            assert_eq!(without_colors(line), input_line);
+        }
+    }
+}
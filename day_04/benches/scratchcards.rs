@@ -0,0 +1,57 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day_4::{CardSet, Scratchcard};
+
+/// Builds `count` cards in the puzzle format with 10 winning and 25 scratched numbers each.
+///
+/// Every 8th card wins 3 copies so the copy counts stay bounded no matter how many cards there are.
+fn generate_cards(count: usize) -> CardSet {
+    let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+    (1..=count)
+        .map(|id| {
+            let winning: Vec<String> = (1..=10).map(|num| num.to_string()).collect();
+            let scratched: Vec<String> = (0..25)
+                .map(|i| {
+                    if id % 8 == 0 && i < 3 {
+                        (i + 1).to_string()
+                    } else {
+                        rng.u32(11..100).to_string()
+                    }
+                })
+                .collect();
+            format!("Card {id}: {} | {}", winning.join(" "), scratched.join(" "))
+                .parse::<Scratchcard>()
+                .expect("generated card should parse")
+        })
+        .collect()
+}
+
+fn scratchcard_benchmark(c: &mut Criterion) {
+    let cards = generate_cards(10_000);
+
+    c.bench_function("total_score_10k", |b| {
+        b.iter(|| black_box(&cards).total_score())
+    });
+    c.bench_function("copy_game_10k", |b| {
+        b.iter(|| black_box(&cards).copy_game())
+    });
+}
+
+/// Stress test for the `parallel` feature. Compare runs with and without `--features parallel`.
+fn scratchcard_stress_benchmark(c: &mut Criterion) {
+    let cards = generate_cards(1_000_000);
+
+    let mut group = c.benchmark_group("stress_1m");
+    group.sample_size(10);
+    group.bench_function("total_score", |b| {
+        b.iter(|| black_box(&cards).total_score())
+    });
+    group.bench_function("matching_counts", |b| {
+        b.iter(|| black_box(&cards).matching_counts())
+    });
+    group.bench_function("copy_game", |b| b.iter(|| black_box(&cards).copy_game()));
+    group.finish();
+}
+
+criterion_group!(benches, scratchcard_benchmark, scratchcard_stress_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,532 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::str::FromStr;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Scratchcard {
+    id: usize,
+    /// Kept as a set so each scratched number is matched in constant time
+    winning_numbers: HashSet<u64>,
+    scratched_numbers: Vec<u64>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseScratchcardError;
+
+/// Problems with the card IDs of a [CardSet]
+#[derive(Debug, Eq, PartialEq)]
+pub enum CardIdError {
+    /// The card at `position` should have had ID `expected` for the cards to be sequential
+    OutOfSequence {
+        position: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// More than one card has this ID
+    Duplicate(usize),
+}
+
+impl FromStr for Scratchcard {
+    type Err = ParseScratchcardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut id_data_split = s.split(':');
+        // Get card ID
+        let id = match id_data_split.next() {
+            Some(id_str) => match id_str.split(' ').next_back() {
+                Some(num_str) => num_str.parse::<usize>(),
+                None => Ok(0),
+            },
+            None => Ok(0),
+        };
+        if id.is_err() || id == Ok(0) {
+            return Err(ParseScratchcardError);
+        }
+        let id = id.unwrap();
+
+        // Split numbers into winning and scratched
+        let numbers_split = id_data_split.next().map(|s| s.split('|'));
+        if numbers_split.is_none() {
+            return Err(ParseScratchcardError);
+        }
+        let mut numbers_split = numbers_split.unwrap();
+
+        // Get winning numbers
+        let winning_num_split = numbers_split.next().map(|s| s.split(' '));
+        if winning_num_split.is_none() {
+            return Err(ParseScratchcardError);
+        }
+        let winning_num_split = winning_num_split.unwrap();
+        let winning_numbers = winning_num_split
+            .filter_map(|num| num.parse::<u64>().ok())
+            .collect();
+
+        // Get scratched numbers
+        let scratched_num_split = numbers_split.next().map(|s| s.split(' '));
+        if scratched_num_split.is_none() {
+            return Err(ParseScratchcardError);
+        }
+        let scratched_num_split = scratched_num_split.unwrap();
+        let scratched_numbers = scratched_num_split
+            .filter_map(|num| num.parse::<u64>().ok())
+            .collect();
+
+        Ok(Self {
+            id,
+            winning_numbers,
+            scratched_numbers,
+        })
+    }
+}
+
+impl Scratchcard {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn calculate_score(&self) -> u64 {
+        match self.calculate_matching_count() {
+            0 => 0,
+            count => 1 << (count - 1),
+        }
+    }
+
+    pub fn calculate_matching_count(&self) -> usize {
+        self.scratched_numbers
+            .iter()
+            .filter(|&num| self.winning_numbers.contains(num))
+            .count()
+    }
+}
+
+/// Ordered collection of [Scratchcard]s along with the queries both puzzle parts need
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CardSet {
+    cards: Vec<Scratchcard>,
+}
+
+impl FromStr for CardSet {
+    type Err = ParseScratchcardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cards = s
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.parse::<Scratchcard>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { cards })
+    }
+}
+
+impl FromIterator<Scratchcard> for CardSet {
+    fn from_iter<T: IntoIterator<Item = Scratchcard>>(iter: T) -> Self {
+        Self {
+            cards: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl CardSet {
+    /// Reads all cards from the file at `path`, skipping any lines that aren't valid cards
+    pub fn from_file(path: &str) -> Self {
+        let input_file = File::open(path).expect("failed to open input file");
+        let reader = BufReader::new(input_file);
+        reader
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| line.parse::<Scratchcard>().ok())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Scratchcard> {
+        self.cards.iter()
+    }
+
+    /// Gets the card with the given ID
+    pub fn card(&self, id: usize) -> Option<&Scratchcard> {
+        self.cards.iter().find(|card| card.id == id)
+    }
+
+    /// Gets the part 1 score of the card with the given ID
+    pub fn score(&self, id: usize) -> Option<u64> {
+        self.card(id).map(Scratchcard::calculate_score)
+    }
+
+    /// Gets the number of matching numbers on the card with the given ID
+    pub fn matching_count(&self, id: usize) -> Option<usize> {
+        self.card(id).map(Scratchcard::calculate_matching_count)
+    }
+
+    /// Sums the scores of every card for part 1
+    #[cfg(not(feature = "parallel"))]
+    pub fn total_score(&self) -> u64 {
+        self.cards.iter().map(Scratchcard::calculate_score).sum()
+    }
+
+    /// Sums the scores of every card for part 1
+    #[cfg(feature = "parallel")]
+    pub fn total_score(&self) -> u64 {
+        self.cards
+            .par_iter()
+            .map(Scratchcard::calculate_score)
+            .sum()
+    }
+
+    /// Gets the number of matching numbers on every card, in input order
+    #[cfg(not(feature = "parallel"))]
+    pub fn matching_counts(&self) -> Vec<usize> {
+        self.cards
+            .iter()
+            .map(Scratchcard::calculate_matching_count)
+            .collect()
+    }
+
+    /// Gets the number of matching numbers on every card, in input order
+    #[cfg(feature = "parallel")]
+    pub fn matching_counts(&self) -> Vec<usize> {
+        self.cards
+            .par_iter()
+            .map(Scratchcard::calculate_matching_count)
+            .collect()
+    }
+
+    /// Checks that the cards are numbered 1, 2, 3, ... in input order, which [CardSet::copy_game]
+    /// relies on.
+    pub fn validate_ids(&self) -> Result<(), CardIdError> {
+        let mut seen = HashSet::new();
+        for card in &self.cards {
+            if !seen.insert(card.id) {
+                return Err(CardIdError::Duplicate(card.id));
+            }
+        }
+
+        match self
+            .cards
+            .iter()
+            .enumerate()
+            .find(|(position, card)| card.id != position + 1)
+        {
+            Some((position, card)) => Err(CardIdError::OutOfSequence {
+                position,
+                expected: position + 1,
+                found: card.id,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Runs the count and copy algorithm for part 2 without modifying the cards.
+    ///
+    /// Cards win copies of the cards that follow them in input order, so the IDs must be sequential
+    /// (see [CardSet::validate_ids]). Use [CardSet::copy_game_by_id] for anything else.
+    pub fn copy_game(&self) -> Result<CopyGameResult, CopyGameError> {
+        let card_count = self.cards.len();
+        let mut copy_counts: Vec<u128> = vec![1; card_count];
+        for (i, match_count) in self.matching_counts().into_iter().enumerate() {
+            let last_copy = (i + match_count).min(card_count - 1);
+            for j in (i + 1)..=last_copy {
+                copy_counts[j] = copy_counts[j]
+                    .checked_add(copy_counts[i])
+                    .ok_or(CopyGameError::Overflow(self.cards[j].id))?;
+            }
+        }
+
+        CopyGameResult::from_copy_counts(&self.cards, copy_counts)
+    }
+
+    /// Runs the part 2 copy game using the card IDs instead of input positions.
+    ///
+    /// A card with ID `n` and `m` matches wins copies of cards `n + 1..=n + m`. The cards may be in
+    /// any order and IDs missing from the set simply aren't won. Copy counts are still reported in
+    /// input order.
+    pub fn copy_game_by_id(&self) -> Result<CopyGameResult, CopyGameError> {
+        let mut positions = HashMap::with_capacity(self.cards.len());
+        for (position, card) in self.cards.iter().enumerate() {
+            if positions.insert(card.id, position).is_some() {
+                return Err(CardIdError::Duplicate(card.id).into());
+            }
+        }
+
+        let match_counts = self.matching_counts();
+        let mut order: Vec<usize> = (0..self.cards.len()).collect();
+        order.sort_by_key(|&position| self.cards[position].id);
+
+        let mut copy_counts: Vec<u128> = vec![1; self.cards.len()];
+        for position in order {
+            let id = self.cards[position].id;
+            for won_id in (id + 1)..=(id + match_counts[position]) {
+                if let Some(&won_position) = positions.get(&won_id) {
+                    copy_counts[won_position] = copy_counts[won_position]
+                        .checked_add(copy_counts[position])
+                        .ok_or(CopyGameError::Overflow(won_id))?;
+                }
+            }
+        }
+
+        CopyGameResult::from_copy_counts(&self.cards, copy_counts)
+    }
+
+    /// Total number of cards held at the end of the part 2 copy game
+    pub fn total_cards_after_copies(&self) -> Result<u128, CopyGameError> {
+        self.copy_game().map(|result| result.total_cards)
+    }
+}
+
+/// Errors that can stop the part 2 copy game
+#[derive(Debug, Eq, PartialEq)]
+pub enum CopyGameError {
+    /// The copy count of the card with this ID, or the running total once it was added, no longer
+    /// fits in a `u128`
+    Overflow(usize),
+    InvalidIds(CardIdError),
+}
+
+impl From<CardIdError> for CopyGameError {
+    fn from(value: CardIdError) -> Self {
+        Self::InvalidIds(value)
+    }
+}
+
+/// Outcome of the part 2 copy game
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CopyGameResult {
+    /// Number of instances held of each card, in input order
+    pub copy_counts: Vec<u128>,
+    /// Total number of cards held once the game is finished
+    pub total_cards: u128,
+}
+
+impl CopyGameResult {
+    fn from_copy_counts(
+        cards: &[Scratchcard],
+        copy_counts: Vec<u128>,
+    ) -> Result<Self, CopyGameError> {
+        let total_cards =
+            copy_counts
+                .iter()
+                .zip(cards)
+                .try_fold(0u128, |total, (count, card)| {
+                    total
+                        .checked_add(*count)
+                        .ok_or(CopyGameError::Overflow(card.id))
+                })?;
+
+        Ok(Self {
+            copy_counts,
+            total_cards,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\n\
+                              Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\n\
+                              Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1\n\
+                              Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83\n\
+                              Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36\n\
+                              Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    #[test]
+    fn scratchcard_from_str_test() {
+        let expected_card = Scratchcard {
+            id: 1,
+            winning_numbers: HashSet::from([41, 48, 83, 86, 17]),
+            scratched_numbers: vec![83, 86, 6, 31, 17, 9, 48, 53],
+        };
+
+        let line = TEST_INPUT.lines().next().unwrap();
+        assert_eq!(line.parse::<Scratchcard>(), Ok(expected_card));
+
+        let line = "Card 1: q z f";
+        assert_eq!(line.parse::<Scratchcard>(), Err(ParseScratchcardError));
+    }
+
+    #[test]
+    fn scratchcard_calculate_score_test() {
+        let expected_card = Scratchcard {
+            id: 1,
+            winning_numbers: HashSet::from([41, 48, 83, 86, 17]),
+            scratched_numbers: vec![83, 86, 6, 31, 17, 9, 48, 53],
+        };
+        let expected_score = 8;
+        assert_eq!(expected_card.calculate_score(), expected_score);
+
+        let expected_card = Scratchcard {
+            id: 5,
+            winning_numbers: HashSet::from([87, 83, 26, 28, 32]),
+            scratched_numbers: vec![88, 30, 70, 12, 93, 22, 82, 36],
+        };
+        let expected_score = 0;
+        assert_eq!(expected_card.calculate_score(), expected_score);
+    }
+
+    #[test]
+    fn card_set_from_str_test() {
+        let card_set = TEST_INPUT.parse::<CardSet>().unwrap();
+        assert_eq!(card_set.len(), 6);
+        assert_eq!(card_set.card(3).map(Scratchcard::id), Some(3));
+        assert_eq!(card_set.card(7), None);
+
+        let input = "Card 1: 41 48 | 83 86\nCard 2: q z f";
+        assert_eq!(input.parse::<CardSet>(), Err(ParseScratchcardError));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let card_set = TEST_INPUT.parse::<CardSet>().unwrap();
+        let json = serde_json::to_value(&card_set).unwrap();
+        assert_eq!(json["cards"][0]["id"], 1);
+        assert_eq!(
+            json["cards"][0]["scratched_numbers"],
+            serde_json::json!([83, 86, 6, 31, 17, 9, 48, 53])
+        );
+        assert_eq!(serde_json::from_value::<CardSet>(json).unwrap(), card_set);
+
+        let result = card_set.copy_game().unwrap();
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["total_cards"], 30);
+        assert_eq!(
+            serde_json::from_value::<CopyGameResult>(json).unwrap(),
+            result
+        );
+    }
+
+    #[test]
+    fn card_set_per_card_queries_test() {
+        let card_set = TEST_INPUT.parse::<CardSet>().unwrap();
+        assert_eq!(card_set.score(1), Some(8));
+        assert_eq!(card_set.score(5), Some(0));
+        assert_eq!(card_set.matching_count(2), Some(2));
+        assert_eq!(card_set.matching_count(10), None);
+    }
+
+    #[test]
+    fn card_set_total_score_test() {
+        let card_set = TEST_INPUT.parse::<CardSet>().unwrap();
+        let expected = 13;
+        assert_eq!(card_set.total_score(), expected);
+    }
+
+    #[test]
+    fn scratchcard_calculate_matching_count_test() {
+        let expected_card = Scratchcard {
+            id: 1,
+            winning_numbers: HashSet::from([41, 48, 83, 86, 17]),
+            scratched_numbers: vec![83, 86, 6, 31, 17, 9, 48, 53],
+        };
+        let expected = 4;
+
+        assert_eq!(expected_card.calculate_matching_count(), expected);
+    }
+
+    #[test]
+    fn card_set_copy_game_test() {
+        let card_set = TEST_INPUT.parse::<CardSet>().unwrap();
+        let expected = CopyGameResult {
+            copy_counts: vec![1, 2, 4, 8, 14, 1],
+            total_cards: 30,
+        };
+        assert_eq!(card_set.copy_game(), Ok(expected));
+
+        // The cards are untouched, so the game can be replayed
+        assert_eq!(card_set.total_cards_after_copies(), Ok(30));
+
+        // Winning copies on the last card must not run past the end of the table
+        let card_set = "Card 1: 1 2 | 1 5\nCard 2: 3 4 | 3 6"
+            .parse::<CardSet>()
+            .unwrap();
+        assert_eq!(card_set.total_cards_after_copies(), Ok(3));
+    }
+
+    #[test]
+    fn card_set_validate_ids_test() {
+        let card_set = TEST_INPUT.parse::<CardSet>().unwrap();
+        assert_eq!(card_set.validate_ids(), Ok(()));
+
+        let card_set = "Card 1: 1 | 1\nCard 3: 1 | 1".parse::<CardSet>().unwrap();
+        assert_eq!(
+            card_set.validate_ids(),
+            Err(CardIdError::OutOfSequence {
+                position: 1,
+                expected: 2,
+                found: 3
+            })
+        );
+
+        let card_set = "Card 1: 1 | 1\nCard 1: 1 | 1".parse::<CardSet>().unwrap();
+        assert_eq!(card_set.validate_ids(), Err(CardIdError::Duplicate(1)));
+    }
+
+    #[test]
+    fn card_set_copy_game_by_id_test() {
+        // Matches the positional game on well-formed input
+        let card_set = TEST_INPUT.parse::<CardSet>().unwrap();
+        assert_eq!(card_set.copy_game_by_id(), card_set.copy_game());
+
+        // Reversed input gives the same totals, reported in input order
+        let reversed: CardSet = card_set.iter().rev().cloned().collect();
+        let expected = CopyGameResult {
+            copy_counts: vec![1, 14, 8, 4, 2, 1],
+            total_cards: 30,
+        };
+        assert_eq!(reversed.copy_game_by_id(), Ok(expected));
+
+        // Card 2 is missing, so card 1 only wins a copy of card 3
+        let card_set = "Card 1: 5 6 | 5 6\nCard 3: 7 | 7\nCard 4: 1 | 2"
+            .parse::<CardSet>()
+            .unwrap();
+        let expected = CopyGameResult {
+            copy_counts: vec![1, 2, 3],
+            total_cards: 6,
+        };
+        assert_eq!(card_set.copy_game_by_id(), Ok(expected));
+
+        let card_set = "Card 1: 1 | 1\nCard 1: 1 | 1".parse::<CardSet>().unwrap();
+        assert_eq!(
+            card_set.copy_game_by_id(),
+            Err(CopyGameError::InvalidIds(CardIdError::Duplicate(1)))
+        );
+    }
+
+    #[test]
+    fn card_set_copy_game_overflow_test() {
+        // Every card wins a copy of every later card, so card n ends up with 2^(n - 1) copies
+        let numbers = (1..=200)
+            .map(|num| num.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let card_set: CardSet = (1..=130)
+            .map(|id| format!("Card {id}: {numbers} | {numbers}"))
+            .map(|line| line.parse::<Scratchcard>().unwrap())
+            .collect();
+
+        assert_eq!(card_set.copy_game(), Err(CopyGameError::Overflow(129)));
+        assert_eq!(
+            card_set.copy_game_by_id(),
+            Err(CopyGameError::Overflow(129))
+        );
+
+        // With 128 cards even the total just fits
+        let card_set: CardSet = card_set.iter().take(128).cloned().collect();
+        assert_eq!(card_set.total_cards_after_copies(), Ok(u128::MAX));
+    }
+}
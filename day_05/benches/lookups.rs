@@ -0,0 +1,81 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day_5::Almanac;
+
+const CATEGORIES: [&str; 8] = [
+    "seed",
+    "soil",
+    "fertilizer",
+    "water",
+    "light",
+    "temperature",
+    "humidity",
+    "location",
+];
+
+/// Builds an almanac in the puzzle format with `seed_count` seeds and `ranges_per_map` ranges in
+/// each of the seven mappings.
+fn generate_almanac(seed_count: usize, ranges_per_map: usize) -> Almanac {
+    let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+    let seeds: Vec<String> = (0..seed_count)
+        .map(|_| rng.u64(..4_000_000_000).to_string())
+        .collect();
+    let mut input = format!("seeds: {}\n", seeds.join(" "));
+
+    for pair in CATEGORIES.windows(2) {
+        input.push_str(&format!("\n{}-to-{} map:\n", pair[0], pair[1]));
+        let mut src_start = 0;
+        for _ in 0..ranges_per_map {
+            src_start += rng.u64(..10_000_000);
+            let length = 1 + rng.u64(..50_000_000);
+            let dest_start = rng.u64(..4_000_000_000);
+            input.push_str(&format!("{dest_start} {src_start} {length}\n"));
+            src_start += length;
+        }
+    }
+
+    input.parse().expect("generated almanac should parse")
+}
+
+fn bench_lookups(c: &mut Criterion, name: &str, almanac: &Almanac) {
+    let composed = almanac.composed();
+    let seeds = almanac.seeds();
+
+    let scalar: Vec<u64> = seeds
+        .iter()
+        .map(|&seed| composed.get_dest_for_src(seed))
+        .collect();
+    assert_eq!(composed.get_dests_for_srcs(seeds), scalar);
+
+    let mut group = c.benchmark_group(name);
+    group.bench_function("scalar", |b| {
+        b.iter(|| {
+            black_box(seeds)
+                .iter()
+                .map(|&seed| composed.get_dest_for_src(seed))
+                .collect::<Vec<u64>>()
+        })
+    });
+    group.bench_function("batched", |b| {
+        b.iter(|| composed.get_dests_for_srcs(black_box(seeds)))
+    });
+    group.finish();
+}
+
+fn lookup_benchmark(c: &mut Criterion) {
+    // Composes to a few dozen segments, which the batched lookup scans directly
+    bench_lookups(
+        c,
+        "composed_lookups_short_table",
+        &generate_almanac(100_000, 10),
+    );
+    // Composes to well over a hundred segments, which falls back to the scalar lookup
+    bench_lookups(
+        c,
+        "composed_lookups_long_table",
+        &generate_almanac(100_000, 40),
+    );
+}
+
+criterion_group!(benches, lookup_benchmark);
+criterion_main!(benches);
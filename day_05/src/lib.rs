@@ -0,0 +1,1333 @@
+use indicatif::{ParallelProgressIterator, ProgressBar, ProgressStyle};
+use lazy_static::lazy_static;
+use rayon::prelude::*;
+use std::fmt::Write;
+use std::ops::Range;
+use std::str::FromStr;
+
+lazy_static! {
+    static ref PROGRESS_STYLE: ProgressStyle =
+        ProgressStyle::with_template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>10}/{len:10}",)
+            .unwrap()
+            .progress_chars("##-");
+}
+
+/// Source and destination categories named in a mapping header like `seed-to-soil map:`
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct MapCategories {
+    source: String,
+    destination: String,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ParseMapCategoriesError;
+
+impl FromStr for MapCategories {
+    type Err = ParseMapCategoriesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let line = s.replace(':', "");
+        let type_name = line
+            .split_whitespace()
+            .next()
+            .ok_or(ParseMapCategoriesError)?;
+
+        let (source, destination) = type_name
+            .split_once("-to-")
+            .ok_or(ParseMapCategoriesError)?;
+        if source.is_empty() || destination.is_empty() {
+            return Err(ParseMapCategoriesError);
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+            destination: destination.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct AlmanacMapping {
+    src_ranges: Vec<Range<u64>>,
+    dest_ranges: Vec<Range<u64>>,
+    categories: MapCategories,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ParseAlmanacMappingError;
+
+impl FromStr for AlmanacMapping {
+    type Err = ParseAlmanacMappingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+
+        // Get mapping categories
+        let categories = match lines.next() {
+            Some(line) => line.parse::<MapCategories>().ok(),
+            None => None,
+        };
+        if categories.is_none() {
+            return Err(ParseAlmanacMappingError);
+        }
+        let categories = categories.unwrap();
+
+        // Get ranges
+        let mut src_ranges = vec![];
+        let mut dest_ranges = vec![];
+        for line in lines {
+            let values: Vec<u64> = line
+                .split_whitespace()
+                .take(3)
+                .filter_map(|num_str| num_str.parse::<u64>().ok())
+                .collect();
+            if values.len() != 3 {
+                return Err(ParseAlmanacMappingError);
+            }
+
+            let dest_range = values[0]..(values[0] + values[2]);
+            let src_range = values[1]..(values[1] + values[2]);
+
+            src_ranges.push(src_range);
+            dest_ranges.push(dest_range);
+        }
+
+        Ok(Self {
+            categories,
+            src_ranges,
+            dest_ranges,
+        })
+    }
+}
+
+/// Reads back the ranges, checking that every source range has a destination range of the same
+/// length
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AlmanacMapping {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct AlmanacMappingData {
+            src_ranges: Vec<Range<u64>>,
+            dest_ranges: Vec<Range<u64>>,
+            categories: MapCategories,
+        }
+
+        let AlmanacMappingData {
+            src_ranges,
+            dest_ranges,
+            categories,
+        } = AlmanacMappingData::deserialize(deserializer)?;
+        if src_ranges.len() != dest_ranges.len() {
+            return Err(serde::de::Error::custom(format!(
+                "{} source ranges have {} destination ranges",
+                src_ranges.len(),
+                dest_ranges.len()
+            )));
+        }
+        let range_len = |range: &Range<u64>| range.end.checked_sub(range.start);
+        if let Some((src_range, dest_range)) =
+            src_ranges
+                .iter()
+                .zip(&dest_ranges)
+                .find(|(src_range, dest_range)| {
+                    range_len(src_range).is_none() || range_len(src_range) != range_len(dest_range)
+                })
+        {
+            return Err(serde::de::Error::custom(format!(
+                "source range {src_range:?} doesn't match destination range {dest_range:?}"
+            )));
+        }
+        Ok(Self {
+            src_ranges,
+            dest_ranges,
+            categories,
+        })
+    }
+}
+
+impl AlmanacMapping {
+    pub fn get_dest_for_src(&self, src: u64) -> u64 {
+        for (i, src_range) in self.src_ranges.iter().enumerate() {
+            if !src_range.contains(&src) {
+                continue;
+            }
+            // Map src to dest
+            let mut dest_range_copy = self.dest_ranges[i].clone();
+
+            let range_idx = (src - src_range.start) as usize;
+            return dest_range_copy.nth(range_idx).unwrap();
+        }
+
+        // Return unmapped value
+        src
+    }
+
+    /// Gets every source value that maps to `dest`.
+    ///
+    /// Source ranges may overlap in destination space, and a value outside of every source range
+    /// maps to itself, so there can be more than one answer.
+    pub fn get_src_for_dest(&self, dest: u64) -> Vec<u64> {
+        let mut srcs: Vec<u64> = self
+            .dest_ranges
+            .iter()
+            .zip(&self.src_ranges)
+            .filter(|(dest_range, _)| dest_range.contains(&dest))
+            .map(|(dest_range, src_range)| src_range.start + (dest - dest_range.start))
+            .collect();
+
+        let is_unmapped = !self
+            .src_ranges
+            .iter()
+            .any(|src_range| src_range.contains(&dest));
+        if is_unmapped {
+            srcs.push(dest);
+        }
+
+        srcs
+    }
+
+    /// Splits `range` at the boundaries of the source ranges, pairing each piece with the
+    /// destination its first value maps to. The pieces are in no particular order.
+    fn split_range(&self, range: &Range<u64>) -> Vec<(Range<u64>, u64)> {
+        let mut unmapped = vec![range.clone()];
+        let mut pieces = vec![];
+
+        for (src_range, dest_range) in self.src_ranges.iter().zip(&self.dest_ranges) {
+            let mut remaining = vec![];
+            for range in unmapped {
+                let overlap_start = range.start.max(src_range.start);
+                let overlap_end = range.end.min(src_range.end);
+                if overlap_start >= overlap_end {
+                    remaining.push(range);
+                    continue;
+                }
+
+                let dest_start = dest_range.start + (overlap_start - src_range.start);
+                pieces.push((overlap_start..overlap_end, dest_start));
+                if range.start < overlap_start {
+                    remaining.push(range.start..overlap_start);
+                }
+                if overlap_end < range.end {
+                    remaining.push(overlap_end..range.end);
+                }
+            }
+            unmapped = remaining;
+        }
+
+        // Anything not covered by a source range maps to itself
+        pieces.extend(
+            unmapped
+                .into_iter()
+                .filter(|range| !range.is_empty())
+                .map(|range| {
+                    let start = range.start;
+                    (range, start)
+                }),
+        );
+        pieces
+    }
+
+    /// Maps every value in `ranges` to its destination, splitting the ranges wherever they cross
+    /// the boundary of a source range. The output ranges are in no particular order.
+    pub fn get_dest_ranges(&self, ranges: &[Range<u64>]) -> Vec<Range<u64>> {
+        ranges
+            .iter()
+            .flat_map(|range| self.split_range(range))
+            .map(|(piece, dest_start)| dest_start..(dest_start + (piece.end - piece.start)))
+            .collect()
+    }
+}
+
+/// Number of values mapped together by the batched [ComposedMapping] lookups
+#[cfg(feature = "simd")]
+pub const LANES: usize = 8;
+
+/// Largest table the batched lookups will scan. Past this, a binary search per value wins.
+#[cfg(feature = "simd")]
+const MAX_LANE_SCAN_SEGMENTS: usize = 32;
+
+/// A chain of [AlmanacMapping]s collapsed into one piecewise-linear function.
+///
+/// Segment `i` covers the sources `starts[i]..starts[i + 1]` (the last one runs to [u64::MAX]) and
+/// maps them onto consecutive destinations beginning at `dest_starts[i]`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ComposedMapping {
+    starts: Vec<u64>,
+    dest_starts: Vec<u64>,
+}
+
+/// Reads back the segments, checking that they cover every source from 0 up in order and each
+/// has a destination
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ComposedMapping {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct ComposedMappingData {
+            starts: Vec<u64>,
+            dest_starts: Vec<u64>,
+        }
+
+        let ComposedMappingData {
+            starts,
+            dest_starts,
+        } = ComposedMappingData::deserialize(deserializer)?;
+        if starts.first() != Some(&0) {
+            return Err(serde::de::Error::custom(
+                "the first segment has to start at 0",
+            ));
+        }
+        if !starts.windows(2).all(|pair| pair[0] < pair[1]) {
+            return Err(serde::de::Error::custom("the segments have to be in order"));
+        }
+        if starts.len() != dest_starts.len() {
+            return Err(serde::de::Error::custom(format!(
+                "{} segments have {} destinations",
+                starts.len(),
+                dest_starts.len()
+            )));
+        }
+        Ok(Self {
+            starts,
+            dest_starts,
+        })
+    }
+}
+
+impl ComposedMapping {
+    fn identity() -> Self {
+        Self {
+            starts: vec![0],
+            dest_starts: vec![0],
+        }
+    }
+
+    /// Iterates over each segment's source range and the destination of its first value
+    pub fn segments(&self) -> impl Iterator<Item = (Range<u64>, u64)> + '_ {
+        self.starts.iter().enumerate().map(|(i, &start)| {
+            let end = self.starts.get(i + 1).copied().unwrap_or(u64::MAX);
+            (start..end, self.dest_starts[i])
+        })
+    }
+
+    /// Builds the mapping equivalent to applying `self` and then `next`
+    fn then(&self, next: &AlmanacMapping) -> Self {
+        let mut segments: Vec<(Range<u64>, u64)> = self
+            .segments()
+            .flat_map(|(src_range, dest_start)| {
+                let image = dest_start..(dest_start + (src_range.end - src_range.start));
+                next.split_range(&image)
+                    .into_iter()
+                    .map(move |(piece, next_dest_start)| {
+                        let start = src_range.start + (piece.start - image.start);
+                        (start..(start + (piece.end - piece.start)), next_dest_start)
+                    })
+            })
+            .collect();
+        segments.sort_by_key(|(range, _)| range.start);
+
+        // Merge neighboring segments that continue the same line
+        let mut composed = Self {
+            starts: vec![],
+            dest_starts: vec![],
+        };
+        let mut prev: Option<(Range<u64>, u64)> = None;
+        for (range, dest_start) in segments {
+            if let Some((prev_range, prev_dest_start)) = &mut prev {
+                if *prev_dest_start + (prev_range.end - prev_range.start) == dest_start {
+                    prev_range.end = range.end;
+                    continue;
+                }
+            }
+            composed.starts.push(range.start);
+            composed.dest_starts.push(dest_start);
+            prev = Some((range, dest_start));
+        }
+        composed
+    }
+
+    pub fn get_dest_for_src(&self, src: u64) -> u64 {
+        let idx = self.starts.partition_point(|&start| start <= src) - 1;
+        self.dest_starts[idx] + (src - self.starts[idx])
+    }
+
+    /// Gets the lowest destination reached by any of `srcs`
+    #[cfg(not(feature = "simd"))]
+    pub fn get_lowest_dest(&self, srcs: &[u64]) -> Option<u64> {
+        srcs.iter().map(|&src| self.get_dest_for_src(src)).min()
+    }
+
+    /// Gets the lowest destination reached by any of `srcs`
+    #[cfg(feature = "simd")]
+    pub fn get_lowest_dest(&self, srcs: &[u64]) -> Option<u64> {
+        self.get_dests_for_srcs(srcs).into_iter().min()
+    }
+
+    /// Maps [LANES] sources at once.
+    ///
+    /// Instead of a binary search per source, every lane counts the segment starts at or below its
+    /// source. The loop has no branches, so the compiler can turn it into vector compares and adds.
+    /// That only pays off for short tables; see [ComposedMapping::get_dests_for_srcs].
+    #[cfg(feature = "simd")]
+    pub fn get_dests_for_src_lanes(&self, srcs: &[u64; LANES]) -> [u64; LANES] {
+        let mut idxs = [0u64; LANES];
+        for &start in &self.starts {
+            for (idx, &src) in idxs.iter_mut().zip(srcs) {
+                *idx += u64::from(start <= src);
+            }
+        }
+
+        let mut dests = [0u64; LANES];
+        for ((dest, &src), &idx) in dests.iter_mut().zip(srcs).zip(&idxs) {
+            // The first segment always starts at 0, so idx is at least 1
+            let idx = idx as usize - 1;
+            *dest = self.dest_starts[idx] + (src - self.starts[idx]);
+        }
+        dests
+    }
+
+    /// Maps every value in `srcs`, [LANES] at a time. Mappings with more than
+    /// [MAX_LANE_SCAN_SEGMENTS] segments are looked up one value at a time instead.
+    #[cfg(feature = "simd")]
+    pub fn get_dests_for_srcs(&self, srcs: &[u64]) -> Vec<u64> {
+        if self.starts.len() > MAX_LANE_SCAN_SEGMENTS {
+            return srcs.iter().map(|&src| self.get_dest_for_src(src)).collect();
+        }
+
+        let mut dests = Vec::with_capacity(srcs.len());
+        let chunks = srcs.chunks_exact(LANES);
+        let remainder = chunks.remainder();
+        for chunk in chunks {
+            dests.extend_from_slice(&self.get_dests_for_src_lanes(chunk.try_into().unwrap()));
+        }
+        dests.extend(remainder.iter().map(|&src| self.get_dest_for_src(src)));
+        dests
+    }
+
+    /// Gets the lowest destination reached by any value in `ranges`. Only the first value of each
+    /// segment a range overlaps needs to be checked.
+    pub fn get_lowest_dest_in_ranges(&self, ranges: &[Range<u64>]) -> Option<u64> {
+        ranges
+            .iter()
+            .filter(|range| !range.is_empty())
+            .flat_map(|range| {
+                self.segments()
+                    .filter(|(segment, _)| segment.start < range.end && range.start < segment.end)
+                    .map(|(segment, dest_start)| {
+                        dest_start + (range.start.max(segment.start) - segment.start)
+                    })
+            })
+            .min()
+    }
+}
+
+const PLOT_WIDTH: f64 = 800.0;
+const PLOT_HEIGHT: f64 = 600.0;
+const PLOT_MARGIN: f64 = 60.0;
+
+impl ComposedMapping {
+    /// Draws the mapping as an SVG line chart with sources on the x axis and destinations on the y
+    /// axis. The `highlight` ranges are shaded, the parts of the mapping inside them are drawn
+    /// thicker, and the lowest destination they reach is marked.
+    pub fn plot_svg(&self, highlight: &[Range<u64>]) -> String {
+        // The last segment never ends, so stop a little past the last interesting source
+        let x_end = highlight
+            .iter()
+            .map(|range| range.end)
+            .chain(self.starts.last().map(|&start| start + 1))
+            .max()
+            .unwrap_or(1);
+        let x_end = x_end + x_end / 20;
+
+        let lines: Vec<(Range<u64>, u64)> = self
+            .segments()
+            .filter(|(range, _)| range.start < x_end)
+            .map(|(range, dest_start)| (range.start..range.end.min(x_end), dest_start))
+            .collect();
+        let y_end = lines
+            .iter()
+            .map(|(range, dest_start)| dest_start + (range.end - range.start))
+            .max()
+            .unwrap_or(1)
+            .max(1);
+
+        let x_pos =
+            |x: u64| PLOT_MARGIN + x as f64 / x_end as f64 * (PLOT_WIDTH - 2.0 * PLOT_MARGIN);
+        let y_pos = |y: u64| {
+            PLOT_HEIGHT - PLOT_MARGIN - y as f64 / y_end as f64 * (PLOT_HEIGHT - 2.0 * PLOT_MARGIN)
+        };
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{PLOT_WIDTH}" height="{PLOT_HEIGHT}" viewBox="0 0 {PLOT_WIDTH} {PLOT_HEIGHT}">"#
+        )
+        .unwrap();
+        writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+
+        // Highlighted source ranges
+        for range in highlight.iter().filter(|range| !range.is_empty()) {
+            writeln!(
+                svg,
+                r##"<rect x="{:.2}" y="{PLOT_MARGIN}" width="{:.2}" height="{}" fill="#2ca02c" fill-opacity="0.15"/>"##,
+                x_pos(range.start),
+                x_pos(range.end) - x_pos(range.start),
+                PLOT_HEIGHT - 2.0 * PLOT_MARGIN,
+            )
+            .unwrap();
+        }
+
+        // Axes
+        let (x_axis_start, x_axis_end, y_axis) = (x_pos(0), x_pos(x_end), y_pos(0));
+        writeln!(
+            svg,
+            r#"<path d="M {x_axis_start:.2} {:.2} V {y_axis:.2} H {x_axis_end:.2}" fill="none" stroke="black"/>"#,
+            y_pos(y_end),
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{:.2}" y="{:.2}" text-anchor="middle">seed (0 to {x_end})</text>"#,
+            PLOT_WIDTH / 2.0,
+            PLOT_HEIGHT - PLOT_MARGIN / 3.0,
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{:.2}" y="{:.2}" text-anchor="middle" transform="rotate(-90 {:.2} {:.2})">location (0 to {y_end})</text>"#,
+            PLOT_MARGIN / 2.0,
+            PLOT_HEIGHT / 2.0,
+            PLOT_MARGIN / 2.0,
+            PLOT_HEIGHT / 2.0,
+        )
+        .unwrap();
+
+        // Mapping segments, thicker where they fall inside a highlighted range
+        let mut draw_line = |range: &Range<u64>, dest_start: u64, style: &str| {
+            writeln!(
+                svg,
+                r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" {style}/>"#,
+                x_pos(range.start),
+                y_pos(dest_start),
+                x_pos(range.end),
+                y_pos(dest_start + (range.end - range.start)),
+            )
+            .unwrap();
+        };
+        for (range, dest_start) in &lines {
+            draw_line(range, *dest_start, r##"stroke="#1f77b4" stroke-width="1""##);
+        }
+        for highlight_range in highlight {
+            for (range, dest_start) in &lines {
+                let start = range.start.max(highlight_range.start);
+                let end = range.end.min(highlight_range.end);
+                if start < end {
+                    let dest_start = dest_start + (start - range.start);
+                    draw_line(
+                        &(start..end),
+                        dest_start,
+                        r##"stroke="#ff7f0e" stroke-width="3""##,
+                    );
+                }
+            }
+        }
+
+        // Lowest destination reached from the highlighted ranges
+        if let Some(lowest) = self.get_lowest_dest_in_ranges(highlight) {
+            let src = highlight
+                .iter()
+                .flat_map(|range| {
+                    lines.iter().filter_map(move |(segment, dest_start)| {
+                        let start = segment.start.max(range.start);
+                        (start < segment.end.min(range.end)
+                            && dest_start + (start - segment.start) == lowest)
+                            .then_some(start)
+                    })
+                })
+                .next()
+                .unwrap_or(0);
+            writeln!(
+                svg,
+                r#"<circle cx="{:.2}" cy="{:.2}" r="5" fill="red"/>"#,
+                x_pos(src),
+                y_pos(lowest),
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"<text x="{:.2}" y="{:.2}" fill="red">seed {src} → location {lowest}</text>"#,
+                x_pos(src) + 8.0,
+                y_pos(lowest) - 8.0,
+            )
+            .unwrap();
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Number of seeds handed to each rayon task by the part 2 brute force
+const WORK_UNIT_SIZE: u64 = 1_000_000;
+
+const SEED_CATEGORY: &str = "seed";
+const LOCATION_CATEGORY: &str = "location";
+
+#[derive(Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Almanac {
+    seeds: Vec<u64>,
+    /// The mappings from seed to location in the order they are applied, followed by any others in
+    /// input order. They are chained by matching each destination category to the next source
+    /// category.
+    mappings: Vec<AlmanacMapping>,
+    /// How many of the mappings make up the seed to location chain
+    #[cfg_attr(feature = "serde", serde(skip))]
+    location_chain_len: usize,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseAlmanacError;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Almanac {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct AlmanacData {
+            seeds: Vec<u64>,
+            mappings: Vec<AlmanacMapping>,
+        }
+
+        let AlmanacData { seeds, mappings } = AlmanacData::deserialize(deserializer)?;
+        Almanac::new(seeds, mappings)
+            .map_err(|_| serde::de::Error::custom("the mappings don't lead from seed to location"))
+    }
+}
+
+impl Almanac {
+    /// Puts the mappings that lead from seed to location first, in the order they are applied, so
+    /// the lookups don't have to find them again every time.
+    fn new(seeds: Vec<u64>, mut mappings: Vec<AlmanacMapping>) -> Result<Self, ParseAlmanacError> {
+        let mut category = SEED_CATEGORY.to_string();
+        let mut location_chain_len = 0;
+        while category != LOCATION_CATEGORY {
+            let next = mappings[location_chain_len..]
+                .iter()
+                .position(|mapping| mapping.categories.source == category)
+                .ok_or(ParseAlmanacError)?;
+            // Rotating keeps the mappings left over in input order
+            mappings[location_chain_len..=location_chain_len + next].rotate_right(1);
+            category.clone_from(&mappings[location_chain_len].categories.destination);
+            location_chain_len += 1;
+        }
+
+        Ok(Self {
+            seeds,
+            mappings,
+            location_chain_len,
+        })
+    }
+
+    fn parse_seeds(line: &str) -> Result<Vec<u64>, ParseAlmanacError> {
+        let mut line_split = line.split(": ");
+        {
+            let key = line_split.next();
+            if key.is_none() || key.unwrap() != "seeds" {
+                return Err(ParseAlmanacError);
+            }
+        }
+        let seeds_str = line_split.next();
+        if seeds_str.is_none() {
+            return Err(ParseAlmanacError);
+        }
+
+        let seeds: Vec<u64> = seeds_str
+            .unwrap()
+            .split_whitespace()
+            .filter_map(|seed_num| seed_num.parse::<u64>().ok())
+            .collect();
+
+        Ok(seeds)
+    }
+
+    /// Gets the seed numbers listed on the first line of the almanac
+    pub fn seeds(&self) -> &[u64] {
+        &self.seeds
+    }
+
+    /// Follows the mappings from the `from` category until reaching the `to` category. Returns
+    /// `None` if the chain breaks or loops before getting there.
+    fn mapping_chain(&self, from: &str, to: &str) -> Option<Vec<&AlmanacMapping>> {
+        let mut chain = vec![];
+        let mut category = from;
+        while category != to {
+            if chain.len() == self.mappings.len() {
+                return None;
+            }
+            let mapping = self
+                .mappings
+                .iter()
+                .find(|mapping| mapping.categories.source == category)?;
+            chain.push(mapping);
+            category = &mapping.categories.destination;
+        }
+
+        Some(chain)
+    }
+
+    /// Maps `value` from the `from` category to the `to` category, applying only the mappings in
+    /// between. Returns `None` if the almanac has no path from one to the other.
+    pub fn map_value(&self, from: &str, to: &str, value: u64) -> Option<u64> {
+        let chain = self.mapping_chain(from, to)?;
+        Some(
+            chain
+                .iter()
+                .fold(value, |value, mapping| mapping.get_dest_for_src(value)),
+        )
+    }
+
+    /// Gets the mappings in the order they are applied from seed to location
+    fn mappings(&self) -> &[AlmanacMapping] {
+        &self.mappings[..self.location_chain_len]
+    }
+
+    /// Collapses the seven mappings into a single seed to location function
+    pub fn composed(&self) -> ComposedMapping {
+        self.mappings()
+            .iter()
+            .fold(ComposedMapping::identity(), |composed, mapping| {
+                composed.then(mapping)
+            })
+    }
+
+    fn get_location_num(&self, seed: u64) -> u64 {
+        self.mappings()
+            .iter()
+            .fold(seed, |value, mapping| mapping.get_dest_for_src(value))
+    }
+
+    pub fn get_seed_locations(&self) -> Vec<u64> {
+        self.seeds
+            .par_iter()
+            .progress_with_style(PROGRESS_STYLE.clone())
+            .map(|seed| self.get_location_num(*seed))
+            .collect()
+    }
+
+    pub fn get_lowest_location(&self) -> Option<u64> {
+        self.get_seed_locations().iter().min().copied()
+    }
+
+    /// Interprets the seed list as pairs of range starts and lengths for part 2. Overlapping ranges
+    /// are merged so no seed is processed twice.
+    pub fn get_seed_ranges(&self) -> Option<Vec<Range<u64>>> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return None;
+        }
+        let seed_ranges = self
+            .seeds
+            .chunks_exact(2)
+            .map(|pair| pair[0]..(pair[0] + pair[1]))
+            .collect::<Vec<Range<u64>>>();
+
+        Some(condense_ranges(&seed_ranges))
+    }
+
+    /// Maps the given seed ranges all the way to location ranges, splitting them as needed
+    fn get_location_ranges(&self, seed_ranges: &[Range<u64>]) -> Vec<Range<u64>> {
+        self.mappings()
+            .iter()
+            .fold(seed_ranges.to_vec(), |ranges, mapping| {
+                mapping.get_dest_ranges(&ranges)
+            })
+    }
+
+    /// Finds the lowest location for the part 2 seed ranges by pushing whole ranges through each
+    /// mapping instead of checking every seed.
+    pub fn get_lowest_seed_range_location_by_intervals(&self) -> Option<u64> {
+        let seed_ranges = self.get_seed_ranges()?;
+        self.get_location_ranges(&seed_ranges)
+            .iter()
+            .map(|range| range.start)
+            .min()
+    }
+
+    /// Gets every seed that ends up at `location`
+    fn get_seeds_for_location(&self, location: u64) -> Vec<u64> {
+        self.mappings()
+            .iter()
+            .rev()
+            .fold(vec![location], |dests, mapping| {
+                dests
+                    .iter()
+                    .flat_map(|&dest| mapping.get_src_for_dest(dest))
+                    .collect()
+            })
+    }
+
+    /// Finds the lowest location for the part 2 seed ranges by walking locations upward from 0 and
+    /// mapping each one back to its seeds until one of them falls in a seed range.
+    pub fn lowest_location_via_reverse_search(&self) -> Option<u64> {
+        let seed_ranges = self.get_seed_ranges()?;
+        if seed_ranges.iter().all(|range| range.is_empty()) {
+            return None;
+        }
+
+        (0..=u64::MAX).find(|&location| {
+            self.get_seeds_for_location(location)
+                .iter()
+                .any(|seed| seed_ranges.iter().any(|range| range.contains(seed)))
+        })
+    }
+
+    /// Finds the lowest location for the part 2 seed ranges by checking every seed individually.
+    ///
+    /// The seeds are split into work units of at most [WORK_UNIT_SIZE] so one huge range doesn't
+    /// leave a single thread doing most of the work.
+    pub fn get_lowest_seed_range_location(&self) -> Option<u64> {
+        let seed_ranges = self.get_seed_ranges()?;
+        let work_units = split_ranges(&seed_ranges, WORK_UNIT_SIZE);
+
+        // Set up progress bar
+        let pb = ProgressBar::new(sum_range_values(&seed_ranges));
+        pb.set_style(PROGRESS_STYLE.clone());
+
+        let location = work_units
+            .into_par_iter()
+            .filter_map(|work_unit| {
+                let size = work_unit.end - work_unit.start;
+                let location = work_unit.map(|src| self.get_location_num(src)).min();
+                pb.inc(size);
+                location
+            })
+            .min();
+        pb.finish();
+        location
+    }
+}
+
+impl FromStr for Almanac {
+    type Err = ParseAlmanacError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+
+        // Get Seeds
+        let seed_line = lines.next();
+        if seed_line.is_none() {
+            return Err(ParseAlmanacError);
+        }
+        let seed_line = seed_line.unwrap();
+        let seeds = Almanac::parse_seeds(seed_line)?;
+
+        let mut mappings = vec![];
+
+        // Get AlmanacMappings
+        let mut lines_buf = String::new();
+        for line in lines.filter(|&line| !line.is_empty()) {
+            // If line does not start with a digit, it's a new mapping
+            if line.chars().next().unwrap().is_alphabetic() && !lines_buf.is_empty() {
+                let almanac_mapping = lines_buf.parse::<AlmanacMapping>();
+                if almanac_mapping.is_err() {
+                    return Err(ParseAlmanacError);
+                }
+                lines_buf.clear();
+
+                mappings.push(almanac_mapping.unwrap());
+            }
+            lines_buf.push_str(line);
+            lines_buf.push('\n');
+        }
+        // Handle final mapping
+        {
+            let almanac_mapping = lines_buf.parse::<AlmanacMapping>();
+            if almanac_mapping.is_err() {
+                return Err(ParseAlmanacError);
+            }
+            lines_buf.clear();
+
+            mappings.push(almanac_mapping.unwrap());
+        }
+
+        // Make sure seeds can actually be mapped to locations
+        Almanac::new(seeds, mappings)
+    }
+}
+
+/// Combines two ranges into one if they overlap or touch
+fn combine_ranges<Idx: Ord + Copy>(
+    range_0: &Range<Idx>,
+    range_1: &Range<Idx>,
+) -> Option<Range<Idx>> {
+    if range_0.start <= range_1.end && range_1.start <= range_0.end {
+        Some(range_0.start.min(range_1.start)..range_0.end.max(range_1.end))
+    } else {
+        None
+    }
+}
+
+/// Sorts the ranges and merges any that overlap or touch, dropping empty ones
+fn condense_ranges<Idx: Ord + Copy>(ranges: &[Range<Idx>]) -> Vec<Range<Idx>> {
+    let mut sorted: Vec<Range<Idx>> = ranges
+        .iter()
+        .filter(|range| !range.is_empty())
+        .cloned()
+        .collect();
+    sorted.sort_by_key(|range| range.start);
+
+    sorted.into_iter().fold(vec![], |mut condensed, range| {
+        match condensed
+            .last()
+            .and_then(|last: &Range<Idx>| combine_ranges(last, &range))
+        {
+            Some(combined) => *condensed.last_mut().unwrap() = combined,
+            None => condensed.push(range),
+        }
+        condensed
+    })
+}
+
+/// Splits the ranges into consecutive pieces holding at most `max_size` values each
+fn split_ranges(ranges: &[Range<u64>], max_size: u64) -> Vec<Range<u64>> {
+    ranges
+        .iter()
+        .flat_map(|range| {
+            range
+                .clone()
+                .step_by(max_size as usize)
+                .map(move |start| start..(start + max_size).min(range.end))
+        })
+        .collect()
+}
+
+/// Gets count of all values represented by the provided ranges
+fn sum_range_values(ranges: &[Range<u64>]) -> u64 {
+    ranges.iter().map(|range| range.end - range.start).sum()
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "seeds: 79 14 55 13\n\
+                              \n\
+                              seed-to-soil map:\n\
+                              50 98 2\n\
+                              52 50 48\n\
+                              \n\
+                              soil-to-fertilizer map:\n\
+                              0 15 37\n\
+                              37 52 2\n\
+                              39 0 15\n\
+                              \n\
+                              fertilizer-to-water map:\n\
+                              49 53 8\n\
+                              0 11 42\n\
+                              42 0 7\n\
+                              57 7 4\n\
+                              \n\
+                              water-to-light map:\n\
+                              88 18 7\n\
+                              18 25 70\n\
+                              \n\
+                              light-to-temperature map:\n\
+                              45 77 23\n\
+                              81 45 19\n\
+                              68 64 13\n\
+                              \n\
+                              temperature-to-humidity map:\n\
+                              0 69 1\n\
+                              1 0 69\n\
+                              \n\
+                              humidity-to-location map:\n\
+                              60 56 37\n\
+                              56 93 4";
+
+    const SEEDS_LINE: &str = "seeds: 79 14 55 13";
+
+    #[test]
+    fn parse_map_categories_from_str() {
+        let test_data = [
+            ("seed-to-soil map:", "seed", "soil"),
+            ("light-to-temperature map:", "light", "temperature"),
+            ("humidity-to-location", "humidity", "location"),
+            ("planet-to-star map:", "planet", "star"),
+        ];
+
+        for (line, source, destination) in test_data {
+            let expected = MapCategories {
+                source: source.to_string(),
+                destination: destination.to_string(),
+            };
+            assert_eq!(line.parse::<MapCategories>(), Ok(expected));
+        }
+
+        assert_eq!(
+            SEEDS_LINE.parse::<MapCategories>(),
+            Err(ParseMapCategoriesError)
+        );
+        assert_eq!(
+            "-to-soil map:".parse::<MapCategories>(),
+            Err(ParseMapCategoriesError)
+        );
+    }
+
+    #[test]
+    fn parse_almanac_mapping_from_str() {
+        let test_input = "water-to-light map:\n\
+                                88 18 7\n\
+                                18 25 70";
+        let expected = AlmanacMapping {
+            categories: "water-to-light map:".parse().unwrap(),
+            src_ranges: vec![18..25, 25..95],
+            dest_ranges: vec![88..95, 18..88],
+        };
+
+        assert_eq!(test_input.parse::<AlmanacMapping>(), Ok(expected));
+
+        assert_eq!(
+            SEEDS_LINE.parse::<AlmanacMapping>(),
+            Err(ParseAlmanacMappingError)
+        );
+    }
+
+    #[test]
+    fn almanac_mapping_get_dest_for_src() {
+        let mapping = AlmanacMapping {
+            categories: "seed-to-soil map:".parse().unwrap(),
+            src_ranges: vec![98..100, 50..98],
+            dest_ranges: vec![50..52, 52..100],
+        };
+        let test_data = [(79, 81), (14, 14), (55, 57), (13, 13), (98, 50)];
+
+        for (src, dest) in test_data {
+            assert_eq!(mapping.get_dest_for_src(src), dest);
+        }
+    }
+
+    #[test]
+    fn almanac_mapping_get_src_for_dest() {
+        let mapping = AlmanacMapping {
+            categories: "seed-to-soil map:".parse().unwrap(),
+            src_ranges: vec![98..100, 50..98],
+            dest_ranges: vec![50..52, 52..100],
+        };
+        let test_data = [
+            (81, vec![79]),
+            (14, vec![14]),
+            (50, vec![98]),
+            (99, vec![97]),
+        ];
+
+        for (dest, expected) in test_data {
+            assert_eq!(mapping.get_src_for_dest(dest), expected);
+        }
+
+        // 60 is reached from source 58, but also from itself since 60 isn't a mapped source
+        let mapping = AlmanacMapping {
+            categories: "seed-to-soil map:".parse().unwrap(),
+            src_ranges: vec![10..20],
+            dest_ranges: vec![55..65],
+        };
+        assert_eq!(mapping.get_src_for_dest(60), vec![15, 60]);
+        assert_eq!(mapping.get_src_for_dest(15), Vec::<u64>::new());
+
+        // Every source found maps forward to the destination again
+        for dest in 0..120 {
+            for src in mapping.get_src_for_dest(dest) {
+                assert_eq!(mapping.get_dest_for_src(src), dest);
+            }
+        }
+    }
+
+    #[test]
+    fn almanac_mapping_get_dest_ranges() {
+        let mapping = AlmanacMapping {
+            categories: "seed-to-soil map:".parse().unwrap(),
+            src_ranges: vec![98..100, 50..98],
+            dest_ranges: vec![50..52, 52..100],
+        };
+        let test_data = [
+            (vec![79..93], vec![81..95]),
+            (vec![10..20], vec![10..20]),
+            // Straddles both source ranges and the unmapped space on either side
+            (vec![40..105], vec![40..50, 50..52, 52..100, 100..105]),
+            (vec![5..5], vec![]),
+        ];
+
+        for (ranges, expected) in test_data {
+            let mut dest_ranges = mapping.get_dest_ranges(&ranges);
+            dest_ranges.sort_by_key(|range| range.start);
+            assert_eq!(dest_ranges, expected);
+        }
+    }
+
+    #[test]
+    fn parse_seeds_line_from_str() {
+        let expected = vec![79, 14, 55, 13];
+        assert_eq!(Almanac::parse_seeds(SEEDS_LINE), Ok(expected));
+
+        assert_eq!(
+            Almanac::parse_seeds("sounds: 32 12 454"),
+            Err(ParseAlmanacError)
+        );
+    }
+
+    #[test]
+    fn parse_almanac_from_str() {
+        let almanac = TEST_INPUT.parse::<Almanac>();
+        assert!(almanac.is_ok());
+        assert_eq!(almanac.unwrap().mappings().len(), 7);
+
+        // Categories can be renamed, reordered, and extended as long as seeds reach locations
+        let input = "seeds: 1 5 10\n\
+                     \n\
+                     sprout-to-location map:\n\
+                     100 0 20\n\
+                     \n\
+                     location-to-planet map:\n\
+                     0 0 1\n\
+                     \n\
+                     seed-to-sprout map:\n\
+                     0 5 5";
+        let almanac = input.parse::<Almanac>().unwrap();
+        assert_eq!(almanac.get_seed_locations(), vec![101, 100, 110]);
+        let sources: Vec<&str> = almanac
+            .mappings()
+            .iter()
+            .map(|mapping| mapping.categories.source.as_str())
+            .collect();
+        assert_eq!(sources, ["seed", "sprout"]);
+        assert_eq!(almanac.map_value("location", "planet", 0), Some(0));
+
+        let input = "seeds: 1 5\n\
+                     \n\
+                     seed-to-soil map:\n\
+                     0 5 5";
+        assert_eq!(input.parse::<Almanac>(), Err(ParseAlmanacError));
+    }
+
+    #[test]
+    fn almanac_map_value() {
+        let almanac = TEST_INPUT.parse::<Almanac>().unwrap();
+
+        assert_eq!(almanac.map_value("seed", "location", 79), Some(82));
+        assert_eq!(almanac.map_value("soil", "light", 81), Some(74));
+        assert_eq!(almanac.map_value("water", "temperature", 81), Some(78));
+        assert_eq!(almanac.map_value("humidity", "humidity", 7), Some(7));
+        assert_eq!(almanac.map_value("location", "seed", 82), None);
+        assert_eq!(almanac.map_value("seed", "planet", 79), None);
+    }
+
+    #[test]
+    fn almanac_get_seed_locations() {
+        let almanac = TEST_INPUT.parse::<Almanac>().unwrap();
+        let expected = vec![82, 43, 86, 35];
+
+        assert_eq!(almanac.get_seed_locations(), expected);
+    }
+
+    #[test]
+    fn almanac_get_lowest_location() {
+        let almanac = TEST_INPUT.parse::<Almanac>().unwrap();
+        let expected = 35;
+
+        assert_eq!(almanac.get_lowest_location(), Some(expected));
+    }
+
+    #[test]
+    fn almanac_get_lowest_seed_range_location() {
+        let almanac = TEST_INPUT.parse::<Almanac>().unwrap();
+        let expected = 46;
+
+        assert_eq!(almanac.get_lowest_seed_range_location(), Some(expected));
+    }
+
+    #[test]
+    fn almanac_get_lowest_seed_range_location_by_intervals() {
+        let almanac = TEST_INPUT.parse::<Almanac>().unwrap();
+        let expected = 46;
+
+        assert_eq!(
+            almanac.get_lowest_seed_range_location_by_intervals(),
+            Some(expected)
+        );
+
+        // An odd number of seed values doesn't make whole ranges
+        let almanac = TEST_INPUT
+            .replacen("79 14 55 13", "79 14 55", 1)
+            .parse::<Almanac>()
+            .unwrap();
+        assert_eq!(almanac.get_lowest_seed_range_location_by_intervals(), None);
+    }
+
+    #[test]
+    fn almanac_composed() {
+        let almanac = TEST_INPUT.parse::<Almanac>().unwrap();
+        let composed = almanac.composed();
+
+        for seed in 0..120 {
+            assert_eq!(
+                composed.get_dest_for_src(seed),
+                almanac.get_location_num(seed)
+            );
+        }
+        assert_eq!(composed.get_dest_for_src(u64::MAX - 1), u64::MAX - 1);
+
+        // Segments are sorted, contiguous, and start at 0
+        let segments: Vec<_> = composed.segments().collect();
+        assert_eq!(segments[0].0.start, 0);
+        for pair in segments.windows(2) {
+            assert_eq!(pair[0].0.end, pair[1].0.start);
+        }
+
+        // Neighboring segments never continue the same line
+        for pair in segments.windows(2) {
+            let (range, dest_start) = &pair[0];
+            assert_ne!(dest_start + (range.end - range.start), pair[1].1);
+        }
+
+        assert_eq!(composed.get_lowest_dest(&almanac.seeds), Some(35));
+        let seed_ranges = almanac.get_seed_ranges().unwrap();
+        assert_eq!(composed.get_lowest_dest_in_ranges(&seed_ranges), Some(46));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let almanac = TEST_INPUT.parse::<Almanac>().unwrap();
+        let json = serde_json::to_value(&almanac).unwrap();
+        assert_eq!(json["seeds"], serde_json::json!([79, 14, 55, 13]));
+        assert_eq!(
+            json["mappings"][0],
+            serde_json::json!({
+                "src_ranges": [{"start": 98, "end": 100}, {"start": 50, "end": 98}],
+                "dest_ranges": [{"start": 50, "end": 52}, {"start": 52, "end": 100}],
+                "categories": {"source": "seed", "destination": "soil"},
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<Almanac>(json.clone()).unwrap(),
+            almanac
+        );
+
+        let mut missing_mapping = json.clone();
+        missing_mapping["mappings"].as_array_mut().unwrap().pop();
+        assert!(serde_json::from_value::<Almanac>(missing_mapping).is_err());
+        // A destination range shorter than its source range
+        let mut short_dest = json;
+        short_dest["mappings"][0]["dest_ranges"][0]["end"] = serde_json::json!(51);
+        assert!(serde_json::from_value::<Almanac>(short_dest).is_err());
+
+        let composed = almanac.composed();
+        let json = serde_json::to_string(&composed).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ComposedMapping>(&json).unwrap(),
+            composed
+        );
+        for broken in [
+            r#"{"starts": [], "dest_starts": []}"#,
+            r#"{"starts": [5], "dest_starts": [5]}"#,
+            r#"{"starts": [0, 9, 3], "dest_starts": [0, 9, 3]}"#,
+            r#"{"starts": [0, 9], "dest_starts": [0]}"#,
+        ] {
+            assert!(serde_json::from_str::<ComposedMapping>(broken).is_err());
+        }
+    }
+
+    #[test]
+    fn composed_plot_svg() {
+        let almanac = TEST_INPUT.parse::<Almanac>().unwrap();
+        let composed = almanac.composed();
+        let seed_ranges = almanac.get_seed_ranges().unwrap();
+        let svg = composed.plot_svg(&seed_ranges);
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        // One shaded rect per seed range on top of the background
+        assert_eq!(svg.matches("<rect").count(), 1 + seed_ranges.len());
+        assert!(svg.contains("seed 82 → location 46"));
+
+        // Nothing to mark without highlighted ranges
+        assert!(!composed.plot_svg(&[]).contains("<circle"));
+    }
+
+    #[test]
+    #[cfg(feature = "simd")]
+    fn composed_get_dests_for_srcs() {
+        let composed = TEST_INPUT.parse::<Almanac>().unwrap().composed();
+
+        let srcs: Vec<u64> = (0..125).chain([u64::MAX - 1]).collect();
+        let expected: Vec<u64> = srcs
+            .iter()
+            .map(|&src| composed.get_dest_for_src(src))
+            .collect();
+        assert_eq!(composed.get_dests_for_srcs(&srcs), expected);
+    }
+
+    #[test]
+    fn combine_ranges_test() {
+        let test_data = [
+            (0..10, 5..35, Some(0..35)),
+            (11..86, 3..18, Some(3..86)),
+            (25..100, 0..10, None),
+            (0..10, 2..5, Some(0..10)),
+            (0..10, 10..12, Some(0..12)),
+        ];
+
+        for (range_0, range_1, expected) in test_data {
+            assert_eq!(combine_ranges(&range_0, &range_1), expected);
+        }
+    }
+
+    #[test]
+    fn split_ranges_test() {
+        let test_data = [
+            (vec![0..10], 4, vec![0..4, 4..8, 8..10]),
+            (vec![0..3, 10..12], 5, vec![0..3, 10..12]),
+            (vec![5..5, 20..26], 3, vec![20..23, 23..26]),
+        ];
+
+        for (ranges, max_size, expected) in test_data {
+            assert_eq!(split_ranges(&ranges, max_size), expected);
+        }
+    }
+
+    #[test]
+    fn sum_range_values_test() {
+        let test_data = [(vec![0..10, 5..35], 40), (vec![11..86, 3..18], 90)];
+
+        for (ranges, expected) in test_data {
+            assert_eq!(sum_range_values(&ranges), expected);
+        }
+    }
+
+    #[test]
+    fn condense_ranges_test() {
+        let test_data = [
+            (vec![], vec![]),               // base case
+            (vec![0..3], vec![0..3]),       // single item
+            (vec![0..3, 1..5], vec![0..5]), // one merge
+            (vec![0..3, 1..5, 10..20], vec![0..5, 10..20]),
+            (vec![0..3, 1..5, 10..20, 15..18], vec![0..5, 10..20]),
+            (vec![10..20, 0..3, 3..5, 7..7], vec![0..5, 10..20]), // unsorted, adjacent, empty
+        ];
+
+        for (input_ranges, expected) in test_data {
+            assert_eq!(condense_ranges(&input_ranges), expected);
+        }
+    }
+
+    #[test]
+    fn almanac_get_seed_ranges() {
+        let almanac = Almanac {
+            seeds: vec![79, 14, 55, 13, 60, 30, 5, 0],
+            ..Default::default()
+        };
+
+        assert_eq!(almanac.get_seed_ranges(), Some(vec![55..93]));
+    }
+}
@@ -0,0 +1,327 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use lazy_static::lazy_static;
+use rayon::prelude::*;
+use std::ops::RangeInclusive;
+
+lazy_static! {
+    static ref PROGRESS_STYLE: ProgressStyle =
+        ProgressStyle::with_template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>10}/{len:10}",)
+            .unwrap()
+            .progress_chars("##-");
+}
+
+/// How the numbers on each input line are read
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Kerning {
+    /// Whitespace separates the numbers of different races
+    Spaces,
+    /// Whitespace is ignored, so each line holds a single number
+    Ignored,
+}
+
+/// A single boat race: how long it lasts and the distance to beat
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Race {
+    pub time: u64,
+    pub record_distance: u64,
+}
+
+impl Race {
+    /// Checks whether holding the button for `hold_time` beats the record. Holding it for the
+    /// whole race or longer never does. Returns `None` if the distance traveled overflows.
+    pub fn beats_record(&self, hold_time: u64) -> Option<bool> {
+        let speed = hold_time;
+        let Some(remaining_time) = self.time.checked_sub(hold_time) else {
+            return Some(false);
+        };
+        let distance_traveled = remaining_time.checked_mul(speed)?;
+
+        Some(distance_traveled > self.record_distance)
+    }
+
+    /// Gets the hold times that beat the record. The range is empty if the record can't be beaten.
+    pub fn winning_range(&self) -> RangeInclusive<u64> {
+        let distance = |hold_time: u64| u128::from(hold_time) * u128::from(self.time - hold_time);
+        let record_distance = u128::from(self.record_distance);
+
+        // The distance grows until the halfway point, so the shortest winning hold time can be
+        // binary searched there. The longest one mirrors it.
+        let (mut low, mut high) = (0, self.time / 2);
+        if distance(high) <= record_distance {
+            return RangeInclusive::new(1, 0);
+        }
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if distance(mid) > record_distance {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        low..=(self.time - low)
+    }
+
+    /// Gets the number of hold times that beat the record
+    pub fn margin_of_error(&self) -> u64 {
+        let winning_range = self.winning_range();
+        if winning_range.is_empty() {
+            0
+        } else {
+            winning_range.end() - winning_range.start() + 1
+        }
+    }
+
+    /// Counts the hold times that beat the record by trying every one of them. Returns `None` if
+    /// a distance overflows.
+    pub fn count_winning_hold_times(&self) -> Option<u64> {
+        // Ranges of u64 aren't indexed on every platform, so the progress bar is driven by hand
+        let pb = ProgressBar::new(self.time.saturating_sub(1));
+        pb.set_style(PROGRESS_STYLE.clone());
+        let count = (1..self.time)
+            .into_par_iter()
+            .inspect(|_| pb.inc(1))
+            .map(|hold_time| self.beats_record(hold_time))
+            .try_fold(|| 0, |count, won| Some(count + u64::from(won?)))
+            .try_reduce(|| 0, |count_0, count_1| Some(count_0 + count_1));
+        pb.finish();
+        count
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Races {
+    races: Vec<Race>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseBoatRaceError;
+
+/// The distances traveled in a race, or the product of the winning counts up to it, don't fit in
+/// a [u64]
+#[derive(Debug, Eq, PartialEq)]
+pub struct RaceOverflowError {
+    pub race_id: usize,
+}
+
+impl Races {
+    fn parse_line_by_key(
+        key: &str,
+        line: &str,
+        kerning: Kerning,
+    ) -> Result<Vec<u64>, ParseBoatRaceError> {
+        let (line_key, numbers_str) = line.split_once(':').ok_or(ParseBoatRaceError)?;
+        if line_key != key {
+            return Err(ParseBoatRaceError);
+        }
+
+        match kerning {
+            Kerning::Spaces => numbers_str
+                .split_whitespace()
+                .map(|num_str| num_str.parse::<u64>().or(Err(ParseBoatRaceError)))
+                .collect(),
+            Kerning::Ignored => {
+                let number_str: String = numbers_str.split_whitespace().collect();
+                Ok(vec![number_str.parse().or(Err(ParseBoatRaceError))?])
+            }
+        }
+    }
+
+    pub fn parse(s: &str, kerning: Kerning) -> Result<Self, ParseBoatRaceError> {
+        let mut lines = s.lines();
+
+        let times =
+            Races::parse_line_by_key("Time", lines.next().ok_or(ParseBoatRaceError)?, kerning)?;
+        let distances =
+            Races::parse_line_by_key("Distance", lines.next().ok_or(ParseBoatRaceError)?, kerning)?;
+
+        if times.len() != distances.len() {
+            return Err(ParseBoatRaceError);
+        }
+
+        let races = times
+            .into_iter()
+            .zip(distances)
+            .map(|(time, record_distance)| Race {
+                time,
+                record_distance,
+            })
+            .collect();
+        Ok(Self { races })
+    }
+
+    pub fn races(&self) -> &[Race] {
+        &self.races
+    }
+
+    /// Multiplies together the margin of error of every race
+    pub fn margin_of_error(&self) -> Result<u64, RaceOverflowError> {
+        self.multiply_counts(|race| Some(race.margin_of_error()))
+    }
+
+    /// Same as [Races::margin_of_error], but tries every hold time of every race
+    pub fn margin_of_error_brute_force(&self) -> Result<u64, RaceOverflowError> {
+        self.multiply_counts(Race::count_winning_hold_times)
+    }
+
+    fn multiply_counts(
+        &self,
+        count: impl Fn(&Race) -> Option<u64>,
+    ) -> Result<u64, RaceOverflowError> {
+        if self.races.is_empty() {
+            return Ok(0);
+        }
+
+        self.races
+            .iter()
+            .enumerate()
+            .try_fold(1u64, |acc, (race_id, race)| {
+                count(race)
+                    .and_then(|count| acc.checked_mul(count))
+                    .ok_or(RaceOverflowError { race_id })
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "Time:      7  15   30\n\
+                              Distance:  9  40  200";
+
+    fn race(time: u64, record_distance: u64) -> Race {
+        Race {
+            time,
+            record_distance,
+        }
+    }
+
+    #[test]
+    fn parse_races_with_spaces() {
+        let expected = Races {
+            races: vec![race(7, 9), race(15, 40), race(30, 200)],
+        };
+
+        assert_eq!(Races::parse(TEST_INPUT, Kerning::Spaces), Ok(expected));
+
+        assert_eq!(
+            Races::parse("Timmy: 0 1 3", Kerning::Spaces),
+            Err(ParseBoatRaceError)
+        );
+        assert_eq!(
+            Races::parse("Time: 7 15\nDistance: 9", Kerning::Spaces),
+            Err(ParseBoatRaceError)
+        );
+    }
+
+    #[test]
+    fn parse_races_ignoring_kerning() {
+        let expected = Races {
+            races: vec![race(71_530, 940_200)],
+        };
+
+        assert_eq!(Races::parse(TEST_INPUT, Kerning::Ignored), Ok(expected));
+
+        assert_eq!(
+            Races::parse("Time: 03\nDistance: 22q", Kerning::Ignored),
+            Err(ParseBoatRaceError)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let races = Races::parse(TEST_INPUT, Kerning::Spaces).unwrap();
+        let json = serde_json::to_value(&races).unwrap();
+        assert_eq!(
+            json["races"][0],
+            serde_json::json!({"time": 7, "record_distance": 9})
+        );
+        assert_eq!(serde_json::from_value::<Races>(json).unwrap(), races);
+    }
+
+    #[test]
+    fn race_beats_record() {
+        let test_data = [(0, false), (1, false), (2, true), (6, false), (7, false)];
+
+        for (hold_time, expected) in test_data {
+            assert_eq!(race(7, 9).beats_record(hold_time), Some(expected));
+        }
+
+        assert_eq!(race(u64::MAX, 0).beats_record(u64::MAX / 2), None);
+    }
+
+    #[test]
+    fn race_beats_record_past_the_end() {
+        // The race is over before the boat is let go
+        assert_eq!(race(7, 9).beats_record(8), Some(false));
+        assert_eq!(race(7, 0).beats_record(u64::MAX), Some(false));
+        assert_eq!(race(0, 0).beats_record(1), Some(false));
+    }
+
+    #[test]
+    fn race_winning_range() {
+        assert_eq!(race(7, 9).winning_range(), 2..=5);
+        assert_eq!(race(15, 40).winning_range(), 4..=11);
+        assert_eq!(race(30, 200).winning_range(), 11..=19);
+        assert_eq!(race(71_530, 940_200).winning_range(), 14..=71_516);
+        assert!(race(4, 4).winning_range().is_empty());
+        assert!(race(0, 0).winning_range().is_empty());
+
+        // Matches trying every hold time
+        for time in 0..40 {
+            for record_distance in 0..400 {
+                let race = race(time, record_distance);
+                let expected = (0..=time)
+                    .filter(|&hold_time| race.beats_record(hold_time).unwrap())
+                    .count() as u64;
+                assert_eq!(race.margin_of_error(), expected, "{race:?}");
+            }
+        }
+
+        // Distances past u64::MAX don't overflow
+        assert_eq!(race(u64::MAX, u64::MAX).winning_range(), 2..=(u64::MAX - 2));
+    }
+
+    #[test]
+    fn race_margin_of_error() {
+        let test_data = [(race(7, 9), 4), (race(15, 40), 8), (race(30, 200), 9)];
+
+        for (race, expected) in test_data {
+            assert_eq!(race.margin_of_error(), expected);
+            assert_eq!(race.count_winning_hold_times(), Some(expected));
+        }
+        assert_eq!(race(4, 4).margin_of_error(), 0);
+    }
+
+    #[test]
+    fn races_margin_of_error() {
+        let races = Races::parse(TEST_INPUT, Kerning::Spaces).unwrap();
+        assert_eq!(races.margin_of_error(), Ok(288));
+        assert_eq!(races.margin_of_error_brute_force(), Ok(288));
+
+        let races = Races::parse(TEST_INPUT, Kerning::Ignored).unwrap();
+        assert_eq!(races.margin_of_error(), Ok(71_503));
+        assert_eq!(races.margin_of_error_brute_force(), Ok(71_503));
+    }
+
+    #[test]
+    fn races_margin_of_error_overflow() {
+        // 4 winning hold times per race, so 32 races multiply out to 2^64
+        let races = Races {
+            races: vec![race(7, 9); 32],
+        };
+        assert_eq!(
+            races.margin_of_error(),
+            Err(RaceOverflowError { race_id: 31 })
+        );
+
+        let races = Races {
+            races: vec![race(7, 9); 31],
+        };
+        assert_eq!(races.margin_of_error(), Ok(1 << 62));
+    }
+}
@@ -1,97 +1,131 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::fs;
-use std::str::FromStr;
+use std::fmt::Write;
+use std::{env, fmt, fs};
 
 #[derive(Debug, Eq, PartialEq)]
 struct ParseHandError;
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
-enum PartOneCard {
-    Ace,
-    King,
-    Queen,
-    Jack,
-    Number(u32),
+/// Which set of rules to play by
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+enum Rules {
+    /// Part 1: J is a jack, ranked between 10 and Q
+    Standard,
+    /// Part 2: J is a joker. It's the weakest card on its own but takes on whatever card makes
+    /// the best hand type.
+    JokerWild,
 }
 
-impl From<PartOneCard> for u32 {
-    fn from(value: PartOneCard) -> Self {
-        match value {
-            PartOneCard::Ace => 14,
-            PartOneCard::King => 13,
-            PartOneCard::Queen => 12,
-            PartOneCard::Jack => 11,
-            PartOneCard::Number(val) => val,
+impl Rules {
+    fn card_value(&self, card: Card) -> u32 {
+        if self.is_wild(card) {
+            1
+        } else {
+            card.into()
         }
     }
-}
 
-impl PartialOrd for PartOneCard {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    fn is_wild(&self, card: Card) -> bool {
+        *self == Self::JokerWild && card == Card::Jack
     }
 }
 
-impl Ord for PartOneCard {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let self_num: u32 = (*self).into();
-        let other_num: u32 = (*other).into();
-
-        self_num.cmp(&other_num)
-    }
+/// Largest hand any [Variant] can deal
+const MAX_HAND_SIZE: usize = 8;
+
+/// The shape of the game being played
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+enum Variant {
+    /// Five card hands from the puzzle's 13 card deck
+    #[default]
+    Puzzle,
+    /// Hands of `hand_size` cards, up to [MAX_HAND_SIZE], from a deck that adds a knight (C)
+    Extended { hand_size: usize },
 }
 
-impl TryFrom<char> for PartOneCard {
-    type Error = ParseHandError;
+impl Variant {
+    fn hand_size(&self) -> usize {
+        match self {
+            Self::Puzzle => 5,
+            Self::Extended { hand_size } => *hand_size,
+        }
+    }
 
-    fn try_from(value: char) -> Result<Self, Self::Error> {
-        match value {
-            'A' => Ok(Self::Ace),
-            'K' => Ok(Self::King),
-            'Q' => Ok(Self::Queen),
-            'J' => Ok(Self::Jack),
-            'T' => Ok(Self::Number(10)),
-            '9' | '8' | '7' | '6' | '5' | '4' | '3' | '2' => {
-                let val = match value.to_digit(10) {
-                    Some(num) => num,
-                    None => return Err(ParseHandError),
-                };
-                Ok(Self::Number(val))
-            }
-            _ => Err(ParseHandError),
+    fn has_card(&self, card: Card) -> bool {
+        match self {
+            Self::Puzzle => card != Card::Knight,
+            Self::Extended { .. } => true,
         }
     }
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
-enum PartTwoCard {
+enum Card {
     Ace,
     King,
     Queen,
+    /// Only found in the extended deck, ranked between the jack and queen
+    Knight,
+    Jack,
     Number(u32),
-    Joker,
 }
 
-impl From<PartTwoCard> for u32 {
-    fn from(value: PartTwoCard) -> Self {
+impl From<Card> for u32 {
+    fn from(value: Card) -> Self {
         match value {
-            PartTwoCard::Ace => 14,
-            PartTwoCard::King => 13,
-            PartTwoCard::Queen => 12,
-            PartTwoCard::Joker => 1,
-            PartTwoCard::Number(val) => val,
+            Card::Ace => 15,
+            Card::King => 14,
+            Card::Queen => 13,
+            Card::Knight => 12,
+            Card::Jack => 11,
+            Card::Number(val) => val,
         }
     }
 }
 
-impl PartialOrd for PartTwoCard {
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Card::Ace => 'A',
+            Card::King => 'K',
+            Card::Queen => 'Q',
+            Card::Knight => 'C',
+            Card::Jack => 'J',
+            Card::Number(10) => 'T',
+            Card::Number(val) => char::from_digit(*val, 10).unwrap_or('?'),
+        };
+        write!(f, "{c}")
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Read back from the same single character it's written as
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let c = char::deserialize(deserializer)?;
+        Card::try_from(c).map_err(|_| de::Error::custom(format!("{c:?} isn't a card")))
+    }
+}
+
+impl PartialOrd for Card {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Ord for PartTwoCard {
+impl Ord for Card {
     fn cmp(&self, other: &Self) -> Ordering {
         let self_num: u32 = (*self).into();
         let other_num: u32 = (*other).into();
@@ -100,7 +134,7 @@ impl Ord for PartTwoCard {
     }
 }
 
-impl TryFrom<char> for PartTwoCard {
+impl TryFrom<char> for Card {
     type Error = ParseHandError;
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
@@ -108,6 +142,8 @@ impl TryFrom<char> for PartTwoCard {
             'A' => Ok(Self::Ace),
             'K' => Ok(Self::King),
             'Q' => Ok(Self::Queen),
+            'C' => Ok(Self::Knight),
+            'J' => Ok(Self::Jack),
             'T' => Ok(Self::Number(10)),
             '9' | '8' | '7' | '6' | '5' | '4' | '3' | '2' => {
                 let val = match value.to_digit(10) {
@@ -116,7 +152,6 @@ impl TryFrom<char> for PartTwoCard {
                 };
                 Ok(Self::Number(val))
             }
-            'J' => Ok(Self::Joker),
             _ => Err(ParseHandError),
         }
     }
@@ -131,22 +166,92 @@ enum HandType {
     TwoPair,
     OnePair,
     HighCard,
+    /// A hand that isn't five cards long, described by the sizes of its groups of matching cards,
+    /// largest first and padded with zeros
+    Groups([u8; MAX_HAND_SIZE]),
 }
 
-impl From<HandType> for u32 {
-    fn from(value: HandType) -> Self {
-        match value {
-            HandType::FiveOfAKind => 6,
-            HandType::FourOfAKind => 5,
-            HandType::FullHouse => 4,
-            HandType::ThreeOfAKind => 3,
-            HandType::TwoPair => 2,
-            HandType::OnePair => 1,
-            HandType::HighCard => 0,
+impl HandType {
+    /// Finds the best type for the hand. Wild cards join whichever group of cards is already
+    /// largest.
+    fn new(cards: &[Card], rules: Rules) -> Self {
+        let mut card_map: HashMap<Card, u8> = HashMap::new();
+        let mut joker_count = 0;
+        for card in cards {
+            if rules.is_wild(*card) {
+                joker_count += 1;
+            } else {
+                *card_map.entry(*card).or_insert(0) += 1;
+            }
+        }
+
+        let mut counts: Vec<u8> = card_map.into_values().collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        match counts.first_mut() {
+            Some(max_count) => *max_count += joker_count,
+            // Nothing but jokers
+            None => counts.push(joker_count),
+        }
+
+        match counts[..] {
+            [5] => Self::FiveOfAKind,
+            [4, 1] => Self::FourOfAKind,
+            [3, 2] => Self::FullHouse,
+            [3, 1, 1] => Self::ThreeOfAKind,
+            [2, 2, 1] => Self::TwoPair,
+            [2, 1, 1, 1] => Self::OnePair,
+            [1, 1, 1, 1, 1] => Self::HighCard,
+            _ => {
+                let mut groups = [0; MAX_HAND_SIZE];
+                groups[..counts.len()].copy_from_slice(&counts);
+                Self::Groups(groups)
+            }
+        }
+    }
+
+    /// Gets the sizes of the groups of matching cards, largest first and padded with zeros
+    fn group_sizes(&self) -> [u8; MAX_HAND_SIZE] {
+        let named: &[u8] = match self {
+            Self::FiveOfAKind => &[5],
+            Self::FourOfAKind => &[4, 1],
+            Self::FullHouse => &[3, 2],
+            Self::ThreeOfAKind => &[3, 1, 1],
+            Self::TwoPair => &[2, 2, 1],
+            Self::OnePair => &[2, 1, 1, 1],
+            Self::HighCard => &[1, 1, 1, 1, 1],
+            Self::Groups(groups) => return *groups,
+        };
+        let mut groups = [0; MAX_HAND_SIZE];
+        groups[..named.len()].copy_from_slice(named);
+        groups
+    }
+}
+
+impl fmt::Display for HandType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Groups(groups) => {
+                let sizes: Vec<String> = groups
+                    .iter()
+                    .take_while(|&&size| size > 0)
+                    .map(|size| size.to_string())
+                    .collect();
+                f.pad(&sizes.join("-"))
+            }
+            _ => f.pad(&format!("{self:?}")),
         }
     }
 }
 
+/// Written out the same way as [fmt::Display], so five card hands use their names and others use
+/// their group sizes
+#[cfg(feature = "serde")]
+impl Serialize for HandType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
 impl PartialOrd<Self> for HandType {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -154,91 +259,115 @@ impl PartialOrd<Self> for HandType {
 }
 
 impl Ord for HandType {
+    /// Hands with bigger groups win. For five cards this gives the puzzle's order, from five of a
+    /// kind down to high card.
     fn cmp(&self, other: &Self) -> Ordering {
-        let self_num: u32 = (*self).into();
-        let other_num: u32 = (*other).into();
-
-        self_num.cmp(&other_num)
-    }
-}
-
-impl HandType {
-    fn new(cards: &[PartOneCard; 5]) -> Self {
-        let mut card_map: HashMap<PartOneCard, u32> = HashMap::new();
-        for card in cards {
-            match card_map.get_mut(card) {
-                Some(count) => *count += 1,
-                None => {
-                    let _ = card_map.insert(*card, 1);
-                }
-            }
-        }
-
-        return match card_map.len() {
-            1 => {
-                // Must be 5 of a kind since they're all the same
-                Self::FiveOfAKind
-            }
-            2 => {
-                // Can either be four of a kind or a full house
-                let max_count = card_map.values().copied().max().unwrap();
-                if max_count == 4 {
-                    Self::FourOfAKind
-                } else {
-                    Self::FullHouse
-                }
-            }
-            3 => {
-                // Can either be three of a kind or two pairs
-                let max_count = card_map.values().copied().max().unwrap();
-                if max_count == 3 {
-                    Self::ThreeOfAKind
-                } else {
-                    Self::TwoPair
-                }
-            }
-            4 => Self::OnePair,
-            _ => Self::HighCard,
-        };
+        self.group_sizes().cmp(&other.group_sizes())
     }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "HandData"))]
 struct Hand {
-    cards: [PartOneCard; 5],
+    cards: Vec<Card>,
     hand_type: HandType,
     bet: u64,
+    rules: Rules,
+    /// The hand type and card values packed into one number that sorts the same way as the hands
+    #[cfg_attr(feature = "serde", serde(skip))]
+    sort_key: u64,
+}
+
+/// The parts of a [Hand] that are read back from its serialized form. The hand type and sort key
+/// are worked out again from the cards, so they can't disagree with them.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct HandData {
+    cards: Vec<Card>,
+    bet: u64,
+    rules: Rules,
 }
 
-impl FromStr for Hand {
-    type Err = ParseHandError;
+#[cfg(feature = "serde")]
+impl From<HandData> for Hand {
+    fn from(data: HandData) -> Self {
+        Hand::new(data.cards, data.bet, data.rules)
+    }
+}
+
+/// A hand's place in the final ranking
+#[cfg(feature = "serde")]
+#[derive(Debug, Eq, PartialEq, Serialize)]
+struct RankedHand<'a> {
+    rank: u64,
+    #[serde(flatten)]
+    hand: &'a Hand,
+    winnings: u64,
+}
+
+/// Every hand in rank order along with the total winnings
+#[cfg(feature = "serde")]
+#[derive(Debug, Eq, PartialEq, Serialize)]
+struct Ranking<'a> {
+    rules: Rules,
+    hands: Vec<RankedHand<'a>>,
+    total_winnings: u64,
+}
+
+impl Hand {
+    fn parse(s: &str, rules: Rules, variant: Variant) -> Result<Self, ParseHandError> {
+        let hand_size = variant.hand_size();
+        if !(1..=MAX_HAND_SIZE).contains(&hand_size) {
+            return Err(ParseHandError);
+        }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut split = s.split_whitespace();
 
         // Parse cards
         let cards_str = split.next().ok_or(ParseHandError)?;
-        if cards_str.len() < 5 {
+        let cards = cards_str
+            .chars()
+            .map(|c| match Card::try_from(c) {
+                Ok(card) if variant.has_card(card) => Ok(card),
+                _ => Err(ParseHandError),
+            })
+            .collect::<Result<Vec<Card>, ParseHandError>>()?;
+        if cards.len() != hand_size {
             return Err(ParseHandError);
         }
 
-        let mut cards = [PartOneCard::Ace; 5];
-        for (i, c) in cards_str.chars().take(5).enumerate() {
-            let card: PartOneCard = c.try_into()?;
-            cards[i] = card;
-        }
-
         // Parse bet
         let bet_str = split.next().ok_or(ParseHandError)?;
         let bet = bet_str.parse::<u64>().or(Err(ParseHandError))?;
 
-        // Determine HandType
-        let hand_type = HandType::new(&cards);
+        Ok(Self::new(cards, bet, rules))
+    }
+
+    /// Works out the hand type and sort key of `cards` under `rules`
+    fn new(cards: Vec<Card>, bet: u64, rules: Rules) -> Self {
+        let hand_type = HandType::new(&cards, rules);
+        let sort_key = Hand::pack_sort_key(&cards, hand_type, rules);
 
-        Ok(Self {
+        Self {
             cards,
             hand_type,
             bet,
+            rules,
+            sort_key,
+        }
+    }
+
+    /// Packs the hand type's group sizes, 4 bits each, above the card values, also 4 bits each and
+    /// in hand order. Comparing keys then gives the same result as comparing the hand
+    /// types first and the cards one by one after.
+    fn pack_sort_key(cards: &[Card], hand_type: HandType, rules: Rules) -> u64 {
+        let type_key = hand_type
+            .group_sizes()
+            .iter()
+            .fold(0u64, |key, &size| (key << 4) | u64::from(size));
+        cards.iter().fold(type_key, |key, card| {
+            (key << 4) | u64::from(rules.card_value(*card))
         })
     }
 }
@@ -251,25 +380,77 @@ impl PartialOrd for Hand {
 
 impl Ord for Hand {
     fn cmp(&self, other: &Self) -> Ordering {
-        // Order by HandTypes if they aren't the same
-        if self.hand_type != other.hand_type {
-            return self.hand_type.cmp(&other.hand_type);
-        }
+        self.sort_key.cmp(&other.sort_key)
+    }
+}
+
+impl Hand {
+    /// Gets the position of the first card that differs between the two hands, which decides the
+    /// order of hands with the same type
+    fn tie_break_position(&self, other: &Self) -> Option<usize> {
+        self.cards
+            .iter()
+            .zip(&other.cards)
+            .position(|(card, other_card)| card != other_card)
+    }
+
+    fn cards_str(&self) -> String {
+        self.cards.iter().map(|card| card.to_string()).collect()
+    }
 
-        for (i, card) in self.cards.iter().enumerate() {
-            let other_card = &other.cards[i];
-            if card != other_card {
-                return (*card).cmp(other_card);
+    /// Sorts the hands and describes each one's type, how it was ordered against the hand ranked
+    /// just below it, and what it adds to the winnings
+    fn explain_winnings(hands: &mut [Hand]) -> String {
+        Hand::sort(hands);
+
+        let mut explanation = String::new();
+        for (i, hand) in hands.iter().enumerate() {
+            let rank = (i + 1) as u64;
+            write!(
+                explanation,
+                "rank {rank:>4}: {} {:<12} bet {:>4} × {rank:>4} = {:>7}",
+                hand.cards_str(),
+                hand.hand_type,
+                hand.bet,
+                hand.bet * rank,
+            )
+            .unwrap();
+
+            if let Some(lower) = i.checked_sub(1).map(|lower| &hands[lower]) {
+                if lower.hand_type == hand.hand_type {
+                    match hand.tie_break_position(lower) {
+                        Some(pos) => write!(
+                            explanation,
+                            "  beats {} on card {}: {} > {}",
+                            lower.cards_str(),
+                            pos + 1,
+                            hand.cards[pos],
+                            lower.cards[pos],
+                        ),
+                        None => write!(explanation, "  ties with {}", lower.cards_str()),
+                    }
+                    .unwrap();
+                }
             }
+            explanation.push('\n');
         }
+        explanation
+    }
 
-        Ordering::Equal
+    /// Puts the hands in rank order, weakest first
+    #[cfg(not(feature = "parallel"))]
+    fn sort(hands: &mut [Hand]) {
+        hands.sort_unstable_by_key(|hand| hand.sort_key);
+    }
+
+    /// Puts the hands in rank order, weakest first
+    #[cfg(feature = "parallel")]
+    fn sort(hands: &mut [Hand]) {
+        hands.par_sort_unstable_by_key(|hand| hand.sort_key);
     }
-}
 
-impl Hand {
     fn calculate_winnings(hands: &mut [Hand]) -> u64 {
-        hands.sort();
+        Hand::sort(hands);
         hands
             .iter()
             .enumerate()
@@ -279,30 +460,107 @@ impl Hand {
             })
             .sum()
     }
+
+    /// Sorts the hands and lists each one with its rank and winnings
+    #[cfg(feature = "serde")]
+    fn rank(hands: &mut [Hand], rules: Rules) -> Ranking<'_> {
+        Hand::sort(hands);
+        let hands: Vec<RankedHand> = hands
+            .iter()
+            .enumerate()
+            .map(|(i, hand)| {
+                let rank = (i + 1) as u64;
+                RankedHand {
+                    rank,
+                    hand,
+                    winnings: hand.bet * rank,
+                }
+            })
+            .collect();
+        let total_winnings = hands.iter().map(|ranked| ranked.winnings).sum();
+
+        Ranking {
+            rules,
+            hands,
+            total_winnings,
+        }
+    }
 }
 
-fn main() {
-    let mut hands = fs::read_to_string("input.txt")
-        .expect("failed to open input file")
+/// Parses every valid hand in `input`
+fn parse_hands(input: &str, rules: Rules, variant: Variant) -> Vec<Hand> {
+    input
         .lines()
-        .filter_map(|line| line.parse().ok())
-        .collect::<Vec<Hand>>();
+        .filter_map(|line| Hand::parse(line, rules, variant).ok())
+        .collect()
+}
 
-    let winnings = Hand::calculate_winnings(&mut hands);
+#[cfg(feature = "serde")]
+fn print_json_rankings(input: &str, variant: Variant) {
+    let mut part_1_hands = parse_hands(input, Rules::Standard, variant);
+    let mut part_2_hands = parse_hands(input, Rules::JokerWild, variant);
+    let rankings = [
+        Hand::rank(&mut part_1_hands, Rules::Standard),
+        Hand::rank(&mut part_2_hands, Rules::JokerWild),
+    ];
+    let json = serde_json::to_string_pretty(&rankings).expect("failed to serialize rankings");
+    println!("{json}");
+}
 
-    println!("Part 1 result: {winnings}");
+#[cfg(not(feature = "serde"))]
+fn print_json_rankings(_: &str, _: Variant) {
+    eprintln!("--json needs day_7 to be built with the `serde` feature");
+    std::process::exit(1);
+}
+
+fn main() {
+    let input = fs::read_to_string("input.txt").expect("failed to open input file");
+    let args: Vec<String> = env::args().collect();
+    let verbose = args.iter().any(|arg| arg == "--verbose");
+    let json = args.iter().any(|arg| arg == "--json");
+    let variant = match args.iter().position(|arg| arg == "--hand-size") {
+        Some(i) => Variant::Extended {
+            hand_size: args
+                .get(i + 1)
+                .and_then(|size| size.parse().ok())
+                .expect("--hand-size needs a number of cards"),
+        },
+        None => Variant::Puzzle,
+    };
+
+    if json {
+        print_json_rankings(&input, variant);
+        return;
+    }
+
+    for (part, rules) in [(1, Rules::Standard), (2, Rules::JokerWild)] {
+        let mut hands = parse_hands(&input, rules, variant);
+        if verbose {
+            println!("Part {part} ranking ({rules:?} rules):");
+            print!("{}", Hand::explain_winnings(&mut hands));
+        }
+        let winnings = Hand::calculate_winnings(&mut hands);
+        println!("Part {part} result: {winnings}");
+    }
 }
 
 #[cfg(test)]
 mod test {
     mod card {
-        use crate::{ParseHandError, PartOneCard};
+        use crate::{Card, ParseHandError, Rules};
+
+        #[test]
+        fn display() {
+            let cards = [Card::Ace, Card::Jack, Card::Number(10), Card::Number(2)];
+            let displayed: String = cards.iter().map(|card| card.to_string()).collect();
+            assert_eq!(displayed, "AJT2");
+        }
 
         #[test]
         fn try_parse_from_char() {
             let test_data = [
-                ('A', Ok(PartOneCard::Ace)),
-                ('4', Ok(PartOneCard::Number(4))),
+                ('A', Ok(Card::Ace)),
+                ('4', Ok(Card::Number(4))),
                 ('Z', Err(ParseHandError)),
             ];
 
@@ -310,9 +568,27 @@ mod test {
                 assert_eq!(input.try_into(), expected);
             }
         }
+
+        #[test]
+        fn rules_card_value() {
+            assert!(Card::Jack > Card::Number(10));
+            assert!(
+                Rules::Standard.card_value(Card::Jack)
+                    > Rules::Standard.card_value(Card::Number(10))
+            );
+            assert!(
+                Rules::JokerWild.card_value(Card::Jack)
+                    < Rules::JokerWild.card_value(Card::Number(2))
+            );
+            assert_eq!(Rules::JokerWild.card_value(Card::Queen), 13);
+
+            assert!(Rules::JokerWild.is_wild(Card::Jack));
+            assert!(!Rules::Standard.is_wild(Card::Jack));
+            assert!(!Rules::JokerWild.is_wild(Card::Ace));
+        }
     }
     mod hand {
-        use crate::{Hand, HandType, ParseHandError, PartOneCard};
+        use crate::{parse_hands, Card, Hand, HandType, ParseHandError, Rules, Variant};
 
         const INPUT_DATA: &str = "32T3K 765\n\
                                   T55J5 684\n\
@@ -326,55 +602,232 @@ mod test {
                 (
                     "TTT98 256",
                     Ok(Hand {
-                        cards: [
-                            PartOneCard::Number(10),
-                            PartOneCard::Number(10),
-                            PartOneCard::Number(10),
-                            PartOneCard::Number(9),
-                            PartOneCard::Number(8),
+                        cards: vec![
+                            Card::Number(10),
+                            Card::Number(10),
+                            Card::Number(10),
+                            Card::Number(9),
+                            Card::Number(8),
                         ],
                         hand_type: HandType::ThreeOfAKind,
                         bet: 256,
+                        rules: Rules::Standard,
+                        sort_key: 0x3_1100_000A_AA98,
                     }),
                 ),
                 ("TTTZ9", Err(ParseHandError)),
                 ("3", Err(ParseHandError)),
+                ("TTT98A 256", Err(ParseHandError)),
             ];
 
             for (s, expected) in test_data {
-                assert_eq!(s.parse(), expected);
+                assert_eq!(Hand::parse(s, Rules::Standard, Variant::Puzzle), expected);
             }
+
+            let hand = Hand::parse("KTJJT 220", Rules::JokerWild, Variant::Puzzle).unwrap();
+            assert_eq!(hand.hand_type, HandType::FourOfAKind);
+            assert_eq!(hand.rules, Rules::JokerWild);
         }
 
         #[test]
         fn calculate_winnings() {
-            let mut hands = INPUT_DATA
-                .lines()
-                .filter_map(|line| line.parse::<Hand>().ok())
-                .collect::<Vec<Hand>>();
-            let expected = 6440;
+            let mut hands = parse_hands(INPUT_DATA, Rules::Standard, Variant::Puzzle);
+            assert_eq!(Hand::calculate_winnings(&mut hands), 6440);
+        }
+
+        #[test]
+        fn sort_key_matches_card_order() {
+            let hands = [
+                "23456 1", "2345J 1", "A2345 1", "22345 1", "J2345 1", "KKQQ2 1", "33322 1",
+                "AJJJA 1", "JJJJ2 1", "QQQQQ 1",
+            ];
 
-            assert_eq!(Hand::calculate_winnings(&mut hands), expected);
+            for rules in [Rules::Standard, Rules::JokerWild] {
+                let hands: Vec<Hand> = hands
+                    .iter()
+                    .map(|hand| Hand::parse(hand, rules, Variant::Puzzle).unwrap())
+                    .collect();
+                for hand in &hands {
+                    for other in &hands {
+                        let by_cards = hand.hand_type.cmp(&other.hand_type).then_with(|| {
+                            let values = |hand: &Hand| -> Vec<u32> {
+                                hand.cards
+                                    .iter()
+                                    .map(|card| rules.card_value(*card))
+                                    .collect()
+                            };
+                            values(hand).cmp(&values(other))
+                        });
+                        assert_eq!(hand.cmp(other), by_cards, "{hand:?} vs {other:?}");
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn explain_winnings() {
+            let mut hands = parse_hands(INPUT_DATA, Rules::Standard, Variant::Puzzle);
+            let explanation = Hand::explain_winnings(&mut hands);
+            let lines: Vec<&str> = explanation.lines().collect();
+
+            assert_eq!(lines.len(), 5);
+            assert!(lines[0].starts_with("rank    1: 32T3K OnePair      bet  765 ×    1 =     765"));
+            assert!(lines[2].ends_with("beats KTJJT on card 2: K > T"));
+            assert!(lines[4].ends_with("beats T55J5 on card 1: Q > T"));
+
+            let total: u64 = lines
+                .iter()
+                .map(|line| {
+                    line.split('=')
+                        .nth(1)
+                        .unwrap()
+                        .split_whitespace()
+                        .next()
+                        .unwrap()
+                })
+                .map(|winnings| winnings.parse::<u64>().unwrap())
+                .sum();
+            assert_eq!(total, 6440);
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serialize_ranking() {
+            let mut hands = parse_hands(INPUT_DATA, Rules::JokerWild, Variant::Puzzle);
+            let ranking = Hand::rank(&mut hands, Rules::JokerWild);
+            assert_eq!(ranking.total_winnings, 5905);
+
+            let json = serde_json::to_value(&ranking).unwrap();
+            assert_eq!(json["rules"], "JokerWild");
+            assert_eq!(json["total_winnings"], 5905);
+            assert_eq!(
+                json["hands"][4],
+                serde_json::json!({
+                    "rank": 5,
+                    "cards": ["K", "T", "J", "J", "T"],
+                    "hand_type": "FourOfAKind",
+                    "bet": 220,
+                    "rules": "JokerWild",
+                    "winnings": 1100,
+                })
+            );
+
+            let hand = Hand::parse(
+                "CCC222 1",
+                Rules::Standard,
+                Variant::Extended { hand_size: 6 },
+            );
+            let hand = hand.unwrap();
+            let json = serde_json::to_value(&hand).unwrap();
+            assert_eq!(json["hand_type"], "3-3");
+            assert_eq!(serde_json::from_value::<Hand>(json).unwrap(), hand);
+
+            let json = serde_json::json!({"cards": ["K", "X"], "bet": 1, "rules": "Standard"});
+            assert!(serde_json::from_value::<Hand>(json).is_err());
+        }
+
+        #[test]
+        fn calculate_winnings_with_jokers() {
+            let mut hands = parse_hands(INPUT_DATA, Rules::JokerWild, Variant::Puzzle);
+            assert_eq!(Hand::calculate_winnings(&mut hands), 5905);
+        }
+    }
+    mod hand_type {
+        use crate::{Hand, HandType, Rules, Variant};
+
+        #[test]
+        fn parse_from_cards() {
+            let test_data = [
+                ("AAAAA 1", HandType::FiveOfAKind),
+                ("AA8AA 1", HandType::FourOfAKind),
+                ("23332 1", HandType::FullHouse),
+                ("TTT98 1", HandType::ThreeOfAKind),
+                ("23432 1", HandType::TwoPair),
+                ("A23A4 1", HandType::OnePair),
+                ("23456 1", HandType::HighCard),
+                ("JJJJ2 1", HandType::FourOfAKind),
+            ];
+
+            for (hand, expected) in test_data {
+                let hand = Hand::parse(hand, Rules::Standard, Variant::Puzzle).unwrap();
+                assert_eq!(HandType::new(&hand.cards, Rules::Standard), expected);
+            }
+        }
+
+        #[test]
+        fn parse_from_cards_with_jokers() {
+            let test_data = [
+                ("JJJJJ 1", HandType::FiveOfAKind),
+                ("JJJJ2 1", HandType::FiveOfAKind),
+                ("QJJQ2 1", HandType::FourOfAKind),
+                ("KTJJT 1", HandType::FourOfAKind),
+                ("2233J 1", HandType::FullHouse),
+                ("T55J5 1", HandType::FourOfAKind),
+                ("A23J4 1", HandType::OnePair),
+                ("A2JJ4 1", HandType::ThreeOfAKind),
+                ("23456 1", HandType::HighCard),
+            ];
+
+            for (hand, expected) in test_data {
+                let hand = Hand::parse(hand, Rules::JokerWild, Variant::Puzzle).unwrap();
+                assert_eq!(HandType::new(&hand.cards, Rules::JokerWild), expected);
+            }
+        }
+
+        #[test]
+        fn parse_from_extended_cards() {
+            let variant = Variant::Extended { hand_size: 6 };
+            let hand_type = |hand: &str, rules| {
+                Hand::parse(hand, rules, variant)
+                    .map(|hand| hand.hand_type.to_string())
+                    .unwrap()
+            };
+
+            assert_eq!(hand_type("CCC222 1", Rules::Standard), "3-3");
+            assert_eq!(hand_type("CCCJ22 1", Rules::JokerWild), "4-2");
+            assert_eq!(hand_type("A2345C 1", Rules::Standard), "1-1-1-1-1-1");
+            assert_eq!(hand_type("JJJJJJ 1", Rules::JokerWild), "6");
+
+            // Five card extended hands still use the puzzle's names
+            let five_cards = Variant::Extended { hand_size: 5 };
+            let hand = Hand::parse("CCJ23 1", Rules::JokerWild, five_cards).unwrap();
+            assert_eq!(hand.hand_type, HandType::ThreeOfAKind);
+            assert!(Hand::parse("CCJ23C 1", Rules::JokerWild, five_cards).is_err());
+        }
+
+        #[test]
+        fn extended_variant_ordering() {
+            let variant = Variant::Extended { hand_size: 6 };
+            let hands: Vec<Hand> = ["CCC222 1", "QQQQ23 1", "222233 1", "QC2345 1", "CQ2345 1"]
+                .iter()
+                .map(|hand| Hand::parse(hand, Rules::Standard, variant).unwrap())
+                .collect();
+
+            assert!(hands[1] < hands[2]);
+            assert!(hands[0] < hands[1]);
+            assert!(hands[4] < hands[3]);
+            assert!(hands[3] < hands[0]);
+        }
+
+        #[test]
+        fn variant_limits() {
+            assert!(Hand::parse("C2345 1", Rules::Standard, Variant::Puzzle).is_err());
+            assert!(Hand::parse("C2345 1", Rules::Standard, Variant::default()).is_err());
+            assert!(Hand::parse(
+                "23456 1",
+                Rules::Standard,
+                Variant::Extended { hand_size: 6 }
+            )
+            .is_err());
+            assert!(
+                Hand::parse("23 1", Rules::Standard, Variant::Extended { hand_size: 0 }).is_err()
+            );
+            assert!(Hand::parse(
+                "234567892 1",
+                Rules::Standard,
+                Variant::Extended { hand_size: 9 }
+            )
+            .is_err());
         }
     }
-    // mod hand_type {
-    //     use crate::{Hand, HandType};
-    //
-    //     #[test]
-    //     fn parse_from_cards() {
-    //         let test_data = [
-    //             ("AAAAA".parse::<Hand>().unwrap(), HandType::FiveOfAKind),
-    //             ("AA8AA".parse().unwrap(), HandType::FourOfAKind),
-    //             ("23332".parse().unwrap(), HandType::FullHouse),
-    //             ("TTT98".parse().unwrap(), HandType::ThreeOfAKind),
-    //             ("23432".parse().unwrap(), HandType::TwoPair),
-    //             ("A23A4".parse().unwrap(), HandType::OnePair),
-    //             ("23456".parse().unwrap(), HandType::HighCard),
-    //         ];
-    //
-    //         for (hand, expected) in test_data {
-    //             assert_eq!(HandType::new(&hand), expected);
-    //         }
-    //     }
-    // }
 }
@@ -1,10 +1,12 @@
 use indicatif::ParallelProgressIterator;
 use rayon::prelude::*;
 use std::collections::HashMap;
-use std::fs;
+use std::fmt::{self, Display, Formatter};
 use std::str::FromStr;
+use std::{env, fs};
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum Direction {
     Right,
     Left,
@@ -26,6 +28,15 @@ impl TryFrom<char> for Direction {
     }
 }
 
+impl From<Direction> for char {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+        }
+    }
+}
+
 impl Direction {
     fn parse_directions(line: &str) -> Vec<Direction> {
         line.chars().filter_map(|c| c.try_into().ok()).collect()
@@ -71,11 +82,22 @@ impl FromStr for Node {
     }
 }
 
+/// The network of nodes, with every node name interned to a `u16` ID at parse time
 #[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 struct NodeMap {
     directions: Vec<Direction>,
-    nodes: HashMap<String, Node>,
-    start_keys: Vec<String>,
+    /// Node names, indexed by ID
+    names: Vec<String>,
+    /// Looks up a node's ID by name
+    #[cfg_attr(feature = "serde", serde(skip))]
+    ids: HashMap<String, u16>,
+    /// ID of the node to the left of each node
+    lefts: Vec<u16>,
+    /// ID of the node to the right of each node
+    rights: Vec<u16>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    start_ids: Vec<u16>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -96,39 +118,147 @@ impl FromStr for NodeMap {
 
         // Skip the empty line and begin processing Nodes
         let _ = lines.next().ok_or(ParseNodeMapError)?;
-        let mut start_keys = vec![];
-        let mut node_map = HashMap::new();
-        for line in lines {
-            let node = line.parse::<Node>().or(Err(ParseNodeMapError))?;
-            let node_name = node.name.clone();
-            node_map.insert(node_name.clone(), node);
-            if node_name.ends_with('A') {
-                start_keys.push(node_name);
-            }
+        let nodes = lines
+            .map(|line| line.parse::<Node>().or(Err(ParseNodeMapError)))
+            .collect::<Result<Vec<Node>, ParseNodeMapError>>()?;
+        if nodes.len() > usize::from(u16::MAX) + 1 {
+            return Err(ParseNodeMapError);
         }
 
-        Ok(Self {
+        // Give every node an ID, then point the left and right sides at them
+        let ids: HashMap<String, u16> = nodes
+            .iter()
+            .enumerate()
+            .map(|(id, node)| (node.name.clone(), id as u16))
+            .collect();
+        let get_id = |name: &String| ids.get(name).copied().ok_or(ParseNodeMapError);
+        let lefts = nodes
+            .iter()
+            .map(|node| get_id(&node.left))
+            .collect::<Result<Vec<u16>, ParseNodeMapError>>()?;
+        let rights = nodes
+            .iter()
+            .map(|node| get_id(&node.right))
+            .collect::<Result<Vec<u16>, ParseNodeMapError>>()?;
+
+        let names: Vec<String> = nodes.into_iter().map(|node| node.name).collect();
+        Ok(Self::new(directions, names, lefts, rights))
+    }
+}
+
+/// Reads back the directions, names and links, checking the links and looking up the IDs and
+/// starting nodes again instead of trusting them
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for NodeMap {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct NodeMapData {
+            directions: Vec<Direction>,
+            names: Vec<String>,
+            lefts: Vec<u16>,
+            rights: Vec<u16>,
+        }
+
+        let NodeMapData {
             directions,
-            nodes: node_map,
-            start_keys,
-        })
+            names,
+            lefts,
+            rights,
+        } = NodeMapData::deserialize(deserializer)?;
+        if directions.is_empty() {
+            return Err(serde::de::Error::custom("the map has no directions"));
+        }
+        if names.len() > usize::from(u16::MAX) + 1 {
+            return Err(serde::de::Error::custom(format!(
+                "{} nodes don't all fit a u16 ID",
+                names.len()
+            )));
+        }
+        if lefts.len() != names.len() || rights.len() != names.len() {
+            return Err(serde::de::Error::custom(format!(
+                "{} nodes have {} lefts and {} rights",
+                names.len(),
+                lefts.len(),
+                rights.len()
+            )));
+        }
+        if let Some(id) = lefts
+            .iter()
+            .chain(&rights)
+            .find(|&&id| usize::from(id) >= names.len())
+        {
+            return Err(serde::de::Error::custom(format!(
+                "node {id} is past the last of the {} nodes",
+                names.len()
+            )));
+        }
+        let mut seen = std::collections::HashSet::new();
+        if let Some(name) = names.iter().find(|&name| !seen.insert(name)) {
+            return Err(serde::de::Error::custom(format!(
+                "there's more than one node named {name}"
+            )));
+        }
+        Ok(Self::new(directions, names, lefts, rights))
     }
 }
 
 impl NodeMap {
+    /// Looks up every node's ID by name and finds the starting nodes. Every ID in `lefts` and
+    /// `rights` has to be below the number of `names`.
+    fn new(
+        directions: Vec<Direction>,
+        names: Vec<String>,
+        lefts: Vec<u16>,
+        rights: Vec<u16>,
+    ) -> Self {
+        let ids = names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| (name.clone(), id as u16))
+            .collect();
+        let start_ids = (0..names.len() as u16)
+            .filter(|&id| names[usize::from(id)].ends_with('A'))
+            .collect();
+
+        Self {
+            directions,
+            names,
+            ids,
+            lefts,
+            rights,
+            start_ids,
+        }
+    }
+
+    fn id(&self, name: &str) -> Option<u16> {
+        self.ids.get(name).copied()
+    }
+
+    fn next_id(&self, id: u16, direction: Direction) -> u16 {
+        match direction {
+            Direction::Left => self.lefts[usize::from(id)],
+            Direction::Right => self.rights[usize::from(id)],
+        }
+    }
+
+    fn is_target(&self, id: u16, target_pattern: &str) -> bool {
+        self.names[usize::from(id)].ends_with(target_pattern)
+    }
+
     fn count_steps(&self, start_key: &str, target_pattern: &str) -> usize {
-        let mut node = self.nodes.get(start_key).unwrap();
+        self.count_steps_from(self.id(start_key).unwrap(), target_pattern)
+    }
+
+    fn count_steps_from(&self, start_id: u16, target_pattern: &str) -> usize {
+        let mut id = start_id;
 
         self.directions
             .iter()
             .cycle()
             .enumerate()
             .find_map(|(i, direction)| {
-                node = match direction {
-                    Direction::Left => self.nodes.get(&node.left).unwrap(),
-                    Direction::Right => self.nodes.get(&node.right).unwrap(),
-                };
-                if node.name.ends_with(target_pattern) {
+                id = self.next_id(id, *direction);
+                if self.is_target(id, target_pattern) {
                     Some(i + 1)
                 } else {
                     None
@@ -137,18 +267,409 @@ impl NodeMap {
             .unwrap_or(0)
     }
 
+    /// Follows the directions from `start_id` forever, yielding the direction taken and the ID of
+    /// the node reached at each step
+    fn walk(&self, start_id: u16) -> impl Iterator<Item = (Direction, u16)> + '_ {
+        let mut id = start_id;
+        self.directions.iter().cycle().map(move |&direction| {
+            id = self.next_id(id, direction);
+            (direction, id)
+        })
+    }
+
+    /// Lists the nodes visited from `start_key`, one step per line, for at most `max_steps`
+    /// steps. If `break_pattern` is given, the trace stops at the first node whose name ends with
+    /// it. Returns `None` if there is no node named `start_key`.
+    fn trace(
+        &self,
+        start_key: &str,
+        max_steps: usize,
+        break_pattern: Option<&str>,
+    ) -> Option<String> {
+        let start_id = self.id(start_key)?;
+        let mut trace = format!("{:>8}     {start_key}\n", 0);
+
+        for (i, (direction, id)) in self.walk(start_id).take(max_steps).enumerate() {
+            let name = &self.names[usize::from(id)];
+            trace.push_str(&format!("{:>8}  {} {name}", i + 1, char::from(direction)));
+            if break_pattern.is_some_and(|pattern| name.ends_with(pattern)) {
+                trace.push_str("  <- breakpoint\n");
+                return Some(trace);
+            }
+            trace.push('\n');
+        }
+        Some(trace)
+    }
+
+    /// Walks from `start_id` until the combination of node and position in the directions
+    /// repeats, noting every step that lands on a node ending with `target_pattern` along the way
+    fn find_cycle(&self, start_id: u16, target_pattern: &str) -> GhostCycle {
+        let direction_count = self.directions.len();
+        // First step each (node, direction index) state was reached, indexed by
+        // `id * direction_count + direction index`
+        let mut seen = vec![usize::MAX; self.names.len() * direction_count];
+        let mut hits = vec![];
+        let mut id = start_id;
+
+        for step in 0.. {
+            let state = usize::from(id) * direction_count + step % direction_count;
+            if seen[state] != usize::MAX {
+                let cycle_start = seen[state];
+                let (lead_in_hits, cycle_hits) =
+                    hits.iter().partition(|&&hit_step| hit_step < cycle_start);
+                return GhostCycle {
+                    start: cycle_start,
+                    length: step - cycle_start,
+                    lead_in_hits,
+                    cycle_hits,
+                };
+            }
+            seen[state] = step;
+            if step > 0 && self.is_target(id, target_pattern) {
+                hits.push(step);
+            }
+
+            id = self.next_id(id, self.directions[step % direction_count]);
+        }
+        unreachable!("every walk eventually repeats")
+    }
+
+    /// Describes the walk of the ghost starting at each start node, checking whether it meets the
+    /// assumptions behind [NodeMap::lcm_of_steps]
+    fn ghost_stats(&self, target_pattern: &str) -> Vec<GhostStats> {
+        self.start_ids
+            .par_iter()
+            .map(|&start_id| {
+                let cycle = self.find_cycle(start_id, target_pattern);
+                let mut targets: Vec<u16> = self
+                    .walk(start_id)
+                    .take(cycle.start + cycle.length)
+                    .map(|(_, id)| id)
+                    .filter(|&id| self.is_target(id, target_pattern))
+                    .collect();
+                targets.sort_unstable();
+                targets.dedup();
+
+                GhostStats {
+                    start: self.names[usize::from(start_id)].clone(),
+                    first_hit: cycle.first_hit(),
+                    cycle_length: cycle.length,
+                    distinct_targets: targets.len(),
+                    lcm_valid: cycle.hits_only_at_multiples_of_first(),
+                }
+            })
+            .collect()
+    }
+
+    /// Finds the first step where every ghost stands on a node ending with `target_pattern`.
+    ///
+    /// Each ghost's walk is split into a lead-in and a repeating cycle. Target hits in the
+    /// lead-ins are checked directly, and the hits inside the cycles are combined with the
+    /// Chinese remainder theorem. Returns `None` if the ghosts never line up.
+    fn steps_until_all_on_target(&self, target_pattern: &str) -> Option<usize> {
+        let cycles: Vec<GhostCycle> = self
+            .start_ids
+            .par_iter()
+            .progress()
+            .map(|&id| self.find_cycle(id, target_pattern))
+            .collect();
+        let first = cycles.first()?;
+
+        // Before every ghost has reached its cycle, only the first ghost's hits are candidates
+        let lead_in_end = cycles.iter().map(|cycle| cycle.start).max().unwrap();
+        let lead_in_candidates = first
+            .lead_in_hits
+            .iter()
+            .copied()
+            .chain(
+                first
+                    .cycle_hits
+                    .iter()
+                    .flat_map(|&hit| (hit..lead_in_end).step_by(first.length)),
+            )
+            .filter(|&step| step < lead_in_end);
+        let lead_in_answer = lead_in_candidates
+            .filter(|&step| cycles.iter().all(|cycle| cycle.hits_at(step)))
+            .min();
+        if lead_in_answer.is_some() {
+            return lead_in_answer;
+        }
+
+        // Afterwards every ghost repeats, so each one limits the step to a set of remainders
+        let congruences = cycles.iter().fold(vec![(0, 1)], |congruences, cycle| {
+            congruences
+                .iter()
+                .flat_map(|&congruence| {
+                    cycle.cycle_hits.iter().filter_map(move |&hit| {
+                        combine_congruences(congruence, (hit as i128, cycle.length as i128))
+                    })
+                })
+                .collect::<Vec<(i128, i128)>>()
+        });
+
+        congruences
+            .iter()
+            .map(|&(remainder, modulus)| {
+                // Smallest step at or after the lead-ins with the right remainder
+                let lead_in_end = lead_in_end as i128;
+                let offset = (remainder - lead_in_end).rem_euclid(modulus);
+                (lead_in_end + offset) as usize
+            })
+            .min()
+    }
+
+    /// Finds the first step where every ghost stands on a node ending with `target_pattern` by
+    /// moving them all together, one step at a time, for at most `max_steps` steps.
+    ///
+    /// This makes no assumptions about the shape of the walks, so it can check
+    /// [NodeMap::steps_until_all_on_target] on small inputs. Ghosts that meet on the same node
+    /// walk together from then on, so the state is just the set of occupied nodes.
+    fn steps_until_all_on_target_in_lockstep(
+        &self,
+        target_pattern: &str,
+        max_steps: usize,
+    ) -> Option<usize> {
+        if self.start_ids.is_empty() {
+            return None;
+        }
+
+        let mut targets = NodeSet::new(self.names.len());
+        for id in 0..self.names.len() {
+            let id = id as u16;
+            if self.is_target(id, target_pattern) {
+                targets.insert(id);
+            }
+        }
+
+        let mut current = NodeSet::new(self.names.len());
+        for &id in &self.start_ids {
+            current.insert(id);
+        }
+        let mut next = NodeSet::new(self.names.len());
+
+        for (i, &direction) in self.directions.iter().cycle().take(max_steps).enumerate() {
+            next.clear();
+            for id in current.iter() {
+                next.insert(self.next_id(id, direction));
+            }
+            std::mem::swap(&mut current, &mut next);
+
+            if current.is_subset(&targets) {
+                return Some(i + 1);
+            }
+        }
+        None
+    }
+
+    /// Assumes that every ghost loops back to its start right after its first target hit, which
+    /// happens to hold for the puzzle input. [NodeMap::steps_until_all_on_target] doesn't need
+    /// that assumption.
     fn lcm_of_steps(&self, target_pattern: &str) -> usize {
         let step_counts = self
-            .start_keys
+            .start_ids
             .par_iter()
             .progress()
-            .map(|key| self.count_steps(key, target_pattern))
+            .map(|&id| self.count_steps_from(id, target_pattern))
             .collect::<Vec<usize>>();
 
         step_counts.iter().copied().reduce(lcm).unwrap_or(0)
     }
 }
 
+/// Node reached after `2^k` full passes through the directions, for every node and `k`, so the
+/// position after any number of steps can be found with `O(log n)` lookups
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct JumpTable {
+    /// `passes[k][id]` is where a ghost at `id` ends up after `2^k` passes
+    passes: Vec<Vec<u16>>,
+}
+
+impl JumpTable {
+    fn new(node_map: &NodeMap) -> Self {
+        let one_pass: Vec<u16> = (0..node_map.names.len())
+            .map(|id| {
+                node_map
+                    .walk(id as u16)
+                    .take(node_map.directions.len())
+                    .last()
+                    .map_or(id as u16, |(_, id)| id)
+            })
+            .collect();
+
+        let mut passes = vec![one_pass];
+        for _ in 1..usize::BITS {
+            let previous = passes.last().unwrap();
+            let doubled = previous
+                .iter()
+                .map(|&id| previous[usize::from(id)])
+                .collect();
+            passes.push(doubled);
+        }
+        Self { passes }
+    }
+
+    /// Finds the node a ghost starting at `start_id` is on after `steps` steps
+    fn position_after(&self, node_map: &NodeMap, start_id: u16, steps: usize) -> u16 {
+        let pass_count = steps / node_map.directions.len();
+        let mut id = start_id;
+        for (k, pass) in self.passes.iter().enumerate() {
+            if pass_count >> k & 1 == 1 {
+                id = pass[usize::from(id)];
+            }
+        }
+
+        let remaining = steps % node_map.directions.len();
+        node_map
+            .walk(id)
+            .take(remaining)
+            .last()
+            .map_or(id, |(_, id)| id)
+    }
+
+    /// Checks that every ghost is on a node ending with `target_pattern` after `steps` steps
+    fn all_on_target_after(&self, node_map: &NodeMap, target_pattern: &str, steps: usize) -> bool {
+        node_map
+            .start_ids
+            .iter()
+            .all(|&id| node_map.is_target(self.position_after(node_map, id, steps), target_pattern))
+    }
+}
+
+/// How a ghost's walk repeats once the same node comes up at the same point in the directions
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct GhostCycle {
+    /// Step at which the repeating part of the walk begins
+    start: usize,
+    /// Number of steps in one trip around the cycle
+    length: usize,
+    /// Steps before the cycle begins that land on a target node
+    lead_in_hits: Vec<usize>,
+    /// Steps during the first trip around the cycle that land on a target node
+    cycle_hits: Vec<usize>,
+}
+
+impl GhostCycle {
+    fn hits_at(&self, step: usize) -> bool {
+        if step < self.start {
+            return self.lead_in_hits.contains(&step);
+        }
+        let cycle_step = self.start + (step - self.start) % self.length;
+        self.cycle_hits.contains(&cycle_step)
+    }
+
+    fn first_hit(&self) -> Option<usize> {
+        self.lead_in_hits
+            .first()
+            .or(self.cycle_hits.first())
+            .copied()
+    }
+
+    /// Checks that the ghost is on a target exactly at the multiples of its first hit, which is
+    /// what the LCM shortcut relies on. The walk repeats every `length` steps after `start`, so
+    /// checking up to the end of the first trip around the cycle covers every later step too.
+    fn hits_only_at_multiples_of_first(&self) -> bool {
+        let Some(first_hit) = self.first_hit() else {
+            return false;
+        };
+        self.length.is_multiple_of(first_hit)
+            && (1..self.start + self.length)
+                .all(|step| self.hits_at(step) == step.is_multiple_of(first_hit))
+    }
+}
+
+/// Facts about one ghost's walk, as reported by `--stats`
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct GhostStats {
+    start: String,
+    /// Steps until the ghost first reaches a target node
+    first_hit: Option<usize>,
+    cycle_length: usize,
+    /// Number of different target nodes the ghost ever reaches
+    distinct_targets: usize,
+    /// Whether the ghost is on a target exactly at the multiples of `first_hit`
+    lcm_valid: bool,
+}
+
+impl Display for GhostStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let first_hit = match self.first_hit {
+            Some(step) => step.to_string(),
+            None => String::from("never"),
+        };
+        write!(
+            f,
+            "{}  first target: {first_hit:>8}  cycle length: {:>8}  distinct targets: {:>2}  LCM valid: {}",
+            self.start,
+            self.cycle_length,
+            self.distinct_targets,
+            if self.lcm_valid { "yes" } else { "no" }
+        )
+    }
+}
+
+/// A set of node IDs stored as one bit per node
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct NodeSet {
+    words: Vec<u64>,
+}
+
+impl NodeSet {
+    fn new(node_count: usize) -> Self {
+        Self {
+            words: vec![0; node_count.div_ceil(64)],
+        }
+    }
+
+    fn insert(&mut self, id: u16) {
+        let id = usize::from(id);
+        self.words[id / 64] |= 1 << (id % 64);
+    }
+
+    fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    fn is_subset(&self, other: &NodeSet) -> bool {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .all(|(word, other_word)| word & !other_word == 0)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = u16> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| (i * 64 + bit) as u16)
+        })
+    }
+}
+
+/// Finds `(g, x, y)` where `g` is the GCD of `a` and `b` and `a * x + b * y = g`
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+/// Combines `x ≡ r0 (mod m0)` and `x ≡ r1 (mod m1)` into a single congruence. The moduli don't
+/// need to be coprime. Returns `None` if no number satisfies both.
+fn combine_congruences((r0, m0): (i128, i128), (r1, m1): (i128, i128)) -> Option<(i128, i128)> {
+    let (g, x, _) = extended_gcd(m0, m1);
+    let diff = r1 - r0;
+    if diff % g != 0 {
+        return None;
+    }
+
+    let modulus = m0 / g * m1;
+    let k = (diff / g * x).rem_euclid(m1 / g);
+    Some(((r0 + m0 * k).rem_euclid(modulus), modulus))
+}
+
 fn gcd(a: usize, b: usize) -> usize {
     let mut a = a;
     let mut b = b;
@@ -166,19 +687,95 @@ fn lcm(a: usize, b: usize) -> usize {
     a * b / gcd(a, b)
 }
 
+/// Steps printed by `--trace` when `--max-steps` isn't given
+const DEFAULT_TRACE_STEPS: usize = 100;
+/// Steps simulated by `--lockstep` when `--max-steps` isn't given
+const DEFAULT_LOCKSTEP_STEPS: usize = 100_000_000;
+
+/// Prints the parsed network as JSON, or each ghost's stats with `stats`
+#[cfg(feature = "serde")]
+fn print_json(node_map: &NodeMap, stats: bool) {
+    let json = if stats {
+        serde_json::to_string_pretty(&node_map.ghost_stats("Z"))
+    } else {
+        serde_json::to_string_pretty(node_map)
+    };
+    println!("{}", json.expect("failed to serialize"));
+}
+
+#[cfg(not(feature = "serde"))]
+fn print_json(_: &NodeMap, _: bool) {
+    eprintln!("--json needs day_8 to be built with the `serde` feature");
+    std::process::exit(1);
+}
+
 fn main() {
     let node_map = fs::read_to_string("input.txt")
         .expect("failed to open input file. Check that it exists at 'input.txt'")
         .parse::<NodeMap>()
         .expect("failed to parse input data");
+    let args: Vec<String> = env::args().collect();
+    let flag_value = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|i| args.get(i + 1))
+            .map(String::as_str)
+    };
+
+    if let Some(start_key) = flag_value("--trace") {
+        let max_steps = flag_value("--max-steps")
+            .map(|steps| steps.parse().expect("--max-steps needs a number"))
+            .unwrap_or(DEFAULT_TRACE_STEPS);
+        match node_map.trace(start_key, max_steps, flag_value("--break")) {
+            Some(trace) => print!("{trace}"),
+            None => eprintln!("no node named {start_key}"),
+        }
+        return;
+    }
+
+    if args.iter().any(|arg| arg == "--json") {
+        print_json(&node_map, args.iter().any(|arg| arg == "--stats"));
+        return;
+    }
+
+    if args.iter().any(|arg| arg == "--stats") {
+        for stats in node_map.ghost_stats("Z") {
+            println!("{stats}");
+        }
+        return;
+    }
 
     let steps = node_map.count_steps("AAA", "ZZZ");
 
     println!("Part 1 result: {steps}");
 
-    let steps = node_map.lcm_of_steps("Z");
-
-    println!("Part 2 result: {steps}");
+    if args.iter().any(|arg| arg == "--lcm") {
+        let steps = node_map.lcm_of_steps("Z");
+        println!("Part 2 result: {steps}");
+    } else if args.iter().any(|arg| arg == "--lockstep") {
+        let max_steps = flag_value("--max-steps")
+            .map(|steps| steps.parse().expect("--max-steps needs a number"))
+            .unwrap_or(DEFAULT_LOCKSTEP_STEPS);
+        match node_map.steps_until_all_on_target_in_lockstep("Z", max_steps) {
+            Some(steps) => println!("Part 2 result: {steps}"),
+            None => println!("Part 2 result not found in {max_steps} steps"),
+        }
+    } else {
+        match node_map.steps_until_all_on_target("Z") {
+            Some(steps) => {
+                println!("Part 2 result: {steps}");
+                if args.iter().any(|arg| arg == "--verify") {
+                    let jump_table = JumpTable::new(&node_map);
+                    if jump_table.all_on_target_after(&node_map, "Z", steps) {
+                        println!("Verified: every ghost is on a Z node after {steps} steps");
+                    } else {
+                        println!("Verification failed: not every ghost is on a Z node");
+                    }
+                }
+            }
+            None => println!("Part 2 result not found"),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -216,8 +813,28 @@ mod test {
             assert_eq!("AAA = s".parse::<Node>(), Err(ParseNodeError));
         }
     }
+    mod node_set {
+        use crate::NodeSet;
+
+        #[test]
+        fn insert_and_iter() {
+            let mut set = NodeSet::new(130);
+            for id in [129, 0, 64, 3] {
+                set.insert(id);
+            }
+            assert_eq!(set.iter().collect::<Vec<u16>>(), vec![0, 3, 64, 129]);
+
+            let mut superset = set.clone();
+            superset.insert(100);
+            assert!(set.is_subset(&superset));
+            assert!(!superset.is_subset(&set));
+
+            set.clear();
+            assert_eq!(set.iter().count(), 0);
+        }
+    }
     mod node_map {
-        use crate::NodeMap;
+        use crate::{GhostCycle, GhostStats, JumpTable, NodeMap};
 
         const TEST_INPUT: &str = "RL\n\
                                   \n\
@@ -256,7 +873,53 @@ mod test {
             let node_map = node_map.unwrap();
 
             assert_eq!(node_map.directions.len(), direction_count);
-            assert_eq!(node_map.nodes.len(), node_count);
+            assert_eq!(node_map.names.len(), node_count);
+            assert_eq!(node_map.start_ids, vec![0]);
+
+            // BBB is the second node, DDD the fourth and EEE the fifth
+            let bbb = node_map.id("BBB").unwrap();
+            assert_eq!(bbb, 1);
+            assert_eq!(node_map.lefts[usize::from(bbb)], 3);
+            assert_eq!(node_map.rights[usize::from(bbb)], 4);
+
+            // Every side has to name a node in the map
+            assert!("L\n\nAAA = (BBB, AAA)".parse::<NodeMap>().is_err());
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde_round_trip() {
+            let node_map = TEST_INPUT_2.parse::<NodeMap>().unwrap();
+            let json = serde_json::to_value(&node_map).unwrap();
+            assert_eq!(
+                json["directions"],
+                serde_json::json!(["Left", "Left", "Right"])
+            );
+            assert_eq!(json["names"], serde_json::json!(["AAA", "BBB", "ZZZ"]));
+            assert_eq!(json["rights"], serde_json::json!([1, 2, 2]));
+            assert!(json.get("ids").is_none());
+            assert_eq!(
+                serde_json::from_value::<NodeMap>(json.clone()).unwrap(),
+                node_map
+            );
+
+            // Each of these would panic once the map is walked
+            let broken = |field: &str, value: serde_json::Value| {
+                let mut json = json.clone();
+                json[field] = value;
+                serde_json::from_value::<NodeMap>(json).is_err()
+            };
+            assert!(broken("directions", serde_json::json!([])));
+            assert!(broken("lefts", serde_json::json!([1, 3, 2])));
+            assert!(broken("rights", serde_json::json!([1, 2])));
+            assert!(broken("names", serde_json::json!(["AAA", "AAA", "ZZZ"])));
+
+            let stats = node_map.ghost_stats("Z");
+            let json = serde_json::to_string(&stats).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Vec<GhostStats>>(&json).unwrap(),
+                stats
+            );
         }
 
         #[test]
@@ -272,6 +935,18 @@ mod test {
             assert_eq!(node_map.count_steps("AAA", "ZZZ"), expected);
         }
 
+        /// The first ghost reaches its cycle a step late, and the second one's first hit isn't part
+        /// of its cycle
+        const OFFSET_INPUT: &str = "L\n\
+                                    \n\
+                                    11A = (11B, 11B)\n\
+                                    11B = (11Z, 11Z)\n\
+                                    11Z = (11B, 11B)\n\
+                                    22A = (22Z, 22Z)\n\
+                                    22Z = (22C, 22C)\n\
+                                    22C = (22D, 22D)\n\
+                                    22D = (22Z, 22Z)";
+
         #[test]
         fn lcm_of_steps() {
             let node_map = P2_INPUT.parse::<NodeMap>().unwrap();
@@ -279,5 +954,175 @@ mod test {
 
             assert_eq!(node_map.lcm_of_steps("Z"), expected);
         }
+
+        #[test]
+        fn trace() {
+            let node_map = TEST_INPUT_2.parse::<NodeMap>().unwrap();
+
+            let expected = "       0     AAA\n       1  L BBB\n       2  L AAA\n";
+            assert_eq!(node_map.trace("AAA", 2, None), Some(expected.to_string()));
+
+            let trace = node_map.trace("AAA", 100, Some("Z")).unwrap();
+            assert_eq!(trace.lines().count(), 7);
+            assert!(trace.ends_with("       6  R ZZZ  <- breakpoint\n"));
+
+            assert_eq!(node_map.trace("QQQ", 5, None), None);
+        }
+
+        #[test]
+        fn find_cycle() {
+            let node_map = P2_INPUT.parse::<NodeMap>().unwrap();
+            let expected = GhostCycle {
+                start: 1,
+                length: 6,
+                lead_in_hits: vec![],
+                cycle_hits: vec![3, 6],
+            };
+
+            assert_eq!(
+                node_map.find_cycle(node_map.id("22A").unwrap(), "Z"),
+                expected
+            );
+
+            let node_map = OFFSET_INPUT.parse::<NodeMap>().unwrap();
+            let cycle = node_map.find_cycle(node_map.id("22A").unwrap(), "Z");
+            assert_eq!(cycle.start, 1);
+            assert_eq!(cycle.length, 3);
+            assert_eq!(cycle.cycle_hits, vec![1]);
+            assert!(cycle.hits_at(4));
+            assert!(!cycle.hits_at(2));
+        }
+
+        #[test]
+        fn ghost_stats() {
+            let node_map = P2_INPUT.parse::<NodeMap>().unwrap();
+            let expected = vec![
+                GhostStats {
+                    start: String::from("11A"),
+                    first_hit: Some(2),
+                    cycle_length: 2,
+                    distinct_targets: 1,
+                    lcm_valid: true,
+                },
+                GhostStats {
+                    start: String::from("22A"),
+                    first_hit: Some(3),
+                    cycle_length: 6,
+                    distinct_targets: 1,
+                    lcm_valid: true,
+                },
+            ];
+            assert_eq!(node_map.ghost_stats("Z"), expected);
+
+            let node_map = OFFSET_INPUT.parse::<NodeMap>().unwrap();
+            let stats = node_map.ghost_stats("Z");
+            assert!(stats[0].lcm_valid);
+            assert_eq!(stats[1].first_hit, Some(1));
+            assert!(!stats[1].lcm_valid);
+
+            let node_map = TEST_INPUT.parse::<NodeMap>().unwrap();
+            let stats = node_map.ghost_stats("Q");
+            assert_eq!(stats[0].first_hit, None);
+            assert!(!stats[0].lcm_valid);
+        }
+
+        #[test]
+        fn steps_until_all_on_target() {
+            let node_map = P2_INPUT.parse::<NodeMap>().unwrap();
+            assert_eq!(node_map.steps_until_all_on_target("Z"), Some(6));
+
+            // LCM of the first hits gives 2 here, but the second ghost isn't on a Z node then
+            let node_map = OFFSET_INPUT.parse::<NodeMap>().unwrap();
+            assert_eq!(node_map.lcm_of_steps("Z"), 2);
+            assert_eq!(node_map.steps_until_all_on_target("Z"), Some(4));
+
+            let node_map = TEST_INPUT.parse::<NodeMap>().unwrap();
+            assert_eq!(node_map.steps_until_all_on_target("Z"), Some(2));
+            assert_eq!(node_map.steps_until_all_on_target("Q"), None);
+        }
+
+        #[test]
+        fn steps_until_all_on_target_in_lockstep() {
+            let node_map = P2_INPUT.parse::<NodeMap>().unwrap();
+            assert_eq!(
+                node_map.steps_until_all_on_target_in_lockstep("Z", 100),
+                Some(6)
+            );
+            assert_eq!(node_map.steps_until_all_on_target_in_lockstep("Z", 5), None);
+
+            let node_map = OFFSET_INPUT.parse::<NodeMap>().unwrap();
+            assert_eq!(
+                node_map.steps_until_all_on_target_in_lockstep("Z", 100),
+                Some(4)
+            );
+        }
+
+        /// Uses the lockstep engine as an oracle for the cycle solver on small random networks
+        #[test]
+        fn cycle_solver_matches_lockstep() {
+            let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+            for _ in 0..200 {
+                let node_count = 3 + rng.usize(..10);
+                let names: Vec<String> = (0..node_count)
+                    .map(|i| {
+                        let suffix = match (i, rng.u64(..3)) {
+                            (0..=2, _) => 'A',
+                            (_, 0) => 'Z',
+                            _ => 'X',
+                        };
+                        format!("{i:02}{suffix}")
+                    })
+                    .collect();
+                let directions: String = (0..1 + rng.u64(..4))
+                    .map(|_| if rng.u64(..2) == 0 { 'L' } else { 'R' })
+                    .collect();
+                let mut input = format!("{directions}\n\n");
+                for name in &names {
+                    let left = &names[rng.usize(..node_count)];
+                    let right = &names[rng.usize(..node_count)];
+                    input.push_str(&format!("{name} = ({left}, {right})\n"));
+                }
+
+                let node_map = input.parse::<NodeMap>().unwrap();
+                let limit = 10_000;
+                let expected = node_map.steps_until_all_on_target_in_lockstep("Z", limit);
+                let actual = node_map
+                    .steps_until_all_on_target("Z")
+                    .filter(|&steps| steps <= limit);
+                assert_eq!(actual, expected, "{input}");
+            }
+        }
+
+        #[test]
+        fn jump_table() {
+            let node_map = P2_INPUT.parse::<NodeMap>().unwrap();
+            let jump_table = JumpTable::new(&node_map);
+
+            for &start_id in &node_map.start_ids {
+                let mut id = start_id;
+                for (step, (_, next_id)) in node_map.walk(start_id).take(50).enumerate() {
+                    assert_eq!(jump_table.position_after(&node_map, start_id, step), id);
+                    id = next_id;
+                }
+            }
+
+            assert!(jump_table.all_on_target_after(&node_map, "Z", 6));
+            assert!(jump_table.all_on_target_after(&node_map, "Z", 6_000_000_000_000));
+            assert!(!jump_table.all_on_target_after(&node_map, "Z", 4));
+
+            let node_map = OFFSET_INPUT.parse::<NodeMap>().unwrap();
+            let jump_table = JumpTable::new(&node_map);
+            let steps = node_map.steps_until_all_on_target("Z").unwrap();
+            assert!(jump_table.all_on_target_after(&node_map, "Z", steps));
+            assert!(!jump_table.all_on_target_after(&node_map, "Z", node_map.lcm_of_steps("Z")));
+        }
+
+        #[test]
+        fn combine_congruences() {
+            assert_eq!(crate::combine_congruences((0, 2), (1, 3)), Some((4, 6)));
+            assert_eq!(crate::combine_congruences((2, 4), (0, 6)), Some((6, 12)));
+            assert_eq!(crate::combine_congruences((1, 4), (0, 6)), None);
+        }
     }
 }
@@ -0,0 +1,44 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day_9::OASISReport;
+
+/// Builds a report in the puzzle format with `line_count` histories of 21 values, each sampled
+/// from a random polynomial of degree 4 or less. The small coefficients keep the sums in `i32`.
+fn generate_report(line_count: usize) -> OASISReport {
+    let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+    let mut input = String::new();
+    for _ in 0..line_count {
+        let degree = rng.usize(..5);
+        let coefficients: Vec<i64> = (0..=degree).map(|_| rng.i64(-3..=3)).collect();
+        let values: Vec<String> = (0..21i64)
+            .map(|x| {
+                coefficients
+                    .iter()
+                    .rev()
+                    .fold(0, |total, coefficient| total * x + coefficient)
+                    .to_string()
+            })
+            .collect();
+        input.push_str(&values.join(" "));
+        input.push('\n');
+    }
+
+    input.parse().expect("generated report should parse")
+}
+
+fn report_benchmark(c: &mut Criterion) {
+    let report = generate_report(1_000_000);
+
+    let mut group = c.benchmark_group("million_line_report");
+    group.sample_size(10);
+    group.bench_function("next_vals", |b| {
+        b.iter(|| black_box(&report).sum_all_next_vals())
+    });
+    group.bench_function("prev_vals", |b| {
+        b.iter(|| black_box(&report).sum_all_prev_vals())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, report_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,341 @@
+use rayon::prelude::*;
+use std::str::FromStr;
+
+/// The readings from the OASIS, one history of values per line
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OASISReport {
+    histories: Vec<Vec<i32>>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseOASISError;
+
+impl FromStr for OASISReport {
+    type Err = ParseOASISError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let histories = s
+            .lines()
+            .map(|line| {
+                line.split_whitespace()
+                    .filter_map(|num_str| num_str.parse::<i32>().ok())
+                    .collect::<Vec<i32>>()
+            })
+            .collect::<Vec<Vec<i32>>>();
+
+        if !histories.is_empty() {
+            Ok(Self { histories })
+        } else {
+            Err(ParseOASISError)
+        }
+    }
+}
+
+impl OASISReport {
+    /// Predicts the values just before and just after `history`, returned as `(prev, next)`.
+    ///
+    /// Each row of differences overwrites the front of `scratch` in place, so the whole pyramid
+    /// fits in one buffer that can be reused between histories. The next value is the sum of the
+    /// last value of every row, and the previous value is the alternating sum of the first ones.
+    fn infer_vals_with(history: &[i32], scratch: &mut Vec<i32>) -> (i32, i32) {
+        scratch.clear();
+        scratch.extend_from_slice(history);
+
+        let (mut prev, mut next) = (0, 0);
+        let mut sign = 1;
+        let mut len = scratch.len();
+        while scratch[..len].iter().any(|&val| val != 0) {
+            prev += sign * scratch[0];
+            next += scratch[len - 1];
+            sign = -sign;
+
+            for i in 0..len - 1 {
+                scratch[i] = scratch[i + 1] - scratch[i];
+            }
+            len -= 1;
+        }
+        (prev, next)
+    }
+
+    /// Predicts the value that comes after the end of `history`
+    pub fn infer_next_val(history: &[i32]) -> i32 {
+        OASISReport::infer_vals_with(history, &mut Vec::with_capacity(history.len())).1
+    }
+
+    pub fn sum_all_next_vals(&self) -> i32 {
+        self.histories
+            .par_iter()
+            .map_init(Vec::new, |scratch, history| {
+                OASISReport::infer_vals_with(history, scratch).1
+            })
+            .sum()
+    }
+
+    /// Predicts the value that comes before the start of `history`
+    pub fn infer_prev_val(history: &[i32]) -> i32 {
+        OASISReport::infer_vals_with(history, &mut Vec::with_capacity(history.len())).0
+    }
+
+    pub fn sum_all_prev_vals(&self) -> i32 {
+        self.histories
+            .par_iter()
+            .map_init(Vec::new, |scratch, history| {
+                OASISReport::infer_vals_with(history, scratch).0
+            })
+            .sum()
+    }
+
+    pub fn histories(&self) -> &[Vec<i32>] {
+        &self.histories
+    }
+
+    /// Predicts the value `offset` steps past either end of `history`. A positive offset counts
+    /// forward from the last value and a negative one counts back from the first, so `1` gives
+    /// the same value as [OASISReport::infer_next_val] and `-1` the same as
+    /// [OASISReport::infer_prev_val]. An offset of `0` gives the last value.
+    ///
+    /// Uses Newton's forward difference formula, `f(x) = sum of C(x, k) * Δ^k f(0)`, which works
+    /// for any `x`, including the negative ones before the start. Gives `None` if the value, or
+    /// any step on the way to it, doesn't fit in 64 bits.
+    pub fn extrapolate(history: &[i32], offset: i64) -> Option<i64> {
+        if history.is_empty() {
+            return Some(0);
+        }
+        let position = if offset >= 0 {
+            (history.len() - 1) as i128 + i128::from(offset)
+        } else {
+            i128::from(offset)
+        };
+
+        let mut differences = history
+            .iter()
+            .map(|&val| i64::from(val))
+            .collect::<Vec<i64>>();
+        let mut coefficient: i128 = 1;
+        let mut total: i128 = 0;
+        for k in 0.. {
+            if differences.iter().all(|&val| val == 0) {
+                break;
+            }
+            total = total.checked_add(coefficient.checked_mul(i128::from(differences[0]))?)?;
+            // C(x, k + 1) = C(x, k) * (x - k) / (k + 1), which always divides exactly
+            coefficient = coefficient.checked_mul(position - k)? / (k + 1);
+            differences = differences
+                .windows(2)
+                .map(|pair| pair[1].checked_sub(pair[0]))
+                .collect::<Option<Vec<i64>>>()?;
+        }
+        i64::try_from(total).ok()
+    }
+
+    /// Extends every history with its next `n` predicted values, or gives `None` if any of them
+    /// overflows
+    pub fn forecast(&self, n: usize) -> Option<Vec<Vec<i64>>> {
+        self.histories
+            .iter()
+            .map(|history| {
+                history
+                    .iter()
+                    .map(|&val| Some(i64::from(val)))
+                    .chain((1..=n as i64).map(|offset| OASISReport::extrapolate(history, offset)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Predicts the value after the end of `history` by treating the values as samples of a
+    /// polynomial at `0..n` and evaluating its Lagrange form at `n`. The Lagrange weights there
+    /// reduce to the binomial coefficients `(-1)^(n - 1 - i) * C(n, i)`, so no difference
+    /// pyramid is needed. Gives `None` once the weights or the result no longer fit, which
+    /// happens for histories of around 60 values or more.
+    pub fn interpolate_next_val(history: &[i32]) -> Option<i32> {
+        let n = history.len() as i64;
+        let total = binomials(n)?
+            .into_iter()
+            .zip(history)
+            .enumerate()
+            .try_fold(0i64, |total, (i, (coefficient, &val))| {
+                let sign = if (n - 1 - i as i64) % 2 == 0 { 1 } else { -1 };
+                total.checked_add((sign * coefficient).checked_mul(i64::from(val))?)
+            })?;
+        i32::try_from(total).ok()
+    }
+
+    /// Predicts the value before the start of `history` by evaluating the same polynomial as
+    /// [OASISReport::interpolate_next_val] at `-1`, where the weights are `(-1)^i * C(n, i + 1)`
+    pub fn interpolate_prev_val(history: &[i32]) -> Option<i32> {
+        let n = history.len() as i64;
+        let total = binomials(n)?
+            .into_iter()
+            .skip(1)
+            .zip(history)
+            .enumerate()
+            .try_fold(0i64, |total, (i, (coefficient, &val))| {
+                let sign = if i % 2 == 0 { 1 } else { -1 };
+                total.checked_add((sign * coefficient).checked_mul(i64::from(val))?)
+            })?;
+        i32::try_from(total).ok()
+    }
+
+    pub fn sum_all_next_vals_interpolated(&self) -> Option<i32> {
+        self.histories.iter().try_fold(0i32, |sum, history| {
+            sum.checked_add(OASISReport::interpolate_next_val(history)?)
+        })
+    }
+
+    pub fn sum_all_prev_vals_interpolated(&self) -> Option<i32> {
+        self.histories.iter().try_fold(0i32, |sum, history| {
+            sum.checked_add(OASISReport::interpolate_prev_val(history)?)
+        })
+    }
+}
+
+/// Gives `C(n, 0)` through `C(n, n)`, or `None` if the middle ones are too big for an `i64`
+fn binomials(n: i64) -> Option<Vec<i64>> {
+    let mut coefficient: i64 = 1;
+    let mut coefficients = vec![coefficient];
+    for k in 0..n {
+        coefficient = coefficient.checked_mul(n - k)? / (k + 1);
+        coefficients.push(coefficient);
+    }
+    Some(coefficients)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "0 3 6 9 12 15\n\
+                              1 3 6 10 15 21\n\
+                              10 13 16 21 30 45";
+
+    #[test]
+    fn parse_oasis_report_from_str() {
+        let expected = OASISReport {
+            histories: vec![
+                vec![0, 3, 6, 9, 12, 15],
+                vec![1, 3, 6, 10, 15, 21],
+                vec![10, 13, 16, 21, 30, 45],
+            ],
+        };
+
+        assert_eq!(TEST_INPUT.parse(), Ok(expected));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let report = TEST_INPUT.parse::<OASISReport>().unwrap();
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            json["histories"][0],
+            serde_json::json!([0, 3, 6, 9, 12, 15])
+        );
+        assert_eq!(serde_json::from_value::<OASISReport>(json).unwrap(), report);
+    }
+
+    #[test]
+    fn predict_next_val() {
+        let test_data = [
+            ([0, 3, 6, 9, 12, 15], 18),
+            ([1, 3, 6, 10, 15, 21], 28),
+            ([10, 13, 16, 21, 30, 45], 68),
+        ];
+
+        for (history, expected) in test_data {
+            assert_eq!(OASISReport::infer_next_val(&history), expected);
+        }
+    }
+
+    #[test]
+    fn extrapolate() {
+        let history = [10, 13, 16, 21, 30, 45];
+
+        assert_eq!(OASISReport::extrapolate(&history, 1), Some(68));
+        assert_eq!(OASISReport::extrapolate(&history, -1), Some(5));
+        assert_eq!(OASISReport::extrapolate(&history, 0), Some(45));
+
+        let polynomial = |x: i64| x.pow(3) - 4 * x.pow(2) + 2 * x - 9;
+        let history = (0..6).map(|x| polynomial(x) as i32).collect::<Vec<i32>>();
+        for offset in 1..=5 {
+            assert_eq!(
+                OASISReport::extrapolate(&history, offset),
+                Some(polynomial(5 + offset))
+            );
+        }
+        for offset in 1..=3 {
+            assert_eq!(
+                OASISReport::extrapolate(&history, -offset),
+                Some(polynomial(-offset))
+            );
+        }
+
+        assert_eq!(OASISReport::extrapolate(&[], 3), Some(0));
+        assert_eq!(OASISReport::extrapolate(&[4], -2), Some(4));
+
+        assert_eq!(OASISReport::extrapolate(&[0, 1], i64::MAX), None);
+        let swinging = (0..80)
+            .map(|i| if i % 2 == 0 { i32::MAX } else { i32::MIN })
+            .collect::<Vec<i32>>();
+        assert_eq!(OASISReport::extrapolate(&swinging, 1), None);
+    }
+
+    #[test]
+    fn forecast() {
+        let report = TEST_INPUT.parse::<OASISReport>().unwrap();
+        let expected = vec![
+            vec![0, 3, 6, 9, 12, 15, 18, 21],
+            vec![1, 3, 6, 10, 15, 21, 28, 36],
+            vec![10, 13, 16, 21, 30, 45, 68, 101],
+        ];
+
+        assert_eq!(report.forecast(2), Some(expected));
+    }
+
+    #[test]
+    fn binomials() {
+        assert_eq!(crate::binomials(5), Some(vec![1, 5, 10, 10, 5, 1]));
+        assert_eq!(crate::binomials(0), Some(vec![1]));
+        assert_eq!(crate::binomials(61).unwrap()[30], 232714176627630544);
+        assert_eq!(crate::binomials(62), None);
+    }
+
+    #[test]
+    fn interpolation_matches_differences() {
+        let report = TEST_INPUT.parse::<OASISReport>().unwrap();
+        let mut histories = report.histories().to_vec();
+        histories.extend([
+            vec![],
+            vec![7],
+            vec![3, -4],
+            vec![-2, 5, 40, 1, 0, 0, 0, 9],
+            (0..21).map(|x: i32| x.pow(4) - 3 * x.pow(3) + 7).collect(),
+        ]);
+
+        for history in histories {
+            assert_eq!(
+                OASISReport::interpolate_next_val(&history),
+                Some(OASISReport::infer_next_val(&history)),
+                "{history:?}"
+            );
+            assert_eq!(
+                OASISReport::interpolate_prev_val(&history),
+                Some(OASISReport::infer_prev_val(&history)),
+                "{history:?}"
+            );
+        }
+
+        let long_history = vec![1; 80];
+        assert_eq!(OASISReport::interpolate_next_val(&long_history), None);
+        assert_eq!(OASISReport::interpolate_prev_val(&long_history), None);
+    }
+
+    #[test]
+    fn predict_previous_val() {
+        let history = [10, 13, 16, 21, 30, 45];
+        let expected = 5;
+
+        assert_eq!(OASISReport::infer_prev_val(&history), expected);
+    }
+}
@@ -1,11 +1,13 @@
-use std::cell::RefCell;
-use std::fmt::{Display, Formatter};
-use std::fs;
-use std::rc::Rc;
+#[cfg(feature = "render")]
+mod render;
 
-type TilePtr = Rc<RefCell<MazeTile>>;
+use std::collections::VecDeque;
+use std::env;
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+use std::{fs, process};
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
 enum Direction {
     North,
     South,
@@ -13,98 +15,783 @@ enum Direction {
     West,
 }
 
-#[derive(Debug, Eq, PartialEq)]
-struct Connection {
-    direction: Direction,
-    tile: TilePtr,
-}
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::South,
+        Direction::East,
+        Direction::West,
+    ];
+
+    fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::East => Direction::West,
+            Direction::West => Direction::East,
+        }
+    }
 
-#[derive(Debug, Default, Eq, PartialEq)]
-struct Pipe {
-    connection_0: Option<Connection>,
-    connection_1: Option<Connection>,
-    is_start: bool,
+    /// Gets the position one step in this direction from `(row, col)`, if it isn't off the top or
+    /// left edge
+    fn step_from(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        match self {
+            Direction::North => Some((row.checked_sub(1)?, col)),
+            Direction::South => Some((row + 1, col)),
+            Direction::East => Some((row, col + 1)),
+            Direction::West => Some((row, col.checked_sub(1)?)),
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum MazeTile {
-    Vertical(Pipe),
-    Horizontal(Pipe),
-    NorthEastBend(Pipe),
-    NorthWestBend(Pipe),
-    SouthWestBend(Pipe),
-    SouthEastBend(Pipe),
+/// A single tile of the maze. The start is replaced by the pipe hidden under it when the maze is
+/// parsed.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Tile {
+    Vertical,
+    Horizontal,
+    NorthEastBend,
+    NorthWestBend,
+    SouthWestBend,
+    SouthEastBend,
     Ground,
     Start,
 }
 
 #[derive(Debug, PartialEq, Eq)]
-struct ParseMazeTileError;
+struct ParseTileError;
 
-impl TryFrom<char> for MazeTile {
-    type Error = ParseMazeTileError;
+impl TryFrom<char> for Tile {
+    type Error = ParseTileError;
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '|' => Ok(Self::Vertical(Pipe::default())),
-            '-' => Ok(Self::Horizontal(Pipe::default())),
-            'L' => Ok(Self::NorthEastBend(Pipe::default())),
-            'J' => Ok(Self::NorthWestBend(Pipe::default())),
-            '7' => Ok(Self::SouthWestBend(Pipe::default())),
-            'F' => Ok(Self::SouthEastBend(Pipe::default())),
+            '|' => Ok(Self::Vertical),
+            '-' => Ok(Self::Horizontal),
+            'L' => Ok(Self::NorthEastBend),
+            'J' => Ok(Self::NorthWestBend),
+            '7' => Ok(Self::SouthWestBend),
+            'F' => Ok(Self::SouthEastBend),
             '.' => Ok(Self::Ground),
             'S' => Ok(Self::Start),
-            _ => Err(ParseMazeTileError),
+            _ => Err(ParseTileError),
         }
     }
 }
 
-impl Display for MazeTile {
+impl Display for Tile {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let symbol = match self {
-            MazeTile::Vertical(_) => '┃',
-            MazeTile::Horizontal(_) => '━',
-            MazeTile::NorthEastBend(_) => '┗',
-            MazeTile::NorthWestBend(_) => '┛',
-            MazeTile::SouthWestBend(_) => '┓',
-            MazeTile::SouthEastBend(_) => '┏',
-            MazeTile::Ground => '░',
-            MazeTile::Start => 'S',
+            Tile::Vertical => '┃',
+            Tile::Horizontal => '━',
+            Tile::NorthEastBend => '┗',
+            Tile::NorthWestBend => '┛',
+            Tile::SouthWestBend => '┓',
+            Tile::SouthEastBend => '┏',
+            Tile::Ground => '░',
+            Tile::Start => 'S',
         };
 
         write!(f, "{symbol}")
     }
 }
 
-fn connect_pipes(tiles: &mut [[TilePtr]]) {
-    for (i, row) in tiles.iter_mut().enumerate() {}
+impl Tile {
+    /// Gets the pipe that joins the two given directions
+    fn joining(directions: [Direction; 2]) -> Option<Self> {
+        use Direction::*;
+
+        match directions {
+            [North, South] | [South, North] => Some(Self::Vertical),
+            [East, West] | [West, East] => Some(Self::Horizontal),
+            [North, East] | [East, North] => Some(Self::NorthEastBend),
+            [North, West] | [West, North] => Some(Self::NorthWestBend),
+            [South, West] | [West, South] => Some(Self::SouthWestBend),
+            [South, East] | [East, South] => Some(Self::SouthEastBend),
+            _ => None,
+        }
+    }
+
+    /// Gets the two directions the tile's pipe opens to, or `None` if it isn't a pipe
+    fn openings(&self) -> Option<[Direction; 2]> {
+        match self {
+            Tile::Vertical => Some([Direction::North, Direction::South]),
+            Tile::Horizontal => Some([Direction::East, Direction::West]),
+            Tile::NorthEastBend => Some([Direction::North, Direction::East]),
+            Tile::NorthWestBend => Some([Direction::North, Direction::West]),
+            Tile::SouthWestBend => Some([Direction::South, Direction::West]),
+            Tile::SouthEastBend => Some([Direction::South, Direction::East]),
+            Tile::Ground | Tile::Start => None,
+        }
+    }
+
+    fn opens_to(&self, direction: Direction) -> bool {
+        self.openings()
+            .is_some_and(|openings| openings.contains(&direction))
+    }
+}
+
+/// Where a tile sits relative to the main loop
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum Region {
+    Loop,
+    Inside,
+    Outside,
+}
+
+/// The field of pipes, stored row by row in a flat list
+#[derive(Debug, Eq, PartialEq)]
+struct Maze {
+    width: usize,
+    height: usize,
+    tiles: Vec<Tile>,
+    /// Index of the neighbor each pipe's two openings lead to, if that neighbor opens back
+    connections: Vec<[Option<usize>; 2]>,
+    start: usize,
+    /// Indices of the tiles in the main loop, in order, starting at the start
+    loop_tiles: Vec<usize>,
+}
+
+/// Why a maze couldn't be read. Positions are zero-based `(row, col)` pairs.
+#[derive(Debug, PartialEq, Eq)]
+enum ParseMazeError {
+    InvalidTile {
+        position: (usize, usize),
+        symbol: char,
+    },
+    RaggedRow {
+        row: usize,
+        length: usize,
+        width: usize,
+    },
+    MissingStart,
+    ExtraStart {
+        position: (usize, usize),
+    },
+    /// The start needs exactly two neighbors that open towards it to know which pipe it hides
+    StartConnections {
+        position: (usize, usize),
+        count: usize,
+    },
+    /// Following the loop from the start reached a pipe that doesn't connect onwards
+    BrokenLoop {
+        position: (usize, usize),
+    },
+}
+
+impl Display for ParseMazeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseMazeError::InvalidTile { position, symbol } => {
+                write!(f, "unknown tile '{symbol}' at {position:?}")
+            }
+            ParseMazeError::RaggedRow { row, length, width } => {
+                write!(
+                    f,
+                    "row {row} has {length} tiles, but the first row has {width}"
+                )
+            }
+            ParseMazeError::MissingStart => write!(f, "the maze has no start tile"),
+            ParseMazeError::ExtraStart { position } => {
+                write!(f, "found a second start tile at {position:?}")
+            }
+            ParseMazeError::StartConnections { position, count } => write!(
+                f,
+                "the start at {position:?} has {count} neighbors opening towards it instead of 2"
+            ),
+            ParseMazeError::BrokenLoop { position } => {
+                write!(f, "the loop is broken at {position:?}")
+            }
+        }
+    }
+}
+
+impl FromStr for Maze {
+    type Err = ParseMazeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let width = s.lines().next().map_or(0, |line| line.chars().count());
+        let mut tiles = vec![];
+        let mut height = 0;
+        for (row, line) in s.lines().enumerate() {
+            for (col, symbol) in line.chars().enumerate() {
+                let tile = Tile::try_from(symbol).or(Err(ParseMazeError::InvalidTile {
+                    position: (row, col),
+                    symbol,
+                }))?;
+                tiles.push(tile);
+            }
+            let length = line.chars().count();
+            if length != width {
+                return Err(ParseMazeError::RaggedRow { row, length, width });
+            }
+            height += 1;
+        }
+
+        Maze::from_tiles(width, height, tiles)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Maze {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(serde::Serialize)]
+        struct MazeData<'a> {
+            width: usize,
+            height: usize,
+            start: (usize, usize),
+            tiles: &'a [Tile],
+            loop_tiles: &'a [usize],
+        }
+
+        MazeData {
+            width: self.width,
+            height: self.height,
+            start: self.position(self.start),
+            tiles: &self.tiles,
+            loop_tiles: &self.loop_tiles,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Reads back the tiles and the start, working out the start's pipe and the loop again instead of
+/// trusting them
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Maze {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(serde::Deserialize)]
+        struct MazeData {
+            width: usize,
+            height: usize,
+            start: (usize, usize),
+            tiles: Vec<Tile>,
+        }
+
+        let MazeData {
+            width,
+            height,
+            start: (row, col),
+            mut tiles,
+        } = MazeData::deserialize(deserializer)?;
+        if width.checked_mul(height) != Some(tiles.len()) {
+            return Err(D::Error::custom(format!(
+                "{} tiles don't make a {width}x{height} maze",
+                tiles.len()
+            )));
+        }
+        if row >= height || col >= width {
+            return Err(D::Error::custom(format!(
+                "the start {:?} is outside the maze",
+                (row, col)
+            )));
+        }
+        tiles[row * width + col] = Tile::Start;
+        Maze::from_tiles(width, height, tiles).map_err(D::Error::custom)
+    }
 }
 
-const TEST_INPUT: &str = ".....\n\
-.F-7.\n\
-.|.|.\n\
-.L-J.\n\
-.....";
+impl Maze {
+    /// Finds the start in `tiles`, laid out row by row, and the loop through it
+    fn from_tiles(width: usize, height: usize, tiles: Vec<Tile>) -> Result<Self, ParseMazeError> {
+        let mut maze = Self {
+            width,
+            height,
+            tiles,
+            connections: vec![],
+            start: 0,
+            loop_tiles: vec![],
+        };
+        let mut starts = (0..maze.tiles.len()).filter(|&index| maze.tiles[index] == Tile::Start);
+        maze.start = starts.next().ok_or(ParseMazeError::MissingStart)?;
+        if let Some(extra) = starts.next() {
+            return Err(ParseMazeError::ExtraStart {
+                position: maze.position(extra),
+            });
+        }
+
+        // The start hides the pipe that joins the two neighbors opening towards it
+        let start_openings: Vec<Direction> = Direction::ALL
+            .into_iter()
+            .filter(|&direction| {
+                maze.neighbor(maze.start, direction)
+                    .is_some_and(|neighbor| maze.tiles[neighbor].opens_to(direction.opposite()))
+            })
+            .collect();
+        let start_connections = ParseMazeError::StartConnections {
+            position: maze.position(maze.start),
+            count: start_openings.len(),
+        };
+        let start_openings: [Direction; 2] =
+            start_openings.try_into().or(Err(start_connections))?;
+        maze.tiles[maze.start] =
+            Tile::joining(start_openings).expect("two different directions always join");
+
+        maze.connections = (0..maze.tiles.len())
+            .map(|index| maze.connections_of(index))
+            .collect();
+        maze.loop_tiles = maze.follow_loop()?;
+
+        Ok(maze)
+    }
+}
+
+impl Display for Maze {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.tiles.chunks(self.width) {
+            for tile in row {
+                write!(f, "{tile}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Maze {
+    fn index(&self, row: usize, col: usize) -> usize {
+        row * self.width + col
+    }
+
+    fn position(&self, index: usize) -> (usize, usize) {
+        (index / self.width, index % self.width)
+    }
+
+    /// Gets the index of the tile one step in `direction` from `index`, if it's in the maze
+    fn neighbor(&self, index: usize, direction: Direction) -> Option<usize> {
+        let (row, col) = self.position(index);
+        let (row, col) = direction.step_from(row, col)?;
+        (row < self.height && col < self.width).then(|| self.index(row, col))
+    }
+
+    /// Finds the neighbors that the pipe at `index` connects to. Openings towards a tile that
+    /// doesn't open back are `None`.
+    fn connections_of(&self, index: usize) -> [Option<usize>; 2] {
+        let Some(openings) = self.tiles[index].openings() else {
+            return [None, None];
+        };
+        openings.map(|direction| {
+            self.neighbor(index, direction)
+                .filter(|&neighbor| self.tiles[neighbor].opens_to(direction.opposite()))
+        })
+    }
+
+    /// Follows the pipes from the start until they come back around. Every pipe has at most
+    /// two connections, so the walk either returns to the start or stops at a pipe with no
+    /// onward connection.
+    fn follow_loop(&self) -> Result<Vec<usize>, ParseMazeError> {
+        let mut tiles = vec![self.start];
+        let mut previous = None;
+        let mut current = self.start;
+
+        loop {
+            let next = self.connections[current]
+                .into_iter()
+                .flatten()
+                .find(|&next| Some(next) != previous)
+                .ok_or(ParseMazeError::BrokenLoop {
+                    position: self.position(current),
+                })?;
+            if next == self.start {
+                return Ok(tiles);
+            }
+            tiles.push(next);
+            previous = Some(current);
+            current = next;
+        }
+    }
+
+    /// Lists the indices of the tiles in the loop, in order, starting at the start
+    fn loop_tiles(&self) -> &[usize] {
+        &self.loop_tiles
+    }
+
+    /// Gets the `(row, col)` positions of the loop's corner tiles, in loop order. Joined up in
+    /// order, they trace out the loop as a polygon without needing the tile grid.
+    fn loop_vertices(&self) -> Vec<(usize, usize)> {
+        self.loop_tiles()
+            .iter()
+            .filter(|&&index| !matches!(self.tiles[index], Tile::Vertical | Tile::Horizontal))
+            .map(|&index| self.position(index))
+            .collect()
+    }
+
+    /// Draws the loop as an SVG polygon through the middle of its corner tiles, one unit per tile
+    fn loop_svg(&self) -> String {
+        let points: Vec<String> = self
+            .loop_vertices()
+            .iter()
+            .map(|&(row, col)| format!("{}.5,{}.5", col, row))
+            .collect();
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}">"#,
+            self.width, self.height
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r##"<polygon points="{}" fill="#28964640" stroke="#ffc828" stroke-width="0.3"/>"##,
+            points.join(" ")
+        )
+        .unwrap();
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Sorts every tile into the loop, inside it or outside it. Scanning each row from the
+    /// left, crossing a loop pipe that opens north flips between outside and inside.
+    fn regions(&self) -> Vec<Region> {
+        let mut regions = vec![Region::Outside; self.tiles.len()];
+        for &index in self.loop_tiles() {
+            regions[index] = Region::Loop;
+        }
+
+        for (region_row, tile_row) in regions
+            .chunks_mut(self.width)
+            .zip(self.tiles.chunks(self.width))
+        {
+            let mut inside = false;
+            for (region, tile) in region_row.iter_mut().zip(tile_row) {
+                if *region == Region::Loop {
+                    if tile.opens_to(Direction::North) {
+                        inside = !inside;
+                    }
+                } else if inside {
+                    *region = Region::Inside;
+                }
+            }
+        }
+        regions
+    }
+
+    /// Counts the tiles enclosed by the loop
+    fn enclosed_count(&self) -> usize {
+        self.regions()
+            .iter()
+            .filter(|&&region| region == Region::Inside)
+            .count()
+    }
+
+    /// Gets each tile's distance from the start along the loop, found with a breadth-first
+    /// search through the pipe connections. Tiles that aren't part of the loop are `None`.
+    fn distance_map(&self) -> Vec<Option<usize>> {
+        let mut distances = vec![None; self.tiles.len()];
+        distances[self.start] = Some(0);
+        let mut queue = VecDeque::from([(self.start, 0)]);
+
+        while let Some((index, distance)) = queue.pop_front() {
+            for next in self.connections[index].into_iter().flatten() {
+                if distances[next].is_none() {
+                    distances[next] = Some(distance + 1);
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+        distances
+    }
+
+    /// Gets the number of steps along the loop to the tile farthest from the start
+    fn farthest_distance(&self) -> usize {
+        self.distance_map().into_iter().flatten().max().unwrap_or(0)
+    }
+}
+
+const PNG_PATH: &str = "maze.png";
+const SVG_PATH: &str = "loop.svg";
+/// Pixels per tile in the PNG when `--scale` isn't given
+const DEFAULT_SCALE: usize = 4;
+
+#[cfg(feature = "serde")]
+fn print_json(maze: &Maze) {
+    let json = serde_json::to_string_pretty(maze).expect("failed to serialize");
+    println!("{json}");
+}
+
+#[cfg(not(feature = "serde"))]
+fn print_json(_: &Maze) {
+    eprintln!("--json needs day_10 to be built with the `serde` feature");
+    process::exit(1);
+}
+
+#[cfg(feature = "render")]
+fn render_png(maze: &Maze, path: &str, scale: usize, heat_map: bool) {
+    if let Err(err) = maze.render_png(path, scale, heat_map) {
+        eprintln!("failed to render the maze: {err}");
+        process::exit(1);
+    }
+    println!("Wrote the maze to {path}");
+}
+
+#[cfg(not(feature = "render"))]
+fn render_png(_: &Maze, _: &str, _: usize, _: bool) {
+    eprintln!("--png needs day_10 to be built with the `render` feature");
+    process::exit(1);
+}
 
 fn main() {
-    let tiles: Vec<Vec<TilePtr>> = fs::read_to_string("input.txt")
+    let maze = match fs::read_to_string("input.txt")
         .expect("failed to open input file")
-        .lines()
-        .map(|line| {
-            line.chars()
-                .filter_map(|c| c.try_into().ok())
-                .map(|tile| Rc::new(RefCell::new(tile)))
-                .collect()
-        })
-        .collect();
+        .parse::<Maze>()
+    {
+        Ok(maze) => maze,
+        Err(err) => {
+            eprintln!("invalid maze: {err}");
+            process::exit(1);
+        }
+    };
+    let args: Vec<String> = env::args().collect();
+    let has_flag = |flag: &str| args.iter().any(|arg| arg == flag);
 
-    let mut out_str = String::new();
-    for row in tiles {
-        for tile in row {
-            out_str.push_str(&*format!("{tile}"));
+    if has_flag("--json") {
+        print_json(&maze);
+        return;
+    }
+
+    if has_flag("--print") {
+        print!("{maze}");
+    }
+
+    println!("Part 1 result: {}", maze.farthest_distance());
+    println!("Part 2 result: {}", maze.enclosed_count());
+
+    if has_flag("--png") {
+        let scale = args
+            .iter()
+            .position(|arg| arg == "--scale")
+            .and_then(|i| args.get(i + 1))
+            .map(|scale| scale.parse().expect("--scale needs a positive number"))
+            .unwrap_or(DEFAULT_SCALE);
+        assert!(scale > 0, "--scale needs a positive number");
+
+        render_png(&maze, PNG_PATH, scale, has_flag("--heat-map"));
+    }
+
+    if has_flag("--svg") {
+        fs::write(SVG_PATH, maze.loop_svg()).expect("failed to write the loop image");
+        println!("Wrote the loop to {SVG_PATH}");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    pub(crate) const TEST_INPUT: &str = ".....\n\
+                                         .S-7.\n\
+                                         .|.|.\n\
+                                         .L-J.\n\
+                                         .....";
+
+    const TEST_INPUT_2: &str = "7-F7-\n\
+                                .FJ|7\n\
+                                SJLL7\n\
+                                |F--J\n\
+                                LJ.LJ";
+
+    const TEST_INPUT_3: &str = "...........\n\
+                                .S-------7.\n\
+                                .|F-----7|.\n\
+                                .||.....||.\n\
+                                .||.....||.\n\
+                                .|L-7.F-J|.\n\
+                                .|..|.|..|.\n\
+                                .L--J.L--J.\n\
+                                ...........";
+
+    const TEST_INPUT_4: &str = ".F----7F7F7F7F-7....\n\
+                                .|F--7||||||||FJ....\n\
+                                .||.FJ||||||||L7....\n\
+                                FJL7L7LJLJ||LJ.L-7..\n\
+                                L--J.L7...LJS7F-7L7.\n\
+                                ....F-J..F7FJ|L7L7L7\n\
+                                ....L7.F7||L7|.L7L7|\n\
+                                .....|FJLJ|FJ|F7|.LJ\n\
+                                ....FJL-7.||.||||...\n\
+                                ....L---J.LJ.LJLJ...";
+
+    #[test]
+    fn parse_start_tile() {
+        let maze = TEST_INPUT.parse::<Maze>().unwrap();
+        assert_eq!((maze.width, maze.height), (5, 5));
+        assert_eq!(maze.position(maze.start), (1, 1));
+        assert_eq!(maze.tiles[maze.start], Tile::SouthEastBend);
+
+        let maze = TEST_INPUT_2.parse::<Maze>().unwrap();
+        assert_eq!(maze.tiles[maze.start], Tile::SouthEastBend);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            ".x\n..".parse::<Maze>(),
+            Err(ParseMazeError::InvalidTile {
+                position: (0, 1),
+                symbol: 'x'
+            })
+        );
+        assert_eq!(
+            "S-7\n|.|\nL-".parse::<Maze>(),
+            Err(ParseMazeError::RaggedRow {
+                row: 2,
+                length: 2,
+                width: 3
+            })
+        );
+        assert_eq!("..\n..".parse::<Maze>(), Err(ParseMazeError::MissingStart));
+        assert_eq!("".parse::<Maze>(), Err(ParseMazeError::MissingStart));
+        assert_eq!(
+            "S-7\n|.|\nL-S".parse::<Maze>(),
+            Err(ParseMazeError::ExtraStart { position: (2, 2) })
+        );
+        assert_eq!(
+            ".S.\n...".parse::<Maze>(),
+            Err(ParseMazeError::StartConnections {
+                position: (0, 1),
+                count: 0
+            })
+        );
+        assert_eq!(
+            ".|.\n-S-\n.|.".parse::<Maze>(),
+            Err(ParseMazeError::StartConnections {
+                position: (1, 1),
+                count: 4
+            })
+        );
+        assert_eq!(
+            "S-7\n|.|\nL-.".parse::<Maze>(),
+            Err(ParseMazeError::BrokenLoop { position: (2, 1) })
+        );
+    }
+
+    #[test]
+    fn connections() {
+        let maze = TEST_INPUT_2.parse::<Maze>().unwrap();
+
+        // The top left 7 only opens towards a tile that doesn't open back
+        assert_eq!(maze.connections[maze.index(0, 0)], [None, None]);
+
+        assert_eq!(
+            maze.connections[maze.start],
+            [Some(maze.index(3, 0)), Some(maze.index(2, 1))]
+        );
+        assert_eq!(maze.neighbor(maze.start, Direction::West), None);
+        assert_eq!(maze.neighbor(maze.index(4, 4), Direction::South), None);
+    }
+
+    #[test]
+    fn farthest_distance() {
+        let maze = TEST_INPUT.parse::<Maze>().unwrap();
+        assert_eq!(maze.loop_tiles().len(), 8);
+        assert_eq!(
+            maze.loop_tiles()[..3],
+            [maze.start, maze.index(2, 1), maze.index(3, 1)]
+        );
+        assert_eq!(maze.farthest_distance(), 4);
+
+        let maze = TEST_INPUT_2.parse::<Maze>().unwrap();
+        assert_eq!(maze.farthest_distance(), 8);
+    }
+
+    #[test]
+    fn enclosed_count() {
+        let maze = TEST_INPUT.parse::<Maze>().unwrap();
+        assert_eq!(maze.enclosed_count(), 1);
+        assert_eq!(maze.regions()[maze.index(2, 2)], Region::Inside);
+        assert_eq!(maze.regions()[maze.index(2, 4)], Region::Outside);
+
+        let maze = TEST_INPUT_3.parse::<Maze>().unwrap();
+        assert_eq!(maze.enclosed_count(), 4);
+
+        let maze = TEST_INPUT_4.parse::<Maze>().unwrap();
+        assert_eq!(maze.enclosed_count(), 8);
+    }
+
+    #[test]
+    fn loop_vertices() {
+        let maze = TEST_INPUT.parse::<Maze>().unwrap();
+        assert_eq!(maze.loop_vertices(), vec![(1, 1), (3, 1), (3, 3), (1, 3)]);
+
+        // The shoelace area and Pick's theorem give the enclosed tiles from the vertices alone
+        for input in [TEST_INPUT, TEST_INPUT_2, TEST_INPUT_3, TEST_INPUT_4] {
+            let maze = input.parse::<Maze>().unwrap();
+            let vertices = maze.loop_vertices();
+            let twice_area = vertices
+                .iter()
+                .zip(vertices.iter().cycle().skip(1))
+                .map(|(&(r0, c0), &(r1, c1))| (c0 * r1) as i64 - (c1 * r0) as i64)
+                .sum::<i64>()
+                .unsigned_abs() as usize;
+            let boundary = maze.loop_tiles().len();
+
+            assert_eq!((twice_area - boundary) / 2 + 1, maze.enclosed_count());
         }
-        out_str.push('\n');
     }
 
-    print!("{out_str}");
+    #[test]
+    fn loop_svg() {
+        let maze = TEST_INPUT.parse::<Maze>().unwrap();
+        let svg = maze.loop_svg();
+
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 5 5">"#));
+        assert!(svg.contains(r#"points="1.5,1.5 1.5,3.5 3.5,3.5 3.5,1.5""#));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn distance_map() {
+        let maze = TEST_INPUT.parse::<Maze>().unwrap();
+        let expected = [
+            [None, None, None, None, None],
+            [None, Some(0), Some(1), Some(2), None],
+            [None, Some(1), None, Some(3), None],
+            [None, Some(2), Some(3), Some(4), None],
+            [None, None, None, None, None],
+        ];
+        assert_eq!(maze.distance_map(), expected.concat());
+
+        let maze = TEST_INPUT_2.parse::<Maze>().unwrap();
+        let expected = [
+            [None, None, Some(4), Some(5), None],
+            [None, Some(2), Some(3), Some(6), None],
+            [Some(0), Some(1), None, Some(7), Some(8)],
+            [Some(1), Some(4), Some(5), Some(6), Some(7)],
+            [Some(2), Some(3), None, None, None],
+        ];
+        assert_eq!(maze.distance_map(), expected.concat());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let maze = TEST_INPUT.parse::<Maze>().unwrap();
+        let json = serde_json::to_value(&maze).unwrap();
+        assert_eq!(json["start"], serde_json::json!([1, 1]));
+        assert_eq!(json["tiles"][6], serde_json::json!("SouthEastBend"));
+        assert_eq!(json["loop_tiles"].as_array().unwrap().len(), 8);
+        assert_eq!(serde_json::from_value::<Maze>(json.clone()).unwrap(), maze);
+
+        let mut broken = json.clone();
+        broken["tiles"][7] = serde_json::json!("Ground");
+        assert!(serde_json::from_value::<Maze>(broken).is_err());
+        let mut broken = json.clone();
+        broken["start"] = serde_json::json!([5, 0]);
+        assert!(serde_json::from_value::<Maze>(broken).is_err());
+        let mut broken = json;
+        broken["width"] = serde_json::json!(4);
+        assert!(serde_json::from_value::<Maze>(broken).is_err());
+    }
+
+    #[test]
+    fn display() {
+        let maze = TEST_INPUT.parse::<Maze>().unwrap();
+        let expected = "░░░░░\n░┏━┓░\n░┃░┃░\n░┗━┛░\n░░░░░\n";
+
+        assert_eq!(maze.to_string(), expected);
+    }
 }
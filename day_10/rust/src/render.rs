@@ -0,0 +1,115 @@
+use super::{Direction, Maze, Region};
+use aoc_viz::{Color, Frame, Palette, Png, RenderError, Renderer};
+
+/// Palette indices for each part of the picture. The rest of the palette shades the loop from
+/// blue to red for the heat map.
+const OUTSIDE: u8 = 0;
+const INSIDE: u8 = 1;
+const LOOP_BACKGROUND: u8 = 2;
+const OTHER_PIPE: u8 = 3;
+const LOOP: u8 = 4;
+const HEAT: u8 = 5;
+
+/// How many shades the heat map has
+const HEAT_STEPS: usize = 251;
+
+fn palette() -> Palette {
+    let mut palette = Palette::new([
+        Color::rgb(30, 30, 45),
+        Color::rgb(40, 150, 70),
+        Color::rgb(70, 50, 20),
+        Color::rgb(110, 110, 110),
+        Color::rgb(255, 200, 40),
+    ]);
+    for step in 0..HEAT_STEPS {
+        palette.push(Color::rgb(0, 40, 255).lerp(
+            Color::rgb(255, 40, 0),
+            step as f64 / (HEAT_STEPS - 1) as f64,
+        ));
+    }
+    palette
+}
+
+/// The heat map shade for a tile `distance` steps along the loop, from blue at the start to red
+/// at `max_distance`
+fn heat_index(distance: usize, max_distance: usize) -> u8 {
+    let step = (distance * (HEAT_STEPS - 1))
+        .checked_div(max_distance)
+        .unwrap_or(0);
+    HEAT + step as u8
+}
+
+impl Maze {
+    /// Draws the maze with each tile taking `scale` by `scale` cells. Pipes are drawn as lines
+    /// through the middle of their tiles, and the loop, the tiles inside it and the tiles outside
+    /// it each get their own colors. With `heat_map` set, the loop is instead shaded from blue to
+    /// red by its distance from the start.
+    fn frame(&self, scale: usize, heat_map: bool) -> Frame {
+        let regions = self.regions();
+        let distances = self.distance_map();
+        let max_distance = distances.iter().flatten().copied().max().unwrap_or(0);
+        let thickness = (scale / 3).max(1);
+        let band_start = (scale - thickness) / 2;
+        let band = band_start..band_start + thickness;
+
+        Frame::from_fn(self.width * scale, self.height * scale, |(y, x)| {
+            let index = self.index(y / scale, x / scale);
+            let (x, y) = (x % scale, y % scale);
+            let tile = self.tiles[index];
+
+            let on_pipe = (band.contains(&y)
+                && ((x < band.end && tile.opens_to(Direction::West))
+                    || (x >= band.start && tile.opens_to(Direction::East))))
+                || (band.contains(&x)
+                    && ((y < band.end && tile.opens_to(Direction::North))
+                        || (y >= band.start && tile.opens_to(Direction::South))));
+
+            match (regions[index], on_pipe) {
+                (Region::Loop, true) if heat_map => {
+                    heat_index(distances[index].unwrap_or(0), max_distance)
+                }
+                (Region::Loop, true) => LOOP,
+                (Region::Loop, false) => LOOP_BACKGROUND,
+                (_, true) => OTHER_PIPE,
+                (Region::Inside, false) => INSIDE,
+                (Region::Outside, false) => OUTSIDE,
+            }
+        })
+    }
+
+    /// Draws the maze like [Maze::frame], with each tile `scale` pixels across, and saves it to
+    /// `path`
+    pub(crate) fn render_png(
+        &self,
+        path: &str,
+        scale: usize,
+        heat_map: bool,
+    ) -> Result<(), RenderError> {
+        Png::single(path, palette()).render(&self.frame(scale, heat_map))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::TEST_INPUT;
+
+    #[test]
+    fn maze_frame() {
+        let maze = TEST_INPUT.parse::<Maze>().unwrap();
+        let frame = maze.frame(3, false);
+
+        assert_eq!((frame.width(), frame.height()), (15, 15));
+        assert_eq!(frame.get((0, 0)), Some(OUTSIDE));
+        assert_eq!(frame.get((7, 7)), Some(INSIDE));
+        // Middle of the horizontal pipe at the top of the loop, and the corner beside it
+        assert_eq!(frame.get((4, 7)), Some(LOOP));
+        assert_eq!(frame.get((3, 7)), Some(LOOP_BACKGROUND));
+
+        let frame = maze.frame(3, true);
+        assert_eq!(frame.get((4, 4)), Some(HEAT));
+        assert_eq!(frame.get((10, 10)), Some(HEAT + (HEAT_STEPS - 1) as u8));
+        assert_eq!(frame.get((7, 7)), Some(INSIDE));
+        assert_eq!(palette().len(), HEAT as usize + HEAT_STEPS);
+    }
+}
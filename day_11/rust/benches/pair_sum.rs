@@ -0,0 +1,48 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day_11::GalaxyMap;
+use indicatif::ProgressBar;
+
+/// Builds a `size` by `size` map where roughly one tile in five holds a galaxy, leaving every
+/// tenth row and column empty so the expansion still has work to do
+fn generate_map(size: usize) -> GalaxyMap {
+    let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+    let mut input = String::new();
+    for row in 0..size {
+        for col in 0..size {
+            let is_galaxy = row % 10 != 9 && col % 10 != 9 && rng.u64(..5) == 0;
+            input.push(if is_galaxy { '#' } else { '.' });
+        }
+        input.push('\n');
+    }
+
+    input.parse().expect("generated map should parse")
+}
+
+fn pair_sum_benchmark(c: &mut Criterion) {
+    let map = generate_map(150);
+    let galaxies = map.galaxies();
+
+    let mut group = c.benchmark_group("dense_map_pair_sum");
+    group.sample_size(10);
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            let factor = black_box(2);
+            let mut sum = 0;
+            for (i, a) in galaxies.iter().enumerate() {
+                for b in &galaxies[i + 1..] {
+                    sum += map.expanded_distance(a, b, factor).unwrap();
+                }
+            }
+            sum
+        })
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| map.sum_galaxy_steps_pairwise(black_box(2), ProgressBar::hidden()))
+    });
+    group.bench_function("axis", |b| b.iter(|| map.sum_galaxy_steps(black_box(2))));
+    group.finish();
+}
+
+criterion_group!(benches, pair_sum_benchmark);
+criterion_main!(benches);
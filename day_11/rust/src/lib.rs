@@ -0,0 +1,632 @@
+use indicatif::{ParallelProgressIterator, ProgressBar};
+use rayon::prelude::*;
+use std::collections::BinaryHeap;
+use std::str::FromStr;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Galaxy {
+    /// Row in the [GalaxyMap] where this [Galaxy] is located
+    x: u64,
+    /// Column in the [GalaxyMap] where this [Galaxy] is located
+    y: u64,
+}
+
+impl Galaxy {
+    fn steps_to(&self, other: &Galaxy) -> u64 {
+        if self.x == other.x {
+            self.y.abs_diff(other.y)
+        } else if self.y == other.y {
+            self.x.abs_diff(other.x)
+        } else {
+            self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+        }
+    }
+}
+
+/// Yields every pair of indices `(i, j)` below `len` with `i < j`, so each unordered pair comes
+/// up exactly once
+#[cfg(test)]
+fn index_pairs(len: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..len).flat_map(move |i| (i + 1..len).map(move |j| (i, j)))
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GalaxyMap {
+    galaxies: Vec<Galaxy>,
+    width: u64,
+    height: u64,
+    /// Number of empty rows above each row, with an extra entry for the whole map at the end
+    #[cfg_attr(feature = "serde", serde(skip))]
+    empty_rows_before: Vec<u64>,
+    /// Number of empty columns left of each column, with an extra entry for the whole map at the
+    /// end
+    #[cfg_attr(feature = "serde", serde(skip))]
+    empty_cols_before: Vec<u64>,
+    /// Galaxy IDs sorted by column. Expansion never reorders columns, so this order holds for
+    /// every expansion factor.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    ids_by_x: Vec<usize>,
+}
+
+/// Counts how many of the first `i` lines hold no galaxy, for every `i` from 0 to `len`
+fn empty_prefix_sums(occupied: impl Iterator<Item = u64>, len: u64) -> Vec<u64> {
+    let mut is_occupied = vec![false; len as usize];
+    for line in occupied {
+        is_occupied[line as usize] = true;
+    }
+
+    let mut sums = Vec::with_capacity(is_occupied.len() + 1);
+    sums.push(0);
+    for occupied in is_occupied {
+        sums.push(sums[sums.len() - 1] + u64::from(!occupied));
+    }
+    sums
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseGalaxyMapError;
+
+impl FromStr for GalaxyMap {
+    type Err = ParseGalaxyMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut height = 0;
+        let galaxies = s
+            .lines()
+            .enumerate()
+            .flat_map(|(row_idx, line)| {
+                height += 1;
+                let row = row_idx as u64;
+                line.chars().enumerate().filter_map(move |(col_idx, c)| {
+                    let col = col_idx as u64;
+                    match c {
+                        '#' => Some(Galaxy { x: col, y: row }),
+                        _ => None,
+                    }
+                })
+            })
+            .collect::<Vec<Galaxy>>();
+
+        // There has to be a better way to do this
+        let width = galaxies
+            .iter()
+            .max_by(|&a, &b| a.x.cmp(&b.x))
+            .expect("failed to find rightmost Galaxy")
+            .x
+            + 1;
+
+        Ok(Self::new(galaxies, width, height))
+    }
+}
+
+/// Reads back the galaxies and the size of the map, working out the expansion again instead of
+/// trusting it
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for GalaxyMap {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct GalaxyMapData {
+            galaxies: Vec<Galaxy>,
+            width: u64,
+            height: u64,
+        }
+
+        let GalaxyMapData {
+            galaxies,
+            width,
+            height,
+        } = GalaxyMapData::deserialize(deserializer)?;
+        if let Some(galaxy) = galaxies
+            .iter()
+            .find(|galaxy| galaxy.x >= width || galaxy.y >= height)
+        {
+            return Err(serde::de::Error::custom(format!(
+                "{galaxy:?} is outside the {width}x{height} map"
+            )));
+        }
+        Ok(Self::new(galaxies, width, height))
+    }
+}
+
+impl GalaxyMap {
+    /// Works out the empty rows and columns of a `width` by `height` map holding `galaxies`
+    fn new(galaxies: Vec<Galaxy>, width: u64, height: u64) -> Self {
+        let empty_rows_before = empty_prefix_sums(galaxies.iter().map(|galaxy| galaxy.y), height);
+        let empty_cols_before = empty_prefix_sums(galaxies.iter().map(|galaxy| galaxy.x), width);
+        let mut ids_by_x: Vec<usize> = (0..galaxies.len()).collect();
+        ids_by_x.sort_by_key(|&id| galaxies[id].x);
+
+        Self {
+            galaxies,
+            width,
+            height,
+            empty_rows_before,
+            empty_cols_before,
+            ids_by_x,
+        }
+    }
+
+    /// Gets the steps between two galaxies once every empty row and column has grown to
+    /// `factor` rows or columns. The map itself is left as it is. Returns `None` if `factor` is 0
+    /// or the steps don't fit in a `u64`.
+    pub fn expanded_distance(&self, a: &Galaxy, b: &Galaxy, factor: u64) -> Option<u64> {
+        self.grown_distance(a, b, factor.checked_sub(1)?)
+    }
+
+    /// [GalaxyMap::expanded_distance] with every empty row and column gaining `growth` more
+    fn grown_distance(&self, a: &Galaxy, b: &Galaxy, growth: u64) -> Option<u64> {
+        let empty_between = |prefix_sums: &[u64], start: u64, end: u64| {
+            let (start, end) = (start.min(end) as usize, start.max(end) as usize);
+            prefix_sums[end] - prefix_sums[start]
+        };
+        let empty_lines = empty_between(&self.empty_rows_before, a.y, b.y)
+            + empty_between(&self.empty_cols_before, a.x, b.x);
+
+        a.steps_to(b).checked_add(empty_lines.checked_mul(growth)?)
+    }
+
+    pub fn galaxies(&self) -> &[Galaxy] {
+        &self.galaxies
+    }
+
+    /// Gets where `galaxy` ends up, as `(x, y)`, once every empty row and column has grown to
+    /// `factor` rows or columns. Returns `None` if `factor` is 0 or the position doesn't fit in a
+    /// `u64`.
+    pub fn expanded_position(&self, galaxy: &Galaxy, factor: u64) -> Option<(u64, u64)> {
+        self.grown_position(galaxy, factor.checked_sub(1)?)
+    }
+
+    /// [GalaxyMap::expanded_position] with every empty row and column gaining `growth` more
+    fn grown_position(&self, galaxy: &Galaxy, growth: u64) -> Option<(u64, u64)> {
+        let grow = |coord: u64, empty_before: &[u64]| {
+            coord.checked_add(empty_before[coord as usize].checked_mul(growth)?)
+        };
+        Some((
+            grow(galaxy.x, &self.empty_cols_before)?,
+            grow(galaxy.y, &self.empty_rows_before)?,
+        ))
+    }
+
+    /// Finds the `k` galaxies closest to the galaxy with ID `galaxy_id` once the empty space has
+    /// expanded by `factor`, as `(id, steps)` pairs from nearest to farthest. Ties go to the lower
+    /// ID. Returns `None` if there is no galaxy with that ID, `factor` is 0 or the expanded
+    /// distances don't fit in a `u64`.
+    ///
+    /// The search walks outwards through the galaxies sorted by column and stops once the column
+    /// gap alone is larger than the `k`th best distance found so far.
+    pub fn nearest(&self, galaxy_id: usize, k: usize, factor: u64) -> Option<Vec<(usize, u64)>> {
+        let galaxy = self.galaxies.get(galaxy_id)?;
+        let growth = factor.checked_sub(1)?;
+        let x = |id: usize| Some(self.grown_position(&self.galaxies[id], growth)?.0);
+        let origin_x = x(galaxy_id)?;
+
+        let first_in_column = self
+            .ids_by_x
+            .partition_point(|&id| self.galaxies[id].x < galaxy.x);
+        let position = first_in_column
+            + self.ids_by_x[first_in_column..]
+                .iter()
+                .position(|&id| id == galaxy_id)
+                .expect("every galaxy is in ids_by_x");
+
+        let mut best: BinaryHeap<(u64, usize)> = BinaryHeap::with_capacity(k + 1);
+        let (mut left, mut right) = (position, position + 1);
+        loop {
+            let left_gap = match left {
+                0 => None,
+                _ => Some(origin_x - x(self.ids_by_x[left - 1])?),
+            };
+            let right_gap = match self.ids_by_x.get(right) {
+                Some(&id) => Some(x(id)? - origin_x),
+                None => None,
+            };
+            let (gap, id) = match (left_gap, right_gap) {
+                (Some(left_gap), Some(right_gap)) if left_gap <= right_gap => {
+                    left -= 1;
+                    (left_gap, self.ids_by_x[left])
+                }
+                (Some(left_gap), None) => {
+                    left -= 1;
+                    (left_gap, self.ids_by_x[left])
+                }
+                (_, Some(right_gap)) => {
+                    right += 1;
+                    (right_gap, self.ids_by_x[right - 1])
+                }
+                (None, None) => break,
+            };
+
+            // Every galaxy left to visit is at least `gap` columns away
+            if best.len() == k && best.peek().is_some_and(|&(worst, _)| gap > worst) {
+                break;
+            }
+            best.push((self.grown_distance(galaxy, &self.galaxies[id], growth)?, id));
+            if best.len() > k {
+                best.pop();
+            }
+        }
+
+        Some(
+            best.into_sorted_vec()
+                .into_iter()
+                .map(|(steps, id)| (id, steps))
+                .collect(),
+        )
+    }
+
+    /// Sums the steps between every pair of galaxies after expanding the empty space by `factor`.
+    ///
+    /// Manhattan distances split into a sum along each axis, and along one axis the sorted
+    /// coordinates give every pair's distance as a difference that prefix sums can total. That
+    /// makes this `O(n log n)` instead of visiting every pair. Returns `None` if `factor` is 0 or
+    /// the sum doesn't fit in a `u64`.
+    pub fn sum_galaxy_steps(&self, factor: u64) -> Option<u64> {
+        let growth = factor.checked_sub(1)?;
+        let (xs, ys): (Vec<u64>, Vec<u64>) = self
+            .galaxies
+            .iter()
+            .map(|galaxy| self.grown_position(galaxy, growth))
+            .collect::<Option<Vec<(u64, u64)>>>()?
+            .into_iter()
+            .unzip();
+
+        axis_distance_sum(xs)?.checked_add(axis_distance_sum(ys)?)
+    }
+
+    /// Sums the steps between every pair of galaxies by visiting each pair, reporting progress to
+    /// `progress`. The pairs are split up in parallel by their first galaxy, and `progress`
+    /// advances once per first galaxy. Returns `None` if `factor` is 0 or the sum doesn't fit in a
+    /// `u64`.
+    pub fn sum_galaxy_steps_pairwise(&self, factor: u64, progress: ProgressBar) -> Option<u64> {
+        let growth = factor.checked_sub(1)?;
+        let galaxy_count = self.galaxies.len();
+        progress.set_length(galaxy_count as u64);
+
+        (0..galaxy_count)
+            .into_par_iter()
+            .progress_with(progress)
+            .map(|a| {
+                self.galaxies[a + 1..]
+                    .iter()
+                    .try_fold(0u64, |sum, galaxy_b| {
+                        sum.checked_add(self.grown_distance(&self.galaxies[a], galaxy_b, growth)?)
+                    })
+            })
+            .try_reduce(|| 0, u64::checked_add)
+    }
+}
+
+/// Sums the distances between every pair of `coords` on a line, or gives `None` if the sum
+/// doesn't fit in a `u64`
+fn axis_distance_sum(mut coords: Vec<u64>) -> Option<u64> {
+    coords.sort_unstable();
+
+    let mut total: u64 = 0;
+    let mut sum_before: u64 = 0;
+    for (i, &coord) in coords.iter().enumerate() {
+        // Every earlier coordinate is at most this one, so the distances to them add up to this
+        total = total.checked_add(coord.checked_mul(i as u64)? - sum_before)?;
+        sum_before = sum_before.checked_add(coord)?;
+    }
+    Some(total)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lazy_static::lazy_static;
+
+    const TEST_INPUT: &str = "...#......\n\
+                               .......#..\n\
+                               #.........\n\
+                               ..........\n\
+                               ......#...\n\
+                               .#........\n\
+                               .........#\n\
+                               ..........\n\
+                               .......#..\n\
+                               #...#.....";
+
+    lazy_static! {
+        static ref TEST_GALAXY_MAP: GalaxyMap = GalaxyMap {
+            width: 10,
+            height: 10,
+            galaxies: vec![
+                Galaxy { x: 3, y: 0 },
+                Galaxy { x: 7, y: 1 },
+                Galaxy { x: 0, y: 2 },
+                Galaxy { x: 6, y: 4 },
+                Galaxy { x: 1, y: 5 },
+                Galaxy { x: 9, y: 6 },
+                Galaxy { x: 7, y: 8 },
+                Galaxy { x: 0, y: 9 },
+                Galaxy { x: 4, y: 9 }
+            ],
+            ids_by_x: vec![2, 7, 4, 0, 8, 3, 1, 6, 5],
+            empty_rows_before: vec![0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2],
+            empty_cols_before: vec![0, 0, 0, 1, 1, 1, 2, 2, 2, 3, 3],
+        };
+    }
+
+    #[test]
+    fn pairwise_sum_matches_index_pairs() {
+        let test_map = TEST_GALAXY_MAP.clone();
+        let galaxies = test_map.galaxies();
+        let expected: Option<u64> = crate::index_pairs(galaxies.len())
+            .map(|(a, b)| test_map.expanded_distance(&galaxies[a], &galaxies[b], 7))
+            .sum();
+
+        assert_eq!(
+            test_map.sum_galaxy_steps_pairwise(7, ProgressBar::hidden()),
+            expected
+        );
+    }
+
+    /// Uses the pairwise sum as an oracle for the axis sum on small random maps
+    #[test]
+    fn axis_sum_matches_pairwise() {
+        let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+        for _ in 0..50 {
+            let size = 1 + rng.u64(..20);
+            let mut input = String::new();
+            for _ in 0..size {
+                for _ in 0..size {
+                    input.push(if rng.u64(..6) == 0 { '#' } else { '.' });
+                }
+                input.push('\n');
+            }
+            if !input.contains('#') {
+                continue;
+            }
+            let map = input.parse::<GalaxyMap>().unwrap();
+
+            for factor in [1, 2, 10, 1_000_000] {
+                assert_eq!(
+                    map.sum_galaxy_steps(factor),
+                    map.sum_galaxy_steps_pairwise(factor, ProgressBar::hidden()),
+                    "{input}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn nearest() {
+        let test_map = TEST_GALAXY_MAP.clone();
+
+        // Galaxy 5 from the puzzle text, numbered from 0 here
+        assert_eq!(test_map.nearest(4, 1, 2), Some(vec![(2, 5)]));
+        assert_eq!(
+            test_map.nearest(4, 3, 2),
+            Some(vec![(2, 5), (7, 6), (3, 8)])
+        );
+        assert_eq!(test_map.nearest(4, 0, 2), Some(vec![]));
+        // Galaxies 0 and 8 tie at 9 steps, and the lower ID comes first
+        assert_eq!(test_map.nearest(4, 5, 2).unwrap()[3..], [(0, 9), (8, 9)]);
+        assert_eq!(test_map.nearest(4, 100, 2).unwrap().len(), 8);
+        assert_eq!(test_map.nearest(9, 1, 2), None);
+    }
+
+    /// Checks `nearest` against sorting every other galaxy by distance on small random maps
+    #[test]
+    fn nearest_matches_full_sort() {
+        let mut rng = fastrand::Rng::with_seed(0x2545F4914F6CDD1D);
+
+        for _ in 0..50 {
+            let size = 1 + rng.u64(..15);
+            let mut input = String::new();
+            for _ in 0..size {
+                for _ in 0..size {
+                    input.push(if rng.u64(..5) == 0 { '#' } else { '.' });
+                }
+                input.push('\n');
+            }
+            if !input.contains('#') {
+                continue;
+            }
+            let map = input.parse::<GalaxyMap>().unwrap();
+            let galaxies = map.galaxies();
+
+            for factor in [1, 2, 100] {
+                for galaxy_id in 0..galaxies.len() {
+                    let mut expected: Vec<(usize, u64)> = (0..galaxies.len())
+                        .filter(|&id| id != galaxy_id)
+                        .map(|id| {
+                            let steps = map
+                                .expanded_distance(&galaxies[galaxy_id], &galaxies[id], factor)
+                                .unwrap();
+                            (id, steps)
+                        })
+                        .collect();
+                    expected.sort_by_key(|&(id, steps)| (steps, id));
+
+                    for k in [1, 3, galaxies.len()] {
+                        let expected = expected.iter().copied().take(k).collect();
+                        assert_eq!(map.nearest(galaxy_id, k, factor), Some(expected));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn axis_distance_sum() {
+        assert_eq!(crate::axis_distance_sum(vec![5, 1, 3]), Some(8));
+        assert_eq!(crate::axis_distance_sum(vec![2, 2]), Some(0));
+        assert_eq!(crate::axis_distance_sum(vec![]), Some(0));
+        assert_eq!(crate::axis_distance_sum(vec![0, u64::MAX, u64::MAX]), None);
+    }
+
+    #[test]
+    fn index_pairs() {
+        let pairs = crate::index_pairs(4).collect::<Vec<(usize, usize)>>();
+        assert_eq!(pairs, vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]);
+
+        assert_eq!(
+            crate::index_pairs(TEST_GALAXY_MAP.galaxies.len()).count(),
+            36
+        );
+        assert_eq!(crate::index_pairs(1).count(), 0);
+        assert_eq!(crate::index_pairs(0).count(), 0);
+    }
+
+    mod galaxy {
+        use crate::Galaxy;
+
+        #[test]
+        fn vertical_distance() {
+            let g0 = Galaxy { x: 4, y: 10 };
+            let g1 = Galaxy { x: 4, y: 3 };
+            let expected = 7;
+
+            assert_eq!(g0.steps_to(&g1), expected);
+        }
+
+        #[test]
+        fn horizontal_distance() {
+            let g0 = Galaxy { x: 10, y: 3 };
+            let g1 = Galaxy { x: 4, y: 3 };
+            let expected = 6;
+
+            assert_eq!(g0.steps_to(&g1), expected);
+        }
+
+        #[test]
+        fn diagonal_distance() {
+            let g0 = Galaxy { x: 1, y: 6 };
+            let g1 = Galaxy { x: 5, y: 11 };
+            let expected = 9;
+
+            assert_eq!(g0.steps_to(&g1), expected);
+        }
+    }
+
+    mod galaxy_map {
+        use crate::test::{TEST_GALAXY_MAP, TEST_INPUT};
+        use crate::GalaxyMap;
+
+        #[test]
+        fn parse_from_str() {
+            let actual_map = TEST_INPUT.parse::<GalaxyMap>();
+            assert!(actual_map.is_ok());
+
+            let actual_map = actual_map.unwrap();
+            assert_eq!(actual_map.height, TEST_GALAXY_MAP.height);
+            assert_eq!(actual_map.width, TEST_GALAXY_MAP.width);
+            assert_eq!(actual_map.galaxies, TEST_GALAXY_MAP.galaxies);
+            assert_eq!(
+                actual_map.empty_rows_before,
+                TEST_GALAXY_MAP.empty_rows_before
+            );
+            assert_eq!(
+                actual_map.empty_cols_before,
+                TEST_GALAXY_MAP.empty_cols_before
+            );
+            assert_eq!(actual_map.ids_by_x, TEST_GALAXY_MAP.ids_by_x);
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde_round_trip() {
+            let json = serde_json::to_value(&*TEST_GALAXY_MAP).unwrap();
+            assert_eq!(json["galaxies"][0], serde_json::json!({"x": 3, "y": 0}));
+            assert_eq!(json["width"], 10);
+            assert!(json.get("ids_by_x").is_none());
+            assert_eq!(
+                serde_json::from_value::<GalaxyMap>(json.clone()).unwrap(),
+                *TEST_GALAXY_MAP
+            );
+
+            let mut json = json;
+            json["height"] = serde_json::json!(9);
+            assert!(serde_json::from_value::<GalaxyMap>(json).is_err());
+        }
+
+        #[test]
+        fn expanded_distance() {
+            let test_map = TEST_GALAXY_MAP.clone();
+            let galaxies = &test_map.galaxies;
+
+            assert_eq!(
+                test_map.expanded_distance(&galaxies[4], &galaxies[8], 2),
+                Some(9)
+            );
+            assert_eq!(
+                test_map.expanded_distance(&galaxies[8], &galaxies[4], 2),
+                Some(9)
+            );
+            assert_eq!(
+                test_map.expanded_distance(&galaxies[0], &galaxies[6], 2),
+                Some(15)
+            );
+            assert_eq!(
+                test_map.expanded_distance(&galaxies[2], &galaxies[5], 2),
+                Some(17)
+            );
+            assert_eq!(
+                test_map.expanded_distance(&galaxies[7], &galaxies[8], 2),
+                Some(5)
+            );
+            assert_eq!(
+                test_map.expanded_distance(&galaxies[7], &galaxies[8], 1),
+                Some(4)
+            );
+        }
+
+        #[test]
+        fn zero_factor() {
+            // Empty space can't shrink away to nothing
+            let test_map = TEST_GALAXY_MAP.clone();
+            let galaxies = &test_map.galaxies;
+
+            assert_eq!(
+                test_map.expanded_distance(&galaxies[4], &galaxies[8], 0),
+                None
+            );
+            assert_eq!(test_map.expanded_position(&galaxies[4], 0), None);
+            assert_eq!(test_map.nearest(4, 1, 0), None);
+            assert_eq!(test_map.sum_galaxy_steps(0), None);
+            assert_eq!(
+                test_map.sum_galaxy_steps_pairwise(0, indicatif::ProgressBar::hidden()),
+                None
+            );
+        }
+
+        #[test]
+        fn overflowing_factor() {
+            let test_map = TEST_GALAXY_MAP.clone();
+            let galaxies = &test_map.galaxies;
+
+            assert_eq!(
+                test_map.expanded_distance(&galaxies[4], &galaxies[8], u64::MAX),
+                None
+            );
+            assert_eq!(test_map.expanded_position(&galaxies[4], u64::MAX), None);
+            assert_eq!(test_map.nearest(4, 1, u64::MAX), None);
+            assert_eq!(test_map.sum_galaxy_steps(u64::MAX / 4), None);
+            assert_eq!(
+                test_map.sum_galaxy_steps_pairwise(u64::MAX / 4, indicatif::ProgressBar::hidden()),
+                None
+            );
+        }
+
+        #[test]
+        fn sum_galaxy_steps_p1() {
+            let test_map = TEST_GALAXY_MAP.clone();
+
+            assert_eq!(test_map.sum_galaxy_steps(2), Some(374));
+        }
+
+        #[test]
+        fn sum_galaxy_steps_p2() {
+            let test_map = TEST_GALAXY_MAP.clone();
+
+            assert_eq!(test_map.sum_galaxy_steps(10), Some(1030));
+            assert_eq!(test_map.sum_galaxy_steps(100), Some(8410));
+            assert_eq!(test_map, TEST_GALAXY_MAP.clone());
+        }
+    }
+}
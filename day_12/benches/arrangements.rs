@@ -0,0 +1,80 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_12::ConditionRecord;
+use std::fs;
+
+/// Builds `count` records of 5 to 20 springs, half of them unknown, each with 1 to 5 groups of up
+/// to 4 damaged springs
+fn generate_records(count: usize) -> Vec<ConditionRecord> {
+    let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+    (0..count)
+        .map(|_| {
+            let springs: String = (0..5 + rng.u64(..16))
+                .map(|_| ['.', '#', '?', '?'][rng.usize(..4)])
+                .collect();
+            let groups: Vec<String> = (0..1 + rng.u64(..5))
+                .map(|_| (1 + rng.u64(..4)).to_string())
+                .collect();
+            format!("{springs} {}", groups.join(","))
+                .parse()
+                .expect("generated record should parse")
+        })
+        .collect()
+}
+
+fn load_records() -> Vec<ConditionRecord> {
+    match fs::read_to_string("input.txt") {
+        Ok(input) => input
+            .lines()
+            .map(|line| line.parse().expect("failed to parse condition records"))
+            .collect(),
+        Err(_) => {
+            println!("input.txt not found, benchmarking generated records instead");
+            generate_records(1000)
+        }
+    }
+}
+
+fn arrangements_benchmark(c: &mut Criterion) {
+    let records = load_records();
+    let unfolded: Vec<ConditionRecord> = records.iter().map(|record| record.unfold(5)).collect();
+
+    let mut group = c.benchmark_group("arrangements");
+    group.sample_size(10);
+    group.bench_function("brute_force", |b| {
+        b.iter(|| {
+            records
+                .iter()
+                .map(ConditionRecord::arrangements_brute_force)
+                .sum::<u64>()
+        })
+    });
+    group.bench_function("memoized", |b| {
+        b.iter(|| {
+            records
+                .iter()
+                .map(ConditionRecord::arrangements)
+                .sum::<u64>()
+        })
+    });
+    group.bench_function("memoized_unfolded", |b| {
+        b.iter(|| {
+            unfolded
+                .iter()
+                .map(ConditionRecord::arrangements)
+                .sum::<u64>()
+        })
+    });
+    group.bench_function("nonogram_unfolded", |b| {
+        b.iter(|| {
+            unfolded
+                .iter()
+                .map(ConditionRecord::arrangements_nonogram)
+                .sum::<u64>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, arrangements_benchmark);
+criterion_main!(benches);
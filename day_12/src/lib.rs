@@ -0,0 +1,283 @@
+mod nonogram;
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Condition {
+    Operational,
+    Damaged,
+    Unknown,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseConditionError;
+
+impl TryFrom<char> for Condition {
+    type Error = ParseConditionError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '.' => Ok(Self::Operational),
+            '#' => Ok(Self::Damaged),
+            '?' => Ok(Self::Unknown),
+            _ => Err(ParseConditionError),
+        }
+    }
+}
+
+/// One row of the condition records: what's known about each spring, and the sizes of the
+/// contiguous groups of damaged springs in the order they appear
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConditionRecord {
+    springs: Vec<Condition>,
+    damaged_groups: Vec<usize>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseConditionRecordError;
+
+impl FromStr for ConditionRecord {
+    type Err = ParseConditionRecordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (springs, groups) = s.split_once(' ').ok_or(ParseConditionRecordError)?;
+
+        let springs = springs
+            .chars()
+            .map(Condition::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .or(Err(ParseConditionRecordError))?;
+        let damaged_groups = groups
+            .split(',')
+            .map(|group| group.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+            .or(Err(ParseConditionRecordError))?;
+
+        Ok(Self {
+            springs,
+            damaged_groups,
+        })
+    }
+}
+
+/// Gets the sizes of the runs of damaged springs, left to right
+fn damaged_groups(springs: &[Condition]) -> Vec<usize> {
+    springs
+        .split(|&spring| spring != Condition::Damaged)
+        .filter(|run| !run.is_empty())
+        .map(<[Condition]>::len)
+        .collect()
+}
+
+/// Memoized arrangement counts, keyed by `(spring position, group index, current run length)`
+type ArrangementMemo = HashMap<(usize, usize, usize), u64>;
+
+impl ConditionRecord {
+    /// Unfolds the record the way part 2 reads it: the springs repeated `times` times with an
+    /// unknown spring between each copy, and the damaged groups repeated as many times
+    pub fn unfold(&self, times: usize) -> Self {
+        let mut springs = self.springs.clone();
+        for _ in 1..times {
+            springs.push(Condition::Unknown);
+            springs.extend_from_slice(&self.springs);
+        }
+
+        Self {
+            springs,
+            damaged_groups: self.damaged_groups.repeat(times),
+        }
+    }
+
+    /// Counts the ways to fill in the unknown springs that match the damaged groups.
+    ///
+    /// Walks the springs left to right, tracking which group is being filled and how long the
+    /// current run of damaged springs is. Many different fillings of the springs before a
+    /// position end up in the same state, so each state's count is only worked out once.
+    pub fn arrangements(&self) -> u64 {
+        let mut memo = ArrangementMemo::new();
+        self.count_from(0, 0, 0, &mut memo)
+    }
+
+    fn count_from(&self, pos: usize, group: usize, run: usize, memo: &mut ArrangementMemo) -> u64 {
+        let Some(&spring) = self.springs.get(pos) else {
+            // Done if every group has been closed, or if the run still open closes the last one
+            let all_closed = run == 0 && group == self.damaged_groups.len();
+            let last_closes =
+                group + 1 == self.damaged_groups.len() && run == self.damaged_groups[group];
+            return (all_closed || last_closes) as u64;
+        };
+        if let Some(&count) = memo.get(&(pos, group, run)) {
+            return count;
+        }
+
+        let mut count = 0;
+        if spring != Condition::Operational {
+            // Damaged, so the current run grows as long as its group has room left
+            if self
+                .damaged_groups
+                .get(group)
+                .is_some_and(|&size| run < size)
+            {
+                count += self.count_from(pos + 1, group, run + 1, memo);
+            }
+        }
+        if spring != Condition::Damaged {
+            // Operational, which closes the current run if there is one
+            if run == 0 {
+                count += self.count_from(pos + 1, group, 0, memo);
+            } else if run == self.damaged_groups[group] {
+                count += self.count_from(pos + 1, group + 1, 0, memo);
+            }
+        }
+
+        memo.insert((pos, group, run), count);
+        count
+    }
+
+    /// Same as [ConditionRecord::arrangements], by trying both conditions for every unknown
+    /// spring. Only usable on folded records.
+    pub fn arrangements_brute_force(&self) -> u64 {
+        let mut springs = self.springs.clone();
+        self.count_arrangements(&mut springs, 0)
+    }
+
+    fn count_arrangements(&self, springs: &mut [Condition], from: usize) -> u64 {
+        let Some(offset) = springs[from..]
+            .iter()
+            .position(|&spring| spring == Condition::Unknown)
+        else {
+            return (damaged_groups(springs) == self.damaged_groups) as u64;
+        };
+
+        let idx = from + offset;
+        let mut count = 0;
+        for condition in [Condition::Operational, Condition::Damaged] {
+            springs[idx] = condition;
+            count += self.count_arrangements(springs, idx + 1);
+        }
+        springs[idx] = Condition::Unknown;
+        count
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "???.### 1,1,3\n\
+                              .??..??...?##. 1,1,3\n\
+                              ?#?#?#?#?#?#?#? 1,3,1,6\n\
+                              ????.#...#... 4,1,1\n\
+                              ????.######..#####. 1,6,5\n\
+                              ?###???????? 3,2,1";
+
+    #[test]
+    fn parse_condition_record() {
+        let expected = ConditionRecord {
+            springs: vec![
+                Condition::Unknown,
+                Condition::Damaged,
+                Condition::Operational,
+            ],
+            damaged_groups: vec![1, 2],
+        };
+        assert_eq!("?#. 1,2".parse(), Ok(expected));
+
+        assert_eq!(
+            "?#x 1,2".parse::<ConditionRecord>(),
+            Err(ParseConditionRecordError)
+        );
+        assert_eq!(
+            "?#. 1,a".parse::<ConditionRecord>(),
+            Err(ParseConditionRecordError)
+        );
+        assert_eq!(
+            "?#.".parse::<ConditionRecord>(),
+            Err(ParseConditionRecordError)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let record = "?#. 2".parse::<ConditionRecord>().unwrap();
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "springs": ["Unknown", "Damaged", "Operational"],
+                "damaged_groups": [2],
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<ConditionRecord>(json).unwrap(),
+            record
+        );
+    }
+
+    #[test]
+    fn arrangements() {
+        let counts = TEST_INPUT
+            .lines()
+            .map(|line| line.parse::<ConditionRecord>().unwrap().arrangements())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![1, 4, 1, 1, 4, 10]);
+        assert_eq!(counts.iter().sum::<u64>(), 21);
+
+        for line in TEST_INPUT.lines() {
+            let record = line.parse::<ConditionRecord>().unwrap();
+            assert_eq!(record.arrangements_brute_force(), record.arrangements());
+        }
+    }
+
+    #[test]
+    fn unfold() {
+        let record = ".# 1".parse::<ConditionRecord>().unwrap();
+        assert_eq!(
+            record.unfold(5),
+            ".#?.#?.#?.#?.# 1,1,1,1,1".parse().unwrap()
+        );
+        assert_eq!(record.unfold(1), record);
+    }
+
+    #[test]
+    fn unfolded_arrangements() {
+        let counts = TEST_INPUT
+            .lines()
+            .map(|line| line.parse::<ConditionRecord>().unwrap())
+            .map(|record| record.unfold(5).arrangements())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![1, 16384, 1, 16, 2500, 506250]);
+        assert_eq!(counts.iter().sum::<u64>(), 525152);
+
+        for line in TEST_INPUT.lines() {
+            let record = line.parse::<ConditionRecord>().unwrap().unfold(5);
+            assert_eq!(record.arrangements_nonogram(), record.arrangements());
+        }
+    }
+
+    #[test]
+    fn arrangements_match_brute_force() {
+        let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+        for _ in 0..300 {
+            let springs: String = (0..1 + rng.u64(..14))
+                .map(|_| ['.', '#', '?', '?'][rng.usize(..4)])
+                .collect();
+            let groups: Vec<String> = (0..1 + rng.u64(..4))
+                .map(|_| (1 + rng.u64(..3)).to_string())
+                .collect();
+            let record = format!("{springs} {}", groups.join(","))
+                .parse::<ConditionRecord>()
+                .unwrap();
+            assert_eq!(
+                record.arrangements(),
+                record.arrangements_brute_force(),
+                "{record:?}"
+            );
+        }
+    }
+}
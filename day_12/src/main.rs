@@ -0,0 +1,53 @@
+use day_12::ConditionRecord;
+use std::{env, fs};
+
+/// How many copies of each record part 2 unfolds it into
+const UNFOLD_TIMES: usize = 5;
+
+fn main() {
+    let records = fs::read_to_string("input.txt")
+        .expect("failed to open input file")
+        .lines()
+        .map(|line| line.parse::<ConditionRecord>())
+        .collect::<Result<Vec<_>, _>>()
+        .expect("failed to parse condition records");
+
+    let arrangement_sum: u64 = records.iter().map(ConditionRecord::arrangements).sum();
+    println!("Part 1 result: {arrangement_sum}");
+
+    let unfolded_sum: u64 = records
+        .iter()
+        .map(|record| record.unfold(UNFOLD_TIMES).arrangements())
+        .sum();
+    println!("Part 2 result: {unfolded_sum}");
+
+    if env::args().any(|arg| arg == "--compare") {
+        let mismatches = records
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| {
+                let unfolded = record.unfold(UNFOLD_TIMES);
+                record.arrangements_nonogram() != record.arrangements()
+                    || unfolded.arrangements_nonogram() != unfolded.arrangements()
+            })
+            .map(|(i, _)| i + 1)
+            .collect::<Vec<usize>>();
+
+        println!(
+            "Nonogram results: {} and {}",
+            records
+                .iter()
+                .map(ConditionRecord::arrangements_nonogram)
+                .sum::<u64>(),
+            records
+                .iter()
+                .map(|record| record.unfold(UNFOLD_TIMES).arrangements_nonogram())
+                .sum::<u64>()
+        );
+        if mismatches.is_empty() {
+            println!("Both solvers agree on every record");
+        } else {
+            println!("The solvers disagree on lines {mismatches:?}");
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! A second way to count arrangements, reading each record as a line of a nonogram: the damaged
+//! groups are the clues, and each one is a block to place somewhere along the springs.
+
+use crate::{Condition, ConditionRecord};
+use std::ops::RangeInclusive;
+
+/// What's needed to check block placements quickly, worked out once per record
+struct Line<'a> {
+    springs: &'a [Condition],
+    /// How many operational springs come before each position, so any stretch can be checked
+    /// for them at once
+    operational_before: Vec<usize>,
+    /// The first damaged spring at or after each position, or the length of the line if none
+    next_damaged: Vec<usize>,
+    /// The last damaged spring before each position, if any
+    prev_damaged: Vec<Option<usize>>,
+}
+
+impl<'a> Line<'a> {
+    fn new(springs: &'a [Condition]) -> Self {
+        let mut operational_before = vec![0];
+        let mut prev_damaged = vec![None];
+        for (pos, &spring) in springs.iter().enumerate() {
+            let operational = usize::from(spring == Condition::Operational);
+            operational_before.push(operational_before[pos] + operational);
+            let damaged = (spring == Condition::Damaged).then_some(pos);
+            prev_damaged.push(damaged.or(prev_damaged[pos]));
+        }
+        let mut next_damaged = vec![springs.len(); springs.len() + 1];
+        for pos in (0..springs.len()).rev() {
+            next_damaged[pos] = match springs[pos] {
+                Condition::Damaged => pos,
+                _ => next_damaged[pos + 1],
+            };
+        }
+
+        Self {
+            springs,
+            operational_before,
+            next_damaged,
+            prev_damaged,
+        }
+    }
+
+    /// Whether a block of `len` damaged springs can start at `start`: none of its springs are
+    /// operational, and the springs either side of it aren't damaged
+    fn fits(&self, start: usize, len: usize) -> bool {
+        let end = start + len;
+        end <= self.springs.len()
+            && self.operational_before[end] == self.operational_before[start]
+            && (start == 0 || self.springs[start - 1] != Condition::Damaged)
+            && self.springs.get(end) != Some(&Condition::Damaged)
+    }
+
+    /// Narrows down where each block in `blocks` could start, returning `None` if there's nowhere
+    /// left for one of them.
+    ///
+    /// Each block starts out able to go anywhere that leaves room for the others, and then the
+    /// ranges are tightened until nothing changes: a block can't start where it doesn't fit, has
+    /// to start after the one before it ends, and can't leave a damaged spring uncovered between
+    /// itself and its neighbors.
+    fn block_ranges(&self, blocks: &[usize]) -> Option<Vec<RangeInclusive<usize>>> {
+        let needed = blocks.iter().sum::<usize>() + blocks.len().saturating_sub(1);
+        if needed > self.springs.len() {
+            return None;
+        }
+        let mut lo = vec![0; blocks.len()];
+        let mut hi = vec![self.springs.len(); blocks.len()];
+        for i in 0..blocks.len() {
+            if i > 0 {
+                lo[i] = lo[i - 1] + blocks[i - 1] + 1;
+            }
+            let i = blocks.len() - 1 - i;
+            hi[i] = match blocks.get(i + 1) {
+                Some(_) => hi[i + 1] - blocks[i] - 1,
+                None => self.springs.len() - blocks[i],
+            };
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for (i, &len) in blocks.iter().enumerate() {
+                let mut start = lo[i];
+                if i > 0 {
+                    start = start.max(lo[i - 1] + blocks[i - 1] + 1);
+                }
+                // The last damaged spring before the next block has to be covered by this one
+                let next_start = lo.get(i + 1).copied().unwrap_or(self.springs.len());
+                if let Some(damaged) = self.prev_damaged[next_start] {
+                    start = start.max((damaged + 1).saturating_sub(len));
+                }
+                while start <= hi[i] && !self.fits(start, len) {
+                    start += 1;
+                }
+                if start > hi[i] {
+                    return None;
+                }
+                changed |= start != lo[i];
+                lo[i] = start;
+            }
+
+            for (i, &len) in blocks.iter().enumerate().rev() {
+                let mut start = hi[i];
+                if i + 1 < blocks.len() {
+                    start = start.min(hi[i + 1] - len - 1);
+                }
+                // The first damaged spring after the previous block has to be covered by this one
+                let prev_end = if i > 0 { hi[i - 1] + blocks[i - 1] } else { 0 };
+                start = start.min(self.next_damaged[prev_end]);
+                loop {
+                    if start < lo[i] {
+                        return None;
+                    }
+                    if self.fits(start, len) {
+                        break;
+                    }
+                    start -= 1;
+                }
+                changed |= start != hi[i];
+                hi[i] = start;
+            }
+        }
+
+        Some(lo.into_iter().zip(hi).map(|(lo, hi)| lo..=hi).collect())
+    }
+}
+
+impl ConditionRecord {
+    /// Same as [ConditionRecord::arrangements], by placing the damaged groups as blocks rather
+    /// than walking the springs one at a time.
+    ///
+    /// Constraint propagation first narrows down the range each block could start in. Then the
+    /// placements are counted from the last block back. For each start in a block's range, the
+    /// count is how many ways there are to place the blocks after it. The next block has to
+    /// start before the first damaged spring past this one, so that's a sum over a range of the
+    /// next block's counts, which prefix sums make a single subtraction.
+    pub fn arrangements_nonogram(&self) -> u64 {
+        let line = Line::new(&self.springs);
+        let blocks = &self.damaged_groups;
+        let len = self.springs.len();
+        if blocks.is_empty() {
+            return u64::from(line.next_damaged[0] == len);
+        }
+        let Some(ranges) = line.block_ranges(blocks) else {
+            return 0;
+        };
+
+        // `counts[start]` is how many ways there are to place the blocks from the current one on
+        // with it starting at `start`, and `totals[start]` the sum of the counts before `start`
+        let mut counts = vec![0; len + 1];
+        let mut totals = vec![0; len + 2];
+        for (i, range) in ranges.iter().enumerate().rev() {
+            let block = blocks[i];
+            let mut next_counts = vec![0; len + 1];
+            for start in range.clone() {
+                if !line.fits(start, block) {
+                    continue;
+                }
+                let after = start + block + 1;
+                next_counts[start] = match ranges.get(i + 1) {
+                    // Nothing after the last block can be damaged
+                    None => u64::from(line.next_damaged[start + block] == len),
+                    Some(_) if after > len => 0,
+                    Some(next) => {
+                        let first = after.max(*next.start());
+                        let last = (*next.end()).min(line.next_damaged[after]);
+                        if first > last {
+                            0
+                        } else {
+                            totals[last + 1] - totals[first]
+                        }
+                    }
+                };
+            }
+            counts = next_counts;
+            for start in 0..=len {
+                totals[start + 1] = totals[start] + counts[start];
+            }
+        }
+
+        counts.iter().sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn block_ranges() {
+        let record = "???.### 1,1,3".parse::<ConditionRecord>().unwrap();
+        let line = Line::new(&record.springs);
+        assert_eq!(
+            line.block_ranges(&record.damaged_groups),
+            Some(vec![0..=0, 2..=2, 4..=4])
+        );
+
+        // The second block has to cover the damaged spring, from either side of it
+        let record = "?.??#? 1,2".parse::<ConditionRecord>().unwrap();
+        let line = Line::new(&record.springs);
+        assert_eq!(
+            line.block_ranges(&record.damaged_groups),
+            Some(vec![0..=2, 3..=4])
+        );
+
+        let record = "#.#.# 1,1".parse::<ConditionRecord>().unwrap();
+        let line = Line::new(&record.springs);
+        assert_eq!(line.block_ranges(&record.damaged_groups), None);
+    }
+
+    #[test]
+    fn arrangements_match_memoized() {
+        let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+        for _ in 0..300 {
+            let springs: String = (0..1 + rng.u64(..14))
+                .map(|_| ['.', '#', '?', '?'][rng.usize(..4)])
+                .collect();
+            let groups: Vec<String> = (0..1 + rng.u64(..4))
+                .map(|_| (1 + rng.u64(..3)).to_string())
+                .collect();
+            let record = format!("{springs} {}", groups.join(","))
+                .parse::<ConditionRecord>()
+                .unwrap();
+            assert_eq!(
+                record.arrangements_nonogram(),
+                record.arrangements(),
+                "{record:?}"
+            );
+            let unfolded = record.unfold(3);
+            assert_eq!(
+                unfolded.arrangements_nonogram(),
+                unfolded.arrangements(),
+                "{unfolded:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use std::fs;
+use std::str::FromStr;
+
+/// A pattern of ash (`.`) and rocks (`#`). Each row and each column is kept as a bit mask with a
+/// bit set for every rock, so comparing two of them is a single integer comparison.
+#[derive(Debug, Eq, PartialEq)]
+struct Pattern {
+    rows: Vec<u64>,
+    cols: Vec<u64>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ParsePatternError;
+
+/// The widest or tallest pattern whose lines still fit in a mask
+const MAX_SIDE: usize = u64::BITS as usize;
+
+impl FromStr for Pattern {
+    type Err = ParsePatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let width = s.lines().next().ok_or(ParsePatternError)?.len();
+        let height = s.lines().count();
+        if width == 0 || width > MAX_SIDE || height > MAX_SIDE {
+            return Err(ParsePatternError);
+        }
+
+        let mut rows = vec![0; height];
+        let mut cols = vec![0; width];
+        for (row, line) in s.lines().enumerate() {
+            if line.len() != width {
+                return Err(ParsePatternError);
+            }
+            for (col, c) in line.chars().enumerate() {
+                match c {
+                    '#' => {
+                        rows[row] |= 1 << col;
+                        cols[col] |= 1 << row;
+                    }
+                    '.' => {}
+                    _ => return Err(ParsePatternError),
+                }
+            }
+        }
+
+        Ok(Self { rows, cols })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Reflection {
+    /// A vertical line with this many columns to its left
+    Vertical(usize),
+    /// A horizontal line with this many rows above it
+    Horizontal(usize),
+}
+
+impl Reflection {
+    /// The number the puzzle asks for: columns left of a vertical line, or 100 times the rows
+    /// above a horizontal one
+    fn summary(&self) -> usize {
+        match self {
+            Reflection::Vertical(cols) => *cols,
+            Reflection::Horizontal(rows) => 100 * rows,
+        }
+    }
+}
+
+/// Finds where to put a mirror between `lines` so that exactly `smudges` cells differ from their
+/// reflection. Lines reflected past either end don't need to match anything.
+///
+/// With no smudges that's a perfect mirror. With one, the mirror only works after flipping a
+/// single cell, so a line that was already perfect never counts.
+fn reflection_index(lines: &[u64], smudges: u32) -> Option<usize> {
+    (1..lines.len()).find(|&idx| {
+        let mismatches: u32 = lines[..idx]
+            .iter()
+            .rev()
+            .zip(&lines[idx..])
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum();
+        mismatches == smudges
+    })
+}
+
+impl Pattern {
+    /// Finds the line of reflection once `smudges` cells have been fixed
+    fn reflection(&self, smudges: u32) -> Option<Reflection> {
+        reflection_index(&self.cols, smudges)
+            .map(Reflection::Vertical)
+            .or_else(|| reflection_index(&self.rows, smudges).map(Reflection::Horizontal))
+    }
+}
+
+fn parse_patterns(s: &str) -> Result<Vec<Pattern>, ParsePatternError> {
+    s.split("\n\n").map(|pattern| pattern.parse()).collect()
+}
+
+/// Adds up the summaries of every pattern's line of reflection with `smudges` fixed cells, or
+/// gives `None` if a pattern doesn't have one
+fn summarize(patterns: &[Pattern], smudges: u32) -> Option<usize> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            pattern
+                .reflection(smudges)
+                .map(|reflection| reflection.summary())
+        })
+        .sum()
+}
+
+fn main() {
+    let input = fs::read_to_string("input.txt")
+        .expect("failed to open input file")
+        .replace("\r\n", "\n");
+    let patterns = parse_patterns(input.trim()).expect("failed to parse patterns");
+
+    match summarize(&patterns, 0) {
+        Some(summary) => println!("Part 1 result: {summary}"),
+        None => println!("Part 1 result not found: a pattern has no line of reflection"),
+    }
+
+    match summarize(&patterns, 1) {
+        Some(summary) => println!("Part 2 result: {summary}"),
+        None => println!("Part 2 result not found: a pattern has no smudged line of reflection"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "#.##..##.\n\
+                              ..#.##.#.\n\
+                              ##......#\n\
+                              ##......#\n\
+                              ..#.##.#.\n\
+                              ..##..##.\n\
+                              #.#.##.#.\n\
+                              \n\
+                              #...##..#\n\
+                              #....#..#\n\
+                              ..##..###\n\
+                              #####.##.\n\
+                              #####.##.\n\
+                              ..##..###\n\
+                              #....#..#";
+
+    #[test]
+    fn parse_pattern() {
+        let expected = Pattern {
+            rows: vec![0b01, 0b11],
+            cols: vec![0b11, 0b10],
+        };
+        assert_eq!("#.\n##".parse(), Ok(expected));
+
+        assert_eq!("#.\n#".parse::<Pattern>(), Err(ParsePatternError));
+        assert_eq!("#x".parse::<Pattern>(), Err(ParsePatternError));
+        assert_eq!("".parse::<Pattern>(), Err(ParsePatternError));
+    }
+
+    #[test]
+    fn reflections() {
+        let patterns = parse_patterns(TEST_INPUT).unwrap();
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(patterns[0].reflection(0), Some(Reflection::Vertical(5)));
+        assert_eq!(patterns[1].reflection(0), Some(Reflection::Horizontal(4)));
+        assert_eq!(summarize(&patterns, 0), Some(405));
+
+        let no_reflection = "#.\n.#\n..".parse::<Pattern>().unwrap();
+        assert_eq!(no_reflection.reflection(0), None);
+    }
+
+    #[test]
+    fn smudged_reflections() {
+        let patterns = parse_patterns(TEST_INPUT).unwrap();
+        assert_eq!(patterns[0].reflection(1), Some(Reflection::Horizontal(3)));
+        assert_eq!(patterns[1].reflection(1), Some(Reflection::Horizontal(1)));
+        assert_eq!(summarize(&patterns, 1), Some(400));
+
+        // The perfect mirror after the first column doesn't count once a smudge is needed
+        let pattern = "###\n..#".parse::<Pattern>().unwrap();
+        assert_eq!(pattern.reflection(0), Some(Reflection::Vertical(1)));
+        assert_eq!(pattern.reflection(1), Some(Reflection::Vertical(2)));
+    }
+}
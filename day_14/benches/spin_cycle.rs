@@ -0,0 +1,69 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_14::{Direction, Platform};
+use std::fs;
+
+/// Builds a `side` by `side` platform where a fifth of the tiles are rounded rocks and a sixth are
+/// cube rocks
+fn generate_platform(side: usize) -> Platform {
+    let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+    let rows: Vec<String> = (0..side)
+        .map(|_| {
+            (0..side)
+                .map(|_| match rng.u64(..30) {
+                    0..=5 => 'O',
+                    6..=10 => '#',
+                    _ => '.',
+                })
+                .collect()
+        })
+        .collect();
+    rows.join("\n")
+        .parse()
+        .expect("generated platform should parse")
+}
+
+fn load_platform() -> Platform {
+    match fs::read_to_string("input.txt") {
+        Ok(input) => input.parse().expect("failed to parse platform"),
+        Err(_) => {
+            println!("input.txt not found, benchmarking a generated platform instead");
+            generate_platform(100)
+        }
+    }
+}
+
+fn spin_cycle_benchmark(c: &mut Criterion) {
+    // Start from a settled platform so every iteration does the same amount of work
+    let mut platform = load_platform();
+    for _ in 0..1000 {
+        platform.spin_cycle();
+    }
+
+    let mut group = c.benchmark_group("spin_cycle");
+    for direction in [
+        Direction::North,
+        Direction::West,
+        Direction::South,
+        Direction::East,
+    ] {
+        group.bench_function(format!("tilt_{direction:?}").to_lowercase(), |b| {
+            b.iter_batched_ref(
+                || platform.clone(),
+                |platform| platform.tilt(direction),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+    }
+    group.bench_function("cycle", |b| {
+        b.iter_batched_ref(
+            || platform.clone(),
+            |platform| platform.spin_cycle(),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, spin_cycle_benchmark);
+criterion_main!(benches);
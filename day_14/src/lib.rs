@@ -0,0 +1,411 @@
+pub mod render;
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+use std::str::FromStr;
+
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Tile {
+    Empty,
+    /// Rolls when the platform is tilted
+    RoundRock,
+    /// Stays put and stops the rounded rocks rolling into it
+    CubeRock,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseTileError;
+
+impl TryFrom<char> for Tile {
+    type Error = ParseTileError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '.' => Ok(Self::Empty),
+            'O' => Ok(Self::RoundRock),
+            '#' => Ok(Self::CubeRock),
+            _ => Err(ParseTileError),
+        }
+    }
+}
+
+impl From<Tile> for char {
+    fn from(value: Tile) -> Self {
+        match value {
+            Tile::Empty => '.',
+            Tile::RoundRock => 'O',
+            Tile::CubeRock => '#',
+        }
+    }
+}
+
+/// Which way the platform is tilted, so the way the rounded rocks roll
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    North,
+    West,
+    South,
+    East,
+}
+
+/// The order a spin cycle tilts the platform in
+pub const SPIN_CYCLE: [Direction; 4] = [
+    Direction::North,
+    Direction::West,
+    Direction::South,
+    Direction::East,
+];
+
+/// The platform of rocks, stored row by row in one flat list
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Platform {
+    width: usize,
+    height: usize,
+    tiles: Vec<Tile>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParsePlatformError;
+
+impl FromStr for Platform {
+    type Err = ParsePlatformError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let width = s.lines().next().map_or(0, str::len);
+        let mut tiles = vec![];
+        let mut height = 0;
+        for line in s.lines() {
+            if line.len() != width {
+                return Err(ParsePlatformError);
+            }
+            for c in line.chars() {
+                tiles.push(Tile::try_from(c).or(Err(ParsePlatformError))?);
+            }
+            height += 1;
+        }
+        if tiles.is_empty() {
+            return Err(ParsePlatformError);
+        }
+
+        Ok(Self {
+            width,
+            height,
+            tiles,
+        })
+    }
+}
+
+/// Checks the platform has tiles and that they fill out its rows before trusting them
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Platform {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct PlatformData {
+            width: usize,
+            height: usize,
+            tiles: Vec<Tile>,
+        }
+
+        let PlatformData {
+            width,
+            height,
+            tiles,
+        } = PlatformData::deserialize(deserializer)?;
+        if tiles.is_empty() || width.checked_mul(height) != Some(tiles.len()) {
+            return Err(serde::de::Error::custom(format!(
+                "{} tiles don't make a {width}x{height} platform",
+                tiles.len()
+            )));
+        }
+        Ok(Self {
+            width,
+            height,
+            tiles,
+        })
+    }
+}
+
+impl Display for Platform {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.tiles.chunks(self.width) {
+            let line: String = row.iter().map(|&tile| char::from(tile)).collect();
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Platform {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The tile at `row` and `col`, counting from the top left
+    pub fn get(&self, row: usize, col: usize) -> Tile {
+        self.tiles[row * self.width + col]
+    }
+
+    /// Rolls the rounded rocks along one line of `len` tiles, starting from the tile at `start`
+    /// that they roll towards and moving `step` tiles at a time away from it
+    fn roll_line(&mut self, start: usize, step: isize, len: usize) {
+        let index = |pos: usize| start.wrapping_add_signed(pos as isize * step);
+        let mut free_pos = 0;
+        for pos in 0..len {
+            match self.tiles[index(pos)] {
+                Tile::CubeRock => free_pos = pos + 1,
+                Tile::RoundRock => {
+                    self.tiles[index(pos)] = Tile::Empty;
+                    self.tiles[index(free_pos)] = Tile::RoundRock;
+                    free_pos += 1;
+                }
+                Tile::Empty => {}
+            }
+        }
+    }
+
+    /// Rolls every rounded rock towards `direction` until it hits the edge, a cube rock or
+    /// another rock that already stopped
+    pub fn tilt(&mut self, direction: Direction) {
+        let (width, height) = (self.width, self.height);
+        match direction {
+            Direction::North => {
+                for col in 0..width {
+                    self.roll_line(col, width as isize, height);
+                }
+            }
+            Direction::South => {
+                for col in 0..width {
+                    self.roll_line((height - 1) * width + col, -(width as isize), height);
+                }
+            }
+            Direction::West => {
+                for row in 0..height {
+                    self.roll_line(row * width, 1, width);
+                }
+            }
+            Direction::East => {
+                for row in 0..height {
+                    self.roll_line(row * width + width - 1, -1, width);
+                }
+            }
+        }
+    }
+
+    /// Tilts the platform north, west, south and then east
+    pub fn spin_cycle(&mut self) {
+        for direction in SPIN_CYCLE {
+            self.tilt(direction);
+        }
+    }
+
+    /// Works out how the platform ends up after `cycles` spin cycles. The rocks settle into a
+    /// loop of arrangements long before a billion cycles, so only the cycles until the first
+    /// repeat are run and the rest is skipped over.
+    pub fn after_spin_cycles(&self, cycles: usize) -> Platform {
+        let (states, loop_start) = find_cycle(self.clone(), |platform| {
+            let mut platform = platform.clone();
+            platform.spin_cycle();
+            platform
+        });
+
+        let idx = if cycles < states.len() {
+            cycles
+        } else {
+            loop_start + (cycles - loop_start) % (states.len() - loop_start)
+        };
+        states[idx].clone()
+    }
+
+    /// Each rounded rock puts as much load on the north support beams as it has rows between
+    /// itself and the south edge, counting its own
+    pub fn north_load(&self) -> usize {
+        self.tiles
+            .chunks(self.width)
+            .enumerate()
+            .map(|(row, tiles)| {
+                let rocks = tiles
+                    .iter()
+                    .filter(|&&tile| tile == Tile::RoundRock)
+                    .count();
+                rocks * (self.height - row)
+            })
+            .sum()
+    }
+}
+
+/// Applies `step` over and over starting from `start` until a state comes up a second time. Gives
+/// back every state seen in order, without the repeat, along with the index of the state it
+/// repeated; every state from there on loops forever.
+pub fn find_cycle<T: Hash + Eq + Clone>(
+    start: T,
+    mut step: impl FnMut(&T) -> T,
+) -> (Vec<T>, usize) {
+    let mut seen = HashMap::new();
+    let mut states = vec![];
+    let mut state = start;
+    loop {
+        if let Some(&loop_start) = seen.get(&state) {
+            return (states, loop_start);
+        }
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "O....#....\n\
+                              O.OO#....#\n\
+                              .....##...\n\
+                              OO.#O....O\n\
+                              .O.....O#.\n\
+                              O.#..O.#.#\n\
+                              ..O..#O..O\n\
+                              .......O..\n\
+                              #....###..\n\
+                              #OO..#....\n";
+
+    const TILTED_NORTH: &str = "OOOO.#.O..\n\
+                                OO..#....#\n\
+                                OO..O##..O\n\
+                                O..#.OO...\n\
+                                ........#.\n\
+                                ..#....#.#\n\
+                                ..O..#.O.O\n\
+                                ..O.......\n\
+                                #....###..\n\
+                                #....#....\n";
+
+    #[test]
+    fn parse_platform() {
+        let platform = TEST_INPUT.parse::<Platform>().unwrap();
+        assert_eq!((platform.width, platform.height), (10, 10));
+        assert_eq!(platform.get(0, 0), Tile::RoundRock);
+        assert_eq!(platform.get(0, 5), Tile::CubeRock);
+        assert_eq!(platform.to_string(), TEST_INPUT);
+
+        assert_eq!("O.\n.".parse::<Platform>(), Err(ParsePlatformError));
+        assert_eq!("O.\nx.".parse::<Platform>(), Err(ParsePlatformError));
+        assert_eq!("".parse::<Platform>(), Err(ParsePlatformError));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let platform = "O.\n.#".parse::<Platform>().unwrap();
+        let json = serde_json::to_value(&platform).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "width": 2,
+                "height": 2,
+                "tiles": ["RoundRock", "Empty", "Empty", "CubeRock"],
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<Platform>(json.clone()).unwrap(),
+            platform
+        );
+
+        let mut json = json;
+        json["height"] = serde_json::json!(3);
+        assert!(serde_json::from_value::<Platform>(json.clone()).is_err());
+        json["width"] = serde_json::json!(0);
+        json["tiles"] = serde_json::json!([]);
+        assert!(serde_json::from_value::<Platform>(json.clone()).is_err());
+        json["width"] = serde_json::json!(2);
+        json["height"] = serde_json::json!(0);
+        assert!(serde_json::from_value::<Platform>(json).is_err());
+    }
+
+    const AFTER_CYCLES: [&str; 3] = [
+        ".....#....\n\
+         ....#...O#\n\
+         ...OO##...\n\
+         .OO#......\n\
+         .....OOO#.\n\
+         .O#...O#.#\n\
+         ....O#....\n\
+         ......OOOO\n\
+         #...O###..\n\
+         #..OO#....\n",
+        ".....#....\n\
+         ....#...O#\n\
+         .....##...\n\
+         ..O#......\n\
+         .....OOO#.\n\
+         .O#...O#.#\n\
+         ....O#...O\n\
+         .......OOO\n\
+         #..OO###..\n\
+         #.OOO#...O\n",
+        ".....#....\n\
+         ....#...O#\n\
+         .....##...\n\
+         ..O#......\n\
+         .....OOO#.\n\
+         .O#...O#.#\n\
+         ....O#...O\n\
+         .......OOO\n\
+         #...O###.O\n\
+         #.OOO#...O\n",
+    ];
+
+    #[test]
+    fn tilt_north() {
+        let mut platform = TEST_INPUT.parse::<Platform>().unwrap();
+        platform.tilt(Direction::North);
+        assert_eq!(platform.to_string(), TILTED_NORTH);
+        assert_eq!(platform.north_load(), 136);
+    }
+
+    #[test]
+    fn tilt_each_way() {
+        let mut platform = "O.#.O\n.O..O".parse::<Platform>().unwrap();
+        platform.tilt(Direction::East);
+        assert_eq!(platform.to_string(), ".O#.O\n...OO\n");
+        platform.tilt(Direction::South);
+        assert_eq!(platform.to_string(), "..#.O\n.O.OO\n");
+        platform.tilt(Direction::West);
+        assert_eq!(platform.to_string(), "..#O.\nOOO..\n");
+        platform.tilt(Direction::North);
+        assert_eq!(platform.to_string(), "OO#O.\n..O..\n");
+    }
+
+    #[test]
+    fn spin_cycles() {
+        let mut platform = TEST_INPUT.parse::<Platform>().unwrap();
+        for expected in AFTER_CYCLES {
+            platform.spin_cycle();
+            assert_eq!(platform.to_string(), expected);
+        }
+
+        let platform = TEST_INPUT.parse::<Platform>().unwrap();
+        assert_eq!(platform.after_spin_cycles(3).to_string(), AFTER_CYCLES[2]);
+        assert_eq!(platform.after_spin_cycles(1_000_000_000).north_load(), 64);
+    }
+
+    #[test]
+    fn find_cycle_in_sequence() {
+        // 0, 1, 2, 3, 4, 2, ...
+        let (states, loop_start) = find_cycle(0, |&n| if n == 4 { 2 } else { n + 1 });
+        assert_eq!(states, vec![0, 1, 2, 3, 4]);
+        assert_eq!(loop_start, 2);
+
+        let (states, loop_start) = find_cycle(7, |&n| n);
+        assert_eq!(states, vec![7]);
+        assert_eq!(loop_start, 0);
+    }
+}
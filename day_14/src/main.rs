@@ -0,0 +1,103 @@
+use aoc_viz::{Renderer, Terminal, Video};
+use day_14::{find_cycle, render, Direction, Platform, SPIN_CYCLE};
+use std::time::Duration;
+use std::{env, fs, io, thread};
+
+/// How many spin cycles part 2 runs
+const SPIN_CYCLES: usize = 1_000_000_000;
+/// How long each frame of `--animate` stays up
+const FRAME_DELAY: Duration = Duration::from_millis(60);
+
+/// How many pixels across each rock is drawn in `--video`
+const VIDEO_CELL_PIXELS: u32 = 8;
+
+/// Draws the spin cycles to `renderer` up to the end of the first time round the loop, drawing
+/// the platform after every `stride` tilts and waiting `delay` after each one
+fn animate(platform: &Platform, stride: usize, renderer: &mut impl Renderer, delay: Duration) {
+    let (states, loop_start) = find_cycle(platform.clone(), |platform| {
+        let mut platform = platform.clone();
+        platform.spin_cycle();
+        platform
+    });
+
+    let mut draw = |platform: &Platform, in_loop: bool| {
+        renderer
+            .render(&render::platform_frame(platform, in_loop))
+            .expect("failed to draw the platform");
+        thread::sleep(delay);
+    };
+    draw(platform, loop_start == 0);
+    let mut tilts = 0;
+    for (cycle, state) in states.iter().enumerate() {
+        let mut platform = state.clone();
+        for (i, direction) in SPIN_CYCLE.into_iter().enumerate() {
+            platform.tilt(direction);
+            tilts += 1;
+            // The last tilt finishes the cycle, which might be the one that reaches the loop
+            let finished = cycle + usize::from(i + 1 == SPIN_CYCLE.len());
+            if tilts % stride == 0 {
+                draw(&platform, finished >= loop_start);
+            }
+        }
+    }
+
+    println!(
+        "After {loop_start} spin cycles the platform repeats every {} cycles",
+        states.len() - loop_start
+    );
+}
+
+fn main() {
+    let platform = fs::read_to_string("input.txt")
+        .expect("failed to open input file")
+        .parse::<Platform>()
+        .expect("failed to parse platform");
+    let args: Vec<String> = env::args().collect();
+
+    let stride = match args.iter().position(|arg| arg == "--stride") {
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|stride| stride.parse::<usize>().ok())
+            .filter(|&stride| stride > 0)
+            .expect("--stride needs a number of tilts"),
+        None => 1,
+    };
+    if args.iter().any(|arg| arg == "--animate") {
+        let mut terminal = Terminal::new(io::stdout(), render::palette());
+        animate(&platform, stride, &mut terminal, FRAME_DELAY);
+        return;
+    }
+    if let Some(i) = args.iter().position(|arg| arg == "--video") {
+        let path = args.get(i + 1).expect("--video needs a path to write to");
+        let mut video = Video::new(path, render::palette()).scale(VIDEO_CELL_PIXELS);
+        if let Some(i) = args.iter().position(|arg| arg == "--fps") {
+            let fps = args
+                .get(i + 1)
+                .and_then(|fps| fps.parse::<f64>().ok())
+                .filter(|&fps| fps > 0.0)
+                .expect("--fps needs a number of frames a second");
+            video = video.fps(fps);
+        }
+        if let Some(i) = args.iter().position(|arg| arg == "--size") {
+            let (width, height) = args
+                .get(i + 1)
+                .and_then(|size| size.split_once('x'))
+                .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+                .expect("--size needs a width and height in pixels, like 1280x720");
+            video = video.resolution(width, height);
+        }
+        animate(&platform, stride, &mut video, Duration::ZERO);
+        video
+            .finish()
+            .expect("failed to encode the video (is ffmpeg installed?)");
+        println!("Wrote the spin cycles to {path}");
+        return;
+    }
+
+    let mut tilted = platform.clone();
+    tilted.tilt(Direction::North);
+    println!("Part 1 result: {}", tilted.north_load());
+
+    let spun = platform.after_spin_cycles(SPIN_CYCLES);
+    println!("Part 2 result: {}", spun.north_load());
+}
@@ -0,0 +1,32 @@
+use crate::{Platform, Tile};
+use aoc_viz::{Color, Frame, Palette};
+
+/// Palette indices for drawing the platform. Empty tiles change color once the spin cycles have
+/// reached the loop part 2 skips through.
+const EMPTY: u8 = 0;
+const ROUND_ROCK: u8 = 1;
+const CUBE_ROCK: u8 = 2;
+const EMPTY_IN_LOOP: u8 = 3;
+
+pub fn palette() -> Palette {
+    Palette::new([
+        Color::from(0x202020),
+        Color::from(0xE0E0E0),
+        Color::from(0x6A5ACD),
+        Color::from(0x10304A),
+    ])
+}
+
+/// Draws each tile of `platform` as one cell, with empty tiles in the loop's color if `in_loop`
+pub fn platform_frame(platform: &Platform, in_loop: bool) -> Frame {
+    Frame::from_fn(
+        platform.width(),
+        platform.height(),
+        |(row, col)| match platform.get(row, col) {
+            Tile::Empty if in_loop => EMPTY_IN_LOOP,
+            Tile::Empty => EMPTY,
+            Tile::RoundRock => ROUND_ROCK,
+            Tile::CubeRock => CUBE_ROCK,
+        },
+    )
+}
@@ -0,0 +1,97 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day_15::holiday_hash_map::hash_str;
+use day_15::{box_lenses_streaming, sum_of_hashes_streaming, InitSequence, Steps};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Wraps the system allocator to count how many allocations a benchmark makes
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn count_allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(f());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+/// Builds a sequence of `step_count` steps over a few thousand labels of two to four letters,
+/// with one step in four taking a lens out
+fn generate_sequence(step_count: usize) -> String {
+    let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+    let mut steps = Vec::with_capacity(step_count);
+    for _ in 0..step_count {
+        let label_len = 2 + rng.u64(..3);
+        let label: String = (0..label_len)
+            .map(|_| (b'a' + rng.u8(..8)) as char)
+            .collect();
+        if rng.u64(..4) == 0 {
+            steps.push(format!("{label}-"));
+        } else {
+            steps.push(format!("{label}={}", 1 + rng.u64(..9)));
+        }
+    }
+    steps.join(",")
+}
+
+fn sequence_benchmark(c: &mut Criterion) {
+    let sequence = generate_sequence(1_000_000);
+    let owned_steps_hash_sum = || {
+        Steps::new(sequence.as_bytes())
+            .map(|step| hash_str(&step.unwrap()))
+            .sum::<u64>()
+    };
+    let streaming_hash_sum = || sum_of_hashes_streaming(sequence.as_bytes()).unwrap();
+    let streaming_box_lenses = || box_lenses_streaming(sequence.as_bytes()).unwrap();
+    let parsed_box_lenses = || {
+        sequence
+            .parse::<InitSequence>()
+            .unwrap()
+            .box_lenses()
+            .focusing_power()
+    };
+
+    println!("allocations for a million steps:");
+    println!(
+        "  owned steps hash sum: {}",
+        count_allocations(owned_steps_hash_sum)
+    );
+    println!(
+        "  streaming hash sum:   {}",
+        count_allocations(streaming_hash_sum)
+    );
+    println!(
+        "  streaming box lenses: {}",
+        count_allocations(streaming_box_lenses)
+    );
+    println!(
+        "  parsed box lenses:    {}",
+        count_allocations(parsed_box_lenses)
+    );
+
+    let mut group = c.benchmark_group("million_step_sequence");
+    group.sample_size(10);
+    group.bench_function("owned_steps_hash_sum", |b| b.iter(owned_steps_hash_sum));
+    group.bench_function("streaming_hash_sum", |b| b.iter(streaming_hash_sum));
+    group.bench_function("streaming_box_lenses", |b| b.iter(streaming_box_lenses));
+    group.bench_function("parsed_box_lenses", |b| b.iter(parsed_box_lenses));
+    group.finish();
+}
+
+criterion_group!(benches, sequence_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,307 @@
+/// Number of boxes in a [HolidayHashMap], one for every possible hash
+const BOX_COUNT: usize = 256;
+
+/// Runs the Holiday ASCII String Helper algorithm on `s`, giving a number from 0 to 255
+pub fn hash_str(s: &str) -> u64 {
+    hash_bytes(s.as_bytes())
+}
+
+/// [hash_str] over raw bytes. The puzzle only uses ASCII, where every character is one byte.
+pub fn hash_bytes(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0u8, |hash, &byte| hash.wrapping_add(byte).wrapping_mul(17)) as u64
+}
+
+/// Labels up to this many bytes are kept inline in their box instead of on the heap
+const INLINE_LABEL_LEN: usize = 15;
+
+/// A label as stored in a box. The puzzle's labels are a handful of letters, so storing one
+/// almost never needs an allocation.
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum Label {
+    Inline {
+        len: u8,
+        bytes: [u8; INLINE_LABEL_LEN],
+    },
+    Heap(Box<str>),
+}
+
+impl Label {
+    fn new(label: &str) -> Self {
+        if label.len() > INLINE_LABEL_LEN {
+            return Label::Heap(label.into());
+        }
+        let mut bytes = [0; INLINE_LABEL_LEN];
+        bytes[..label.len()].copy_from_slice(label.as_bytes());
+        Label::Inline {
+            len: label.len() as u8,
+            bytes,
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Label::Inline { len, bytes } => &bytes[..*len as usize],
+            Label::Heap(label) => label.as_bytes(),
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        std::str::from_utf8(self.as_bytes()).expect("labels are copied from a str")
+    }
+
+    fn is(&self, label: &str) -> bool {
+        self.as_bytes() == label.as_bytes()
+    }
+}
+
+/// The Holiday ASCII String Helper Manual Arrangement Procedure: a hash map with one box per
+/// possible [hash_str] value. Each box keeps its entries in insertion order in a list, so labels
+/// that hash to the same box are chained one after another.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct HolidayHashMap<V> {
+    boxes: Vec<Vec<(Label, V)>>,
+}
+
+impl<V> Default for HolidayHashMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> HolidayHashMap<V> {
+    pub fn new() -> Self {
+        Self {
+            boxes: (0..BOX_COUNT).map(|_| vec![]).collect(),
+        }
+    }
+
+    fn box_for(&self, label: &str) -> &Vec<(Label, V)> {
+        &self.boxes[hash_str(label) as usize]
+    }
+
+    fn box_for_mut(&mut self, label: &str) -> &mut Vec<(Label, V)> {
+        &mut self.boxes[hash_str(label) as usize]
+    }
+
+    /// Stores `value` under `label`. A label that's already in its box keeps its place and has its
+    /// old value replaced and returned; a new label goes to the back of its box.
+    pub fn insert(&mut self, label: &str, value: V) -> Option<V> {
+        let lens_box = self.box_for_mut(label);
+        match lens_box.iter_mut().find(|(other, _)| other.is(label)) {
+            Some((_, old_value)) => Some(std::mem::replace(old_value, value)),
+            None => {
+                lens_box.push((Label::new(label), value));
+                None
+            }
+        }
+    }
+
+    /// Takes `label` out of its box, moving everything behind it forward
+    pub fn remove(&mut self, label: &str) -> Option<V> {
+        let lens_box = self.box_for_mut(label);
+        let idx = lens_box.iter().position(|(other, _)| other.is(label))?;
+        Some(lens_box.remove(idx).1)
+    }
+
+    pub fn get(&self, label: &str) -> Option<&V> {
+        self.box_for(label)
+            .iter()
+            .find(|(other, _)| other.is(label))
+            .map(|(_, value)| value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.boxes.iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boxes.iter().all(Vec::is_empty)
+    }
+
+    /// Visits the entries of box `box_id` front to back as `(label, value)`
+    pub fn box_contents(&self, box_id: usize) -> impl Iterator<Item = (&str, &V)> {
+        self.boxes[box_id]
+            .iter()
+            .map(|(label, value)| (label.as_str(), value))
+    }
+
+    /// Visits every entry as `(box number, slot in the box, label, value)`, box by box and front
+    /// to back within each box
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &str, &V)> {
+        self.boxes
+            .iter()
+            .enumerate()
+            .flat_map(|(box_id, lens_box)| {
+                lens_box
+                    .iter()
+                    .enumerate()
+                    .map(move |(slot, (label, value))| (box_id, slot, label.as_str(), value))
+            })
+    }
+}
+
+/// Written out as the label itself
+#[cfg(feature = "serde")]
+impl serde::Serialize for Label {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Label {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let label = String::deserialize(deserializer)?;
+        Ok(Label::new(&label))
+    }
+}
+
+/// Written out as the list of boxes, each a list of `[label, value]` pairs front to back
+#[cfg(feature = "serde")]
+impl<V: serde::Serialize> serde::Serialize for HolidayHashMap<V> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.boxes.serialize(serializer)
+    }
+}
+
+/// Checks there's a box for every hash, and that every label is in the box it hashes to and
+/// only there once, so lookups still find it
+#[cfg(feature = "serde")]
+impl<'de, V: serde::Deserialize<'de>> serde::Deserialize<'de> for HolidayHashMap<V> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let boxes = Vec::<Vec<(Label, V)>>::deserialize(deserializer)?;
+        if boxes.len() != BOX_COUNT {
+            return Err(D::Error::invalid_length(boxes.len(), &"256 boxes"));
+        }
+        for (box_id, lens_box) in boxes.iter().enumerate() {
+            for (slot, (label, _)) in lens_box.iter().enumerate() {
+                let label = label.as_str();
+                if hash_str(label) as usize != box_id {
+                    return Err(D::Error::custom(format!(
+                        "{label} is in box {box_id} but belongs in box {}",
+                        hash_str(label)
+                    )));
+                }
+                if lens_box[..slot].iter().any(|(other, _)| other.is(label)) {
+                    return Err(D::Error::custom(format!(
+                        "{label} is in box {box_id} more than once"
+                    )));
+                }
+            }
+        }
+        Ok(Self { boxes })
+    }
+}
+
+impl HolidayHashMap<u64> {
+    /// Adds up every lens's focusing power: one more than its box number, times one more than its
+    /// slot, times its focal length
+    pub fn focusing_power(&self) -> u64 {
+        self.iter()
+            .map(|(box_id, slot, _, focal_length)| {
+                (box_id as u64 + 1) * (slot as u64 + 1) * focal_length
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hash_str_test() {
+        let input = "HASH";
+        assert_eq!(hash_str(input), 52);
+
+        // Same results as working through the characters with wide arithmetic
+        for label in ["rn=1", "cm-", "qp", "zzzzzzzzzzzz=9", ""] {
+            let by_chars = label
+                .chars()
+                .fold(0u64, |hash, c| (hash + c as u64) * 17 % 256);
+            assert_eq!(hash_bytes(label.as_bytes()), by_chars);
+        }
+    }
+
+    #[test]
+    fn insert_and_get() {
+        let mut map = HolidayHashMap::new();
+        assert!(map.is_empty());
+
+        assert_eq!(map.insert("rn", 1), None);
+        assert_eq!(map.insert("cm", 2), None);
+        assert_eq!(map.insert("rn", 5), Some(1));
+
+        assert_eq!(map.get("rn"), Some(&5));
+        assert_eq!(map.get("cm"), Some(&2));
+        assert_eq!(map.get("qp"), None);
+        assert_eq!(map.len(), 2);
+
+        // Too long to keep inline
+        let long_label = "abcdefghijklmnopqrstuvwxyz";
+        assert_eq!(map.insert(long_label, 3), None);
+        assert_eq!(map.get(long_label), Some(&3));
+        assert_eq!(map.get(&long_label[..15]), None);
+        assert_eq!(map.remove(long_label), Some(3));
+    }
+
+    #[test]
+    fn remove_keeps_order() {
+        let mut map = HolidayHashMap::new();
+        // All three land in box 3
+        map.insert("pc", 4);
+        map.insert("ot", 9);
+        map.insert("ab", 5);
+
+        assert_eq!(map.remove("pc"), Some(4));
+        assert_eq!(map.remove("pc"), None);
+
+        let entries: Vec<(usize, usize, &str, &u64)> = map.iter().collect();
+        assert_eq!(entries, vec![(3, 0, "ot", &9), (3, 1, "ab", &5)]);
+        let contents: Vec<(&str, &u64)> = map.box_contents(3).collect();
+        assert_eq!(contents, vec![("ot", &9), ("ab", &5)]);
+        assert_eq!(map.box_contents(0).count(), 0);
+
+        // Replacing a value keeps the lens in its slot
+        map.insert("ot", 7);
+        assert_eq!(map.iter().next(), Some((3, 0, "ot", &7)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut map = HolidayHashMap::new();
+        map.insert("ot", 9);
+        map.insert("ab", 5);
+        map.insert("abcdefghijklmnopqrstuvwxyz", 1);
+
+        let mut json = serde_json::to_value(&map).unwrap();
+        assert_eq!(json[3], serde_json::json!([["ot", 9], ["ab", 5]]));
+        assert_eq!(
+            serde_json::from_value::<HolidayHashMap<u64>>(json.clone()).unwrap(),
+            map
+        );
+
+        // "ot" hashes to box 3, so it can't be read back into box 0
+        json[0] = serde_json::json!([["ot", 1]]);
+        assert!(serde_json::from_value::<HolidayHashMap<u64>>(json).is_err());
+        let json = serde_json::json!([[]]);
+        assert!(serde_json::from_value::<HolidayHashMap<u64>>(json).is_err());
+    }
+
+    #[test]
+    fn focusing_power() {
+        let mut map = HolidayHashMap::new();
+        map.insert("rn", 1);
+        map.insert("cm", 2);
+        map.insert("ot", 7);
+        map.insert("ab", 5);
+        map.insert("pc", 6);
+
+        assert_eq!(map.focusing_power(), 145);
+    }
+}
@@ -0,0 +1,403 @@
+pub mod holiday_hash_map;
+
+use holiday_hash_map::{hash_str, HolidayHashMap};
+use std::fmt::{Display, Formatter};
+use std::io::{self, BufRead, Write};
+use std::ops::Range;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub enum ParseInitSequenceError {
+    /// The step isn't a label followed by `-` or by `=` and a focal length
+    InvalidStep {
+        index: usize,
+        step: String,
+    },
+    Io(io::Error),
+}
+
+impl Display for ParseInitSequenceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseInitSequenceError::InvalidStep { index, step } => {
+                write!(f, "step {index} ('{step}') is not a valid instruction")
+            }
+            ParseInitSequenceError::Io(err) => write!(f, "failed to read the sequence: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseInitSequenceError {}
+
+impl From<io::Error> for ParseInitSequenceError {
+    fn from(err: io::Error) -> Self {
+        ParseInitSequenceError::Io(err)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+enum Operation {
+    Remove,
+    Insert(u64),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct InitSequence {
+    sequence: String,
+    hashes: Vec<u64>,
+    /// Where each step's label sits in `sequence`, so labels are never copied out of it
+    steps: Vec<(Range<usize>, Operation)>,
+}
+
+impl FromStr for InitSequence {
+    type Err = ParseInitSequenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut hashes = vec![];
+        let mut steps = vec![];
+        let mut start = 0;
+        for (index, step) in s.split(',').enumerate() {
+            let (label, op) = parse_step(index, step)?;
+            hashes.push(hash_str(step));
+            steps.push((start..start + label.len(), op));
+            start += step.len() + 1;
+        }
+
+        Ok(Self {
+            sequence: s.to_string(),
+            hashes,
+            steps,
+        })
+    }
+}
+
+/// How an [InitSequence] is written out: the sequence itself, then each of its steps broken down
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct InitSequenceData<'a> {
+    sequence: &'a str,
+    steps: Vec<StepData<'a>>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct StepData<'a> {
+    label: &'a str,
+    operation: &'a Operation,
+    hash: u64,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for InitSequence {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let steps = self
+            .steps
+            .iter()
+            .zip(&self.hashes)
+            .map(|((label, operation), &hash)| StepData {
+                label: &self.sequence[label.clone()],
+                operation,
+                hash,
+            })
+            .collect();
+        InitSequenceData {
+            sequence: &self.sequence,
+            steps,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Read back by parsing the sequence again, so the steps always point at the right labels
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for InitSequence {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct Sequence {
+            sequence: String,
+        }
+
+        let Sequence { sequence } = Sequence::deserialize(deserializer)?;
+        sequence.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Splits the `index`th step into its label and what to do with that label's lens
+fn parse_step(index: usize, step: &str) -> Result<(&str, Operation), ParseInitSequenceError> {
+    let parsed = if let Some(label) = step.strip_suffix('-') {
+        Some((label, Operation::Remove))
+    } else {
+        step.split_once('=').and_then(|(label, focal_length)| {
+            let focal_length = focal_length.parse::<u64>().ok()?;
+            Some((label, Operation::Insert(focal_length)))
+        })
+    };
+
+    match parsed {
+        Some((label, op)) if !label.is_empty() && !label.contains(['=', '-']) => Ok((label, op)),
+        _ => Err(ParseInitSequenceError::InvalidStep {
+            index,
+            step: step.to_string(),
+        }),
+    }
+}
+
+impl InitSequence {
+    pub fn sum_of_hashes(&self) -> u64 {
+        self.hashes.iter().sum()
+    }
+
+    /// Runs every step, placing lenses in and taking them out of their boxes by label
+    pub fn box_lenses(&self) -> HolidayHashMap<u64> {
+        let mut lens_boxes = HolidayHashMap::new();
+
+        for (label, op) in &self.steps {
+            apply_step(&mut lens_boxes, &self.sequence[label.clone()], op);
+        }
+
+        lens_boxes
+    }
+}
+
+fn apply_step(lens_boxes: &mut HolidayHashMap<u64>, label: &str, op: &Operation) {
+    match op {
+        Operation::Insert(focal_length) => {
+            lens_boxes.insert(label, *focal_length);
+        }
+        Operation::Remove => {
+            lens_boxes.remove(label);
+        }
+    }
+}
+
+/// Reads the comma-separated steps of an initialization sequence one at a time, so only the
+/// current step is ever held in memory. Line breaks are ignored, like in the puzzle input.
+pub struct Steps<R> {
+    reader: R,
+    buf: Vec<u8>,
+}
+
+impl<R: BufRead> Steps<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: vec![],
+        }
+    }
+
+    /// Reads the next step into the reused buffer and borrows it from there, so going through the
+    /// whole sequence this way doesn't allocate once the buffer has grown to the longest step
+    pub fn next_step(&mut self) -> Option<io::Result<&str>> {
+        loop {
+            self.buf.clear();
+            // `read_until` keeps reading across the reader's internal buffer refills
+            match self.reader.read_until(b',', &mut self.buf) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(err) => return Some(Err(err)),
+            }
+
+            self.buf
+                .retain(|&byte| byte != b',' && byte != b'\n' && byte != b'\r');
+            if self.buf.is_empty() {
+                // Only a line break was left at the end of the input
+                continue;
+            }
+            let step = std::str::from_utf8(&self.buf)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err));
+            return Some(step);
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Steps<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_step().map(|step| step.map(str::to_string))
+    }
+}
+
+/// Same as [InitSequence::sum_of_hashes], reading the steps from `reader` as they're needed
+pub fn sum_of_hashes_streaming(reader: impl BufRead) -> Result<u64, ParseInitSequenceError> {
+    let mut steps = Steps::new(reader);
+    let mut sum = 0;
+    let mut index = 0;
+    while let Some(step) = steps.next_step() {
+        let step = step?;
+        parse_step(index, step)?;
+        sum += hash_str(step);
+        index += 1;
+    }
+    Ok(sum)
+}
+
+/// Same as [InitSequence::box_lenses], reading the steps from `reader` as they're needed
+pub fn box_lenses_streaming(
+    reader: impl BufRead,
+) -> Result<HolidayHashMap<u64>, ParseInitSequenceError> {
+    box_lenses_with(reader, |_, _, _| Ok(()))
+}
+
+/// Same as [box_lenses_streaming], also writing the contents of the box each step touched to
+/// `out`, laid out like the puzzle's worked example
+pub fn box_lenses_traced(
+    reader: impl BufRead,
+    mut out: impl Write,
+) -> Result<HolidayHashMap<u64>, ParseInitSequenceError> {
+    box_lenses_with(reader, |step, box_id, lens_boxes| {
+        writeln!(out, "After \"{step}\":")?;
+        write!(out, "Box {box_id}:")?;
+        for (label, focal_length) in lens_boxes.box_contents(box_id) {
+            write!(out, " [{label} {focal_length}]")?;
+        }
+        writeln!(out, "\n")
+    })
+}
+
+/// Runs every step from `reader`, calling `after_step` with the step, the box it touched and the
+/// boxes as they are after it
+fn box_lenses_with(
+    reader: impl BufRead,
+    mut after_step: impl FnMut(&str, usize, &HolidayHashMap<u64>) -> io::Result<()>,
+) -> Result<HolidayHashMap<u64>, ParseInitSequenceError> {
+    let mut steps = Steps::new(reader);
+    let mut lens_boxes = HolidayHashMap::new();
+    let mut index = 0;
+    while let Some(step) = steps.next_step() {
+        let step = step?;
+        let (label, op) = parse_step(index, step)?;
+        apply_step(&mut lens_boxes, label, &op);
+        after_step(step, hash_str(label) as usize, &lens_boxes)?;
+        index += 1;
+    }
+    Ok(lens_boxes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::BufReader;
+
+    const TEST_INPUT: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
+    #[test]
+    fn init_sequence_sum_of_hashes() {
+        let init_seq = TEST_INPUT.trim_end().parse::<InitSequence>().unwrap();
+        let expected = 1320;
+
+        assert_eq!(init_seq.sum_of_hashes(), expected);
+    }
+
+    #[test]
+    fn box_lenses_focusing_power() {
+        let init_seq = TEST_INPUT.trim_end().parse::<InitSequence>().unwrap();
+        let lens_boxes = init_seq.box_lenses();
+
+        assert_eq!(lens_boxes.get("ot"), Some(&7));
+        assert_eq!(lens_boxes.get("qp"), None);
+        assert_eq!(lens_boxes.focusing_power(), 145);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let init_seq = "rn=1,cm-".parse::<InitSequence>().unwrap();
+        let json = serde_json::to_value(&init_seq).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "sequence": "rn=1,cm-",
+                "steps": [
+                    {"label": "rn", "operation": {"Insert": 1}, "hash": 30},
+                    {"label": "cm", "operation": "Remove", "hash": 253},
+                ],
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<InitSequence>(json).unwrap(),
+            init_seq
+        );
+
+        let json = serde_json::json!({"sequence": "rn=1,cm"});
+        assert!(serde_json::from_value::<InitSequence>(json).is_err());
+    }
+
+    #[test]
+    fn streaming_steps() {
+        // A tiny buffer makes most steps straddle a refill
+        let input = format!("{TEST_INPUT}\n");
+        let reader = BufReader::with_capacity(3, input.as_bytes());
+        let steps = Steps::new(reader)
+            .collect::<io::Result<Vec<String>>>()
+            .unwrap();
+        let expected: Vec<&str> = TEST_INPUT.split(',').collect();
+        assert_eq!(steps, expected);
+
+        let steps = Steps::new("ab=1,\ncd-\n".as_bytes())
+            .collect::<io::Result<Vec<String>>>()
+            .unwrap();
+        assert_eq!(steps, vec!["ab=1", "cd-"]);
+        assert_eq!(Steps::new("".as_bytes()).count(), 0);
+    }
+
+    #[test]
+    fn streaming_results() {
+        let reader = BufReader::with_capacity(4, TEST_INPUT.as_bytes());
+        assert_eq!(sum_of_hashes_streaming(reader).unwrap(), 1320);
+
+        let reader = BufReader::with_capacity(4, TEST_INPUT.as_bytes());
+        let lens_boxes = box_lenses_streaming(reader).unwrap();
+        let init_seq = TEST_INPUT.parse::<InitSequence>().unwrap();
+        assert_eq!(lens_boxes, init_seq.box_lenses());
+        assert_eq!(lens_boxes.focusing_power(), 145);
+    }
+
+    #[test]
+    fn invalid_steps() {
+        for bad_step in ["ab=", "ab=x", "ab", "=3", "-", "ab-3", "ab=-3", "a-b=3"] {
+            let input = format!("rn=1,{bad_step},cm-");
+            match input.parse::<InitSequence>() {
+                Err(ParseInitSequenceError::InvalidStep { index, step }) => {
+                    assert_eq!((index, step.as_str()), (1, bad_step))
+                }
+                other => panic!("'{bad_step}' gave {other:?}"),
+            }
+            assert!(matches!(
+                box_lenses_streaming(input.as_bytes()),
+                Err(ParseInitSequenceError::InvalidStep { index: 1, .. })
+            ));
+        }
+
+        let err = sum_of_hashes_streaming("qp=3,ab=\n".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "step 1 ('ab=') is not a valid instruction");
+    }
+
+    #[test]
+    fn trace_shows_affected_box() {
+        let mut trace = vec![];
+        let lens_boxes =
+            box_lenses_traced("rn=1,cm-,qp=3,cm=2,qp-".as_bytes(), &mut trace).unwrap();
+        assert_eq!(lens_boxes.len(), 2);
+
+        let expected = "\
+After \"rn=1\":
+Box 0: [rn 1]
+
+After \"cm-\":
+Box 0: [rn 1]
+
+After \"qp=3\":
+Box 1: [qp 3]
+
+After \"cm=2\":
+Box 0: [rn 1] [cm 2]
+
+After \"qp-\":
+Box 1:
+
+";
+        assert_eq!(String::from_utf8(trace).unwrap(), expected);
+    }
+}
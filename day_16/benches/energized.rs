@@ -0,0 +1,58 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_16::{max_energized_tiles, BeamCache, Contraption};
+use indicatif::ProgressBar;
+use std::fs;
+
+/// Builds a `side` by `side` contraption where one tile in ten is a mirror or splitter, with the
+/// four kinds equally likely
+fn generate_contraption(side: usize) -> Contraption {
+    let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+    let rows: Vec<String> = (0..side)
+        .map(|_| {
+            (0..side)
+                .map(|_| match rng.u64(..40) {
+                    0 => '/',
+                    1 => '\\',
+                    2 => '|',
+                    3 => '-',
+                    _ => '.',
+                })
+                .collect()
+        })
+        .collect();
+    rows.join("\n")
+        .parse()
+        .expect("generated contraption should parse")
+}
+
+fn load_contraption() -> Contraption {
+    match fs::read_to_string("input.txt") {
+        Ok(input) => input.parse().expect("failed to parse contraption"),
+        Err(_) => {
+            println!("input.txt not found, benchmarking a generated contraption instead");
+            generate_contraption(110)
+        }
+    }
+}
+
+fn energized_benchmark(c: &mut Criterion) {
+    let contraption = load_contraption();
+
+    let mut group = c.benchmark_group("max_energized");
+    group.sample_size(10);
+    group.bench_function("parallel_sweep", |b| {
+        b.iter(|| max_energized_tiles(&contraption, ProgressBar::hidden()))
+    });
+    group.bench_function("cached", |b| {
+        b.iter(|| BeamCache::new(&contraption).max_energized_tiles())
+    });
+    let cache = BeamCache::new(&contraption);
+    group.bench_function("cached_sweep_only", |b| {
+        b.iter(|| cache.max_energized_tiles())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, energized_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,275 @@
+//! Shares the work of following beams between entry points. Every beam that hits a splitter side
+//! on energizes the same tiles from there on no matter where it came from, so that's worked out
+//! once per splitter and reused.
+
+use crate::{entry_points, Contraption, Direction, Tile};
+
+/// A set of tiles, one bit each in the same order as the contraption's tiles
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct TileSet(Vec<u64>);
+
+impl TileSet {
+    fn new(tiles: usize) -> Self {
+        Self(vec![0; tiles.div_ceil(64)])
+    }
+
+    fn insert(&mut self, index: usize) {
+        self.0[index / 64] |= 1 << (index % 64);
+    }
+
+    fn union_with(&mut self, other: &TileSet) {
+        for (word, other) in self.0.iter_mut().zip(&other.0) {
+            *word |= other;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
+/// The tiles one beam passes through until it either leaves the contraption or hits a splitter
+/// side on, and which splitter that is
+struct Segment {
+    tiles: TileSet,
+    hits: Option<usize>,
+}
+
+/// Follows a single beam from `start` heading `direction`, without following anything a splitter
+/// sends out. `splitter_at` gives each splitter's number, by tile.
+fn follow(
+    contraption: &Contraption,
+    splitter_at: &[Option<usize>],
+    start: (usize, usize),
+    direction: Direction,
+) -> Segment {
+    let mut tiles = TileSet::new(splitter_at.len());
+    let (mut pos, mut direction) = (start, direction);
+    // A beam that hasn't stopped after visiting every tile heading every way is going round in
+    // a loop of mirrors, and has already been everywhere it'll go
+    for _ in 0..4 * splitter_at.len() {
+        let Some(&tile) = contraption.get(pos) else {
+            break;
+        };
+        let index = contraption.index(pos);
+        tiles.insert(index);
+
+        let (next_direction, split) = tile.redirect(direction);
+        if split.is_some() {
+            return Segment {
+                tiles,
+                hits: splitter_at[index],
+            };
+        }
+        match contraption.step(pos, next_direction) {
+            Some(next) => (pos, direction) = (next, next_direction),
+            None => break,
+        }
+    }
+
+    Segment { tiles, hits: None }
+}
+
+/// Tarjan's strongly connected components over the splitters, where each splitter leads to the
+/// ones its two beams hit. Splitters can feed back into each other in loops, and every splitter
+/// in a loop energizes exactly the same tiles, so they're all given one set.
+struct Components<'a> {
+    hits: &'a [Vec<usize>],
+    tiles: &'a [TileSet],
+    /// When each splitter was first visited
+    order: Vec<Option<usize>>,
+    next_order: usize,
+    /// The earliest visited splitter each one can get back to through the ones still on the stack
+    low: Vec<usize>,
+    stack: Vec<usize>,
+    on_stack: Vec<bool>,
+    downstream: Vec<Option<TileSet>>,
+}
+
+impl Components<'_> {
+    fn visit(&mut self, splitter: usize) {
+        let order = self.next_order;
+        self.next_order += 1;
+        self.order[splitter] = Some(order);
+        self.low[splitter] = order;
+        self.stack.push(splitter);
+        self.on_stack[splitter] = true;
+
+        let hits = self.hits;
+        for &next in &hits[splitter] {
+            match self.order[next] {
+                None => {
+                    self.visit(next);
+                    self.low[splitter] = self.low[splitter].min(self.low[next]);
+                }
+                Some(next_order) if self.on_stack[next] => {
+                    self.low[splitter] = self.low[splitter].min(next_order);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if self.low[splitter] == order {
+            // Everything this component leads to outside itself was finished before it
+            let mut members = vec![];
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                members.push(member);
+                if member == splitter {
+                    break;
+                }
+            }
+            let mut tiles = self.tiles[splitter].clone();
+            for &member in &members {
+                tiles.union_with(&self.tiles[member]);
+                for &next in &hits[member] {
+                    if let Some(downstream) = &self.downstream[next] {
+                        tiles.union_with(downstream);
+                    }
+                }
+            }
+            for member in members {
+                self.downstream[member] = Some(tiles.clone());
+            }
+        }
+    }
+}
+
+/// Everything needed to find the tiles a beam energizes without following it past the first
+/// splitter it hits side on
+pub struct BeamCache<'a> {
+    contraption: &'a Contraption,
+    /// The number of each splitter, by tile
+    splitter_at: Vec<Option<usize>>,
+    /// Every tile energized once a beam hits each splitter side on, including the splitter
+    downstream: Vec<TileSet>,
+}
+
+impl<'a> BeamCache<'a> {
+    /// Follows the two beams out of every splitter once, then combines them through all the
+    /// splitters they lead to
+    pub fn new(contraption: &'a Contraption) -> Self {
+        let mut splitter_at = vec![None; contraption.width() * contraption.height()];
+        let mut splitters = vec![];
+        for row in 0..contraption.height() {
+            for col in 0..contraption.width() {
+                let outputs = match contraption.get((row, col)) {
+                    Some(Tile::VerticalSplitter) => [Direction::Up, Direction::Down],
+                    Some(Tile::HorizontalSplitter) => [Direction::Left, Direction::Right],
+                    _ => continue,
+                };
+                splitter_at[contraption.index((row, col))] = Some(splitters.len());
+                splitters.push(((row, col), outputs));
+            }
+        }
+
+        let mut tiles = vec![];
+        let mut hits = vec![];
+        for &(pos, outputs) in &splitters {
+            let mut energized = TileSet::new(splitter_at.len());
+            energized.insert(contraption.index(pos));
+            let mut next = vec![];
+            for direction in outputs {
+                if let Some(start) = contraption.step(pos, direction) {
+                    let segment = follow(contraption, &splitter_at, start, direction);
+                    energized.union_with(&segment.tiles);
+                    next.extend(segment.hits);
+                }
+            }
+            tiles.push(energized);
+            hits.push(next);
+        }
+
+        let mut components = Components {
+            hits: &hits,
+            tiles: &tiles,
+            order: vec![None; splitters.len()],
+            next_order: 0,
+            low: vec![0; splitters.len()],
+            stack: vec![],
+            on_stack: vec![false; splitters.len()],
+            downstream: vec![None; splitters.len()],
+        };
+        for splitter in 0..splitters.len() {
+            if components.order[splitter].is_none() {
+                components.visit(splitter);
+            }
+        }
+
+        Self {
+            contraption,
+            splitter_at,
+            downstream: components.downstream.into_iter().flatten().collect(),
+        }
+    }
+
+    /// Same as [crate::energized_tiles], only following the beam as far as the first splitter
+    pub fn energized_tiles(&self, start: (usize, usize), direction: Direction) -> usize {
+        let mut segment = follow(self.contraption, &self.splitter_at, start, direction);
+        if let Some(splitter) = segment.hits {
+            segment.tiles.union_with(&self.downstream[splitter]);
+        }
+        segment.tiles.len()
+    }
+
+    /// Same as [crate::max_energized_tiles]. With the splitters already done each entry point is
+    /// quick, so they're just tried one after another.
+    pub fn max_energized_tiles(&self) -> usize {
+        self.best_entry_point().map_or(0, |(start, direction)| {
+            self.energized_tiles(start, direction)
+        })
+    }
+
+    /// The entry point whose beam energizes the most tiles, the first one found if there's a tie
+    pub fn best_entry_point(&self) -> Option<((usize, usize), Direction)> {
+        entry_points(self.contraption)
+            .into_iter()
+            .rev()
+            .max_by_key(|&(start, direction)| self.energized_tiles(start, direction))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::energized_tiles;
+
+    #[test]
+    fn matches_following_every_beam() {
+        let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+        for _ in 0..50 {
+            let (width, height) = (1 + rng.u64(..12), 1 + rng.u64(..12));
+            let rows: Vec<String> = (0..height)
+                .map(|_| {
+                    (0..width)
+                        .map(|_| ['.', '.', '.', '/', '\\', '|', '-'][rng.usize(..7)])
+                        .collect()
+                })
+                .collect();
+            let contraption = rows.join("\n").parse::<Contraption>().unwrap();
+            let cache = BeamCache::new(&contraption);
+            for (start, direction) in entry_points(&contraption) {
+                assert_eq!(
+                    cache.energized_tiles(start, direction),
+                    energized_tiles(&contraption, start, direction),
+                    "{start:?} {direction:?} in\n{}",
+                    rows.join("\n")
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn splitter_loops() {
+        // Both splitters send beams round into each other, so they end up sharing one set
+        let contraption = "/-\\\n|.|\n\\-/".parse::<Contraption>().unwrap();
+        let cache = BeamCache::new(&contraption);
+        assert_eq!(cache.downstream.len(), 4);
+        assert!(cache
+            .downstream
+            .iter()
+            .all(|tiles| tiles == &cache.downstream[0]));
+        assert_eq!(cache.energized_tiles((1, 1), Direction::Right), 9);
+    }
+}
@@ -0,0 +1,143 @@
+use std::str::FromStr;
+
+/// A way to move across a [Grid2D], one tile at a time
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// A different bit for each direction, so a set of them fits in one byte
+    pub fn bit(self) -> u8 {
+        match self {
+            Direction::Up => 0b0001,
+            Direction::Down => 0b0010,
+            Direction::Left => 0b0100,
+            Direction::Right => 0b1000,
+        }
+    }
+}
+
+/// A rectangle of tiles stored row by row in one flat list. Positions are `(row, col)`, counting
+/// from the top left.
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Grid2D<T> {
+    width: usize,
+    height: usize,
+    tiles: Vec<T>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseGridError;
+
+impl<T: TryFrom<char>> FromStr for Grid2D<T> {
+    type Err = ParseGridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let width = s.lines().next().map_or(0, str::len);
+        let mut tiles = vec![];
+        let mut height = 0;
+        for line in s.lines() {
+            if line.len() != width {
+                return Err(ParseGridError);
+            }
+            for c in line.chars() {
+                tiles.push(T::try_from(c).or(Err(ParseGridError))?);
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            width,
+            height,
+            tiles,
+        })
+    }
+}
+
+/// Checks the grid has tiles and that they fill out its rows before trusting them
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Grid2D<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct GridData<T> {
+            width: usize,
+            height: usize,
+            tiles: Vec<T>,
+        }
+
+        let GridData {
+            width,
+            height,
+            tiles,
+        } = GridData::deserialize(deserializer)?;
+        if tiles.is_empty() || width.checked_mul(height) != Some(tiles.len()) {
+            return Err(serde::de::Error::custom(format!(
+                "{} tiles don't make a {width}x{height} grid",
+                tiles.len()
+            )));
+        }
+        Ok(Self {
+            width,
+            height,
+            tiles,
+        })
+    }
+}
+
+impl<T> Grid2D<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Where the tile at `pos` sits in the flat list, for keeping other data alongside the grid
+    pub fn index(&self, (row, col): (usize, usize)) -> usize {
+        row * self.width + col
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> Option<&T> {
+        let (row, col) = pos;
+        if row < self.height && col < self.width {
+            self.tiles.get(self.index(pos))
+        } else {
+            None
+        }
+    }
+
+    /// The position one tile from `pos` towards `direction`, or `None` past the edge
+    pub fn step(&self, (row, col): (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        let next = match direction {
+            Direction::Up => (row.checked_sub(1)?, col),
+            Direction::Down => (row + 1, col),
+            Direction::Left => (row, col.checked_sub(1)?),
+            Direction::Right => (row, col + 1),
+        };
+        (next.0 < self.height && next.1 < self.width).then_some(next)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn steps_stay_inside() {
+        let grid = "ab\ncd".parse::<Grid2D<char>>().unwrap();
+        assert_eq!(grid.get((1, 0)), Some(&'c'));
+        assert_eq!(grid.get((0, 2)), None);
+        assert_eq!(grid.step((0, 0), Direction::Right), Some((0, 1)));
+        assert_eq!(grid.step((0, 0), Direction::Down), Some((1, 0)));
+        assert_eq!(grid.step((0, 0), Direction::Up), None);
+        assert_eq!(grid.step((0, 1), Direction::Right), None);
+        assert_eq!("ab\nc".parse::<Grid2D<char>>(), Err(ParseGridError));
+    }
+}
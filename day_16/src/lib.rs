@@ -0,0 +1,230 @@
+mod beam_cache;
+mod grid;
+#[cfg(feature = "render")]
+pub mod render;
+
+pub use beam_cache::BeamCache;
+pub use grid::{Direction, Grid2D};
+use indicatif::{ParallelProgressIterator, ProgressBar};
+use rayon::prelude::*;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Tile {
+    Empty,
+    /// `/`
+    ForwardMirror,
+    /// `\`
+    BackMirror,
+    /// `|`
+    VerticalSplitter,
+    /// `-`
+    HorizontalSplitter,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseTileError;
+
+impl TryFrom<char> for Tile {
+    type Error = ParseTileError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '.' => Ok(Self::Empty),
+            '/' => Ok(Self::ForwardMirror),
+            '\\' => Ok(Self::BackMirror),
+            '|' => Ok(Self::VerticalSplitter),
+            '-' => Ok(Self::HorizontalSplitter),
+            _ => Err(ParseTileError),
+        }
+    }
+}
+
+impl Tile {
+    /// Which way a beam heading `direction` leaves this tile, along with the second beam a
+    /// splitter sends out when it's hit side on
+    fn redirect(self, direction: Direction) -> (Direction, Option<Direction>) {
+        use Direction::*;
+        match (self, direction) {
+            (Tile::ForwardMirror, Up) => (Right, None),
+            (Tile::ForwardMirror, Down) => (Left, None),
+            (Tile::ForwardMirror, Left) => (Down, None),
+            (Tile::ForwardMirror, Right) => (Up, None),
+            (Tile::BackMirror, Up) => (Left, None),
+            (Tile::BackMirror, Down) => (Right, None),
+            (Tile::BackMirror, Left) => (Up, None),
+            (Tile::BackMirror, Right) => (Down, None),
+            (Tile::VerticalSplitter, Left | Right) => (Up, Some(Down)),
+            (Tile::HorizontalSplitter, Up | Down) => (Left, Some(Right)),
+            _ => (direction, None),
+        }
+    }
+}
+
+pub type Contraption = Grid2D<Tile>;
+
+/// Follows a beam entering the tile at `start` heading `direction` and counts the tiles it and
+/// every beam split off from it pass through.
+pub fn energized_tiles(
+    contraption: &Contraption,
+    start: (usize, usize),
+    direction: Direction,
+) -> usize {
+    beam_directions(contraption, start, direction)
+        .iter()
+        .filter(|&&directions| directions != 0)
+        .count()
+}
+
+/// Follows a beam entering the tile at `start` heading `direction`, along with every beam split
+/// off from it, and gives the directions beams came into each tile heading as a set of
+/// [Direction::bit]s. The tiles are in the same order as [Grid2D::index].
+///
+/// Beams can go round in loops, so every tile remembers which directions beams have already
+/// come into it heading, and a beam stops once it's doing something that has been done before.
+pub fn beam_directions(
+    contraption: &Contraption,
+    start: (usize, usize),
+    direction: Direction,
+) -> Vec<u8> {
+    let mut seen = vec![0u8; contraption.width() * contraption.height()];
+    let mut beams = vec![(start, direction)];
+    while let Some((pos, direction)) = beams.pop() {
+        let Some(&tile) = contraption.get(pos) else {
+            continue;
+        };
+        let seen_here = &mut seen[contraption.index(pos)];
+        if *seen_here & direction.bit() != 0 {
+            continue;
+        }
+        *seen_here |= direction.bit();
+
+        let (direction, split) = tile.redirect(direction);
+        for direction in [Some(direction), split].into_iter().flatten() {
+            if let Some(next) = contraption.step(pos, direction) {
+                beams.push((next, direction));
+            }
+        }
+    }
+
+    seen
+}
+
+/// Every tile on the edge of `contraption` along with the direction a beam coming in from outside
+/// there heads. Corner tiles show up twice, once from each side.
+fn entry_points(contraption: &Contraption) -> Vec<((usize, usize), Direction)> {
+    let (width, height) = (contraption.width(), contraption.height());
+    let mut entries = vec![];
+    for col in 0..width {
+        entries.push(((0, col), Direction::Down));
+        entries.push(((height - 1, col), Direction::Up));
+    }
+    for row in 0..height {
+        entries.push(((row, 0), Direction::Right));
+        entries.push(((row, width - 1), Direction::Left));
+    }
+    entries
+}
+
+/// Tries a beam from every entry point and gives the most tiles any of them energizes. The entry
+/// points are split up in parallel, and `progress` advances once per entry point.
+pub fn max_energized_tiles(contraption: &Contraption, progress: ProgressBar) -> usize {
+    let entries = entry_points(contraption);
+    progress.set_length(entries.len() as u64);
+
+    entries
+        .into_par_iter()
+        .progress_with(progress)
+        .map(|(start, direction)| energized_tiles(contraption, start, direction))
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = ".|...\\....\n\
+                              |.-.\\.....\n\
+                              .....|-...\n\
+                              ........|.\n\
+                              ..........\n\
+                              .........\\\n\
+                              ..../.\\\\..\n\
+                              .-.-/..|..\n\
+                              .|....-|.\\\n\
+                              ..//.|....\n";
+
+    #[test]
+    fn redirect_beams() {
+        assert_eq!(
+            Tile::ForwardMirror.redirect(Direction::Right),
+            (Direction::Up, None)
+        );
+        assert_eq!(
+            Tile::BackMirror.redirect(Direction::Right),
+            (Direction::Down, None)
+        );
+        assert_eq!(
+            Tile::VerticalSplitter.redirect(Direction::Up),
+            (Direction::Up, None)
+        );
+        assert_eq!(
+            Tile::HorizontalSplitter.redirect(Direction::Down),
+            (Direction::Left, Some(Direction::Right))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let contraption = "./\n|-".parse::<Contraption>().unwrap();
+        let json = serde_json::to_value(&contraption).unwrap();
+        assert_eq!(
+            json["tiles"],
+            serde_json::json!([
+                "Empty",
+                "ForwardMirror",
+                "VerticalSplitter",
+                "HorizontalSplitter"
+            ])
+        );
+        assert_eq!(
+            serde_json::from_value::<Contraption>(json.clone()).unwrap(),
+            contraption
+        );
+
+        let mut json = json;
+        json["width"] = serde_json::json!(3);
+        assert!(serde_json::from_value::<Contraption>(json.clone()).is_err());
+        json["height"] = serde_json::json!(0);
+        json["tiles"] = serde_json::json!([]);
+        assert!(serde_json::from_value::<Contraption>(json).is_err());
+    }
+
+    #[test]
+    fn energized_example() {
+        let contraption = TEST_INPUT.parse::<Contraption>().unwrap();
+        assert_eq!(energized_tiles(&contraption, (0, 0), Direction::Right), 46);
+    }
+
+    #[test]
+    fn max_energized_example() {
+        let contraption = TEST_INPUT.parse::<Contraption>().unwrap();
+        assert_eq!(entry_points(&contraption).len(), 40);
+        assert_eq!(energized_tiles(&contraption, (0, 3), Direction::Down), 51);
+        assert_eq!(max_energized_tiles(&contraption, ProgressBar::hidden()), 51);
+        assert_eq!(BeamCache::new(&contraption).max_energized_tiles(), 51);
+        assert_eq!(
+            BeamCache::new(&contraption).best_entry_point(),
+            Some(((0, 3), Direction::Down))
+        );
+    }
+
+    #[test]
+    fn beam_loops_stop() {
+        // The splitter's beams go round the four mirrors and come back into it forever
+        let contraption = "/.\\\n...\n\\-/".parse::<Contraption>().unwrap();
+        assert_eq!(energized_tiles(&contraption, (0, 1), Direction::Down), 9);
+    }
+}
@@ -0,0 +1,76 @@
+use day_16::{energized_tiles, BeamCache, Contraption, Direction};
+use std::{env, fs, process};
+
+fn main() {
+    let contraption = fs::read_to_string("input.txt")
+        .expect("failed to open input file")
+        .parse::<Contraption>()
+        .expect("failed to parse contraption");
+
+    let energized = energized_tiles(&contraption, (0, 0), Direction::Right);
+    println!("Part 1 result: {energized}");
+
+    let cache = BeamCache::new(&contraption);
+    let max_energized = cache.max_energized_tiles();
+    println!("Part 2 result: {max_energized}");
+
+    let args: Vec<String> = env::args().collect();
+    if let Some(i) = args.iter().position(|arg| arg == "--render") {
+        let Some(dir) = args.get(i + 1) else {
+            eprintln!("--render needs an output directory");
+            process::exit(1);
+        };
+        let mut beams = vec![("part_1.png", (0, 0), Direction::Right)];
+        if let Some((start, direction)) = cache.best_entry_point() {
+            beams.push(("part_2.png", start, direction));
+        }
+        render_heat_maps(&contraption, &beams, dir);
+    }
+    if args.iter().any(|arg| arg == "--live") {
+        play_beam_live(&contraption);
+    }
+}
+
+#[cfg(feature = "render")]
+fn play_beam_live(contraption: &Contraption) {
+    if let Err(err) = day_16::render::play_beam_live(contraption, (0, 0), Direction::Right) {
+        eprintln!("failed to play the beam: {err}");
+        process::exit(1);
+    }
+}
+
+#[cfg(not(feature = "render"))]
+fn play_beam_live(_: &Contraption) {
+    eprintln!("--live needs day_16 to be built with the `render` feature");
+    process::exit(1);
+}
+
+#[cfg(feature = "render")]
+fn render_heat_maps(
+    contraption: &Contraption,
+    beams: &[(&str, (usize, usize), Direction)],
+    dir: &str,
+) {
+    let dir = std::path::Path::new(dir);
+    if let Err(err) = fs::create_dir_all(dir) {
+        eprintln!("failed to create {}: {err}", dir.display());
+        process::exit(1);
+    }
+    for &(name, start, direction) in beams {
+        let path = dir.join(name);
+        if let Err(err) = day_16::render::render_heat_map(contraption, start, direction, &path) {
+            eprintln!("failed to render the heat map: {err}");
+            process::exit(1);
+        }
+        println!(
+            "Wrote the beam entering at {start:?} heading {direction:?} to {}",
+            path.display()
+        );
+    }
+}
+
+#[cfg(not(feature = "render"))]
+fn render_heat_maps(_: &Contraption, _: &[(&str, (usize, usize), Direction)], _: &str) {
+    eprintln!("--render needs day_16 to be built with the `render` feature");
+    process::exit(1);
+}
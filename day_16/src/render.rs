@@ -0,0 +1,194 @@
+use crate::{beam_directions, Contraption, Direction, Tile};
+use aoc_viz::{Color, Frame, Palette, Png, RenderError, Renderer};
+use std::path::Path;
+
+/// How many cells across each tile is drawn, enough to draw the mirrors and splitters in it
+const TILE_CELLS: usize = 3;
+/// How many pixels across each cell is drawn
+const CELL_PIXELS: u32 = 3;
+
+/// Palette indices 0 to 4 shade tiles by how many directions beams passed through them in, and
+/// the last two draw mirrors and splitters on top depending on whether a beam reached them
+const OPTIC: u8 = 5;
+const LIT_OPTIC: u8 = 6;
+
+fn palette() -> Palette {
+    let mut palette = Palette::gradient(Color::from(0x101018), Color::from(0xFFD23F), 5);
+    palette.push(Color::from(0x3A6EA5));
+    palette.push(Color::from(0x9FE7FF));
+    palette
+}
+
+/// Colors for [play_beam], which draws each tile as a single cell. The beams' leading edge is
+/// last so it still shows if the player has to shrink the contraption to fit.
+const DARK: u8 = 0;
+const DARK_OPTIC: u8 = 1;
+const ENERGIZED: u8 = 2;
+const ENERGIZED_OPTIC: u8 = 3;
+const BEAM: u8 = 4;
+
+pub fn live_palette() -> Palette {
+    Palette::new([
+        Color::from(0x101018),
+        Color::from(0x3A6EA5),
+        Color::from(0x8A6D1C),
+        Color::from(0x9FE7FF),
+        Color::from(0xFFD23F),
+    ])
+}
+
+/// The cells inside a tile that draw `tile`, counting from the tile's top left
+fn optic_cells(tile: Tile) -> &'static [(usize, usize)] {
+    match tile {
+        Tile::Empty => &[],
+        Tile::ForwardMirror => &[(0, 2), (1, 1), (2, 0)],
+        Tile::BackMirror => &[(0, 0), (1, 1), (2, 2)],
+        Tile::VerticalSplitter => &[(0, 1), (1, 1), (2, 1)],
+        Tile::HorizontalSplitter => &[(1, 0), (1, 1), (1, 2)],
+    }
+}
+
+/// Draws `contraption` with every tile shaded by how many directions beams pass through it in,
+/// starting from a beam entering at `start` heading `direction`, and saves it to `path`
+pub fn render_heat_map(
+    contraption: &Contraption,
+    start: (usize, usize),
+    direction: Direction,
+    path: &Path,
+) -> Result<(), RenderError> {
+    let directions = beam_directions(contraption, start, direction);
+    Png::single(path, palette())
+        .scale(CELL_PIXELS)
+        .render(&heat_map_frame(contraption, &directions))
+}
+
+/// Draws each tile as a square of cells shaded by how many of `directions` it has, with any
+/// mirror or splitter in it drawn on top
+fn heat_map_frame(contraption: &Contraption, directions: &[u8]) -> Frame {
+    let mut frame = Frame::from_fn(
+        contraption.width() * TILE_CELLS,
+        contraption.height() * TILE_CELLS,
+        |(row, col)| {
+            let pos = (row / TILE_CELLS, col / TILE_CELLS);
+            directions[contraption.index(pos)].count_ones() as u8
+        },
+    );
+
+    for row in 0..contraption.height() {
+        for col in 0..contraption.width() {
+            let Some(&tile) = contraption.get((row, col)) else {
+                continue;
+            };
+            let lit = directions[contraption.index((row, col))] != 0;
+            for &(cell_row, cell_col) in optic_cells(tile) {
+                let cell = (row * TILE_CELLS + cell_row, col * TILE_CELLS + cell_col);
+                frame.set(cell, if lit { LIT_OPTIC } else { OPTIC });
+            }
+        }
+    }
+
+    frame
+}
+
+/// Plays the beam entering at `start` heading `direction` spreading through `contraption`, one
+/// frame for each step every beam takes, with the tiles they've energized so far lit up behind
+/// them. Stops early if `renderer` does.
+pub fn play_beam(
+    contraption: &Contraption,
+    start: (usize, usize),
+    direction: Direction,
+    renderer: &mut impl Renderer,
+) -> Result<(), RenderError> {
+    let mut seen = vec![0u8; contraption.width() * contraption.height()];
+    let mut front = vec![(start, direction)];
+    while !renderer.is_stopped() {
+        // Same as beam_directions, only going a step at a time for every beam at once
+        let mut beams = vec![];
+        for (pos, direction) in front {
+            let Some(&tile) = contraption.get(pos) else {
+                continue;
+            };
+            let seen_here = &mut seen[contraption.index(pos)];
+            if *seen_here & direction.bit() == 0 {
+                *seen_here |= direction.bit();
+                beams.push((pos, tile, direction));
+            }
+        }
+        if beams.is_empty() {
+            break;
+        }
+
+        let mut frame = Frame::from_fn(contraption.width(), contraption.height(), |pos| {
+            let optic = contraption.get(pos) != Some(&Tile::Empty);
+            match (seen[contraption.index(pos)] != 0, optic) {
+                (false, false) => DARK,
+                (false, true) => DARK_OPTIC,
+                (true, false) => ENERGIZED,
+                (true, true) => ENERGIZED_OPTIC,
+            }
+        });
+        front = vec![];
+        for (pos, tile, direction) in beams {
+            frame.set(pos, BEAM);
+            let (direction, split) = tile.redirect(direction);
+            for direction in [Some(direction), split].into_iter().flatten() {
+                if let Some(next) = contraption.step(pos, direction) {
+                    front.push((next, direction));
+                }
+            }
+        }
+        renderer.render(&frame)?;
+    }
+    Ok(())
+}
+
+/// Plays [play_beam] in the terminal, with keys to pause and step through it
+pub fn play_beam_live(
+    contraption: &Contraption,
+    start: (usize, usize),
+    direction: Direction,
+) -> Result<(), RenderError> {
+    let mut player = aoc_viz::Player::stdout(live_palette()).fps(20.0);
+    play_beam(contraption, start, direction, &mut player)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aoc_viz::Recording;
+
+    #[test]
+    fn shade_and_overlay() {
+        // The beam goes right along the top, down the back mirror and out the bottom. The
+        // splitter is hit end on and lets it through.
+        let contraption = ".\\\n.|".parse::<Contraption>().unwrap();
+        let directions = beam_directions(&contraption, (0, 0), Direction::Right);
+        let frame = heat_map_frame(&contraption, &directions);
+        assert_eq!((frame.width(), frame.height()), (6, 6));
+
+        assert_eq!(frame.get((1, 1)), Some(1));
+        assert_eq!(frame.get((0, 3)), Some(LIT_OPTIC));
+        assert_eq!(frame.get((0, 5)), Some(1));
+        assert_eq!(frame.get((4, 4)), Some(LIT_OPTIC));
+        assert_eq!(frame.get((4, 3)), Some(1));
+        assert_eq!(frame.get((4, 1)), Some(0));
+    }
+
+    #[test]
+    fn beam_steps() {
+        let contraption = ".\\\n.|".parse::<Contraption>().unwrap();
+        let mut recording = Recording::new();
+        play_beam(&contraption, (0, 0), Direction::Right, &mut recording).unwrap();
+        let frames = recording.frames();
+        let rows = |frame: &Frame| frame.rows().map(|row| row.to_vec()).collect::<Vec<_>>();
+
+        // Along the top, down through the mirror and the splitter, then off the bottom
+        assert_eq!(frames.len(), 3);
+        assert_eq!(rows(&frames[0]), [[BEAM, DARK_OPTIC], [DARK, DARK_OPTIC]]);
+        assert_eq!(rows(&frames[1]), [[ENERGIZED, BEAM], [DARK, DARK_OPTIC]]);
+        assert_eq!(
+            rows(&frames[2]),
+            [[ENERGIZED, ENERGIZED_OPTIC], [DARK, BEAM]]
+        );
+    }
+}
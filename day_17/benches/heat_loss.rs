@@ -0,0 +1,44 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_17::{HeatMap, CRUCIBLE, ULTRA_CRUCIBLE};
+use std::fs;
+
+/// Builds a `side` by `side` heat map where every block loses 1 to 9 heat, all equally likely
+fn generate_heat_map(side: usize) -> HeatMap {
+    let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+    let rows: Vec<String> = (0..side)
+        .map(|_| (0..side).map(|_| char::from(b'1' + rng.u8(..9))).collect())
+        .collect();
+    rows.join("\n")
+        .parse()
+        .expect("generated heat map should parse")
+}
+
+fn load_heat_map() -> HeatMap {
+    match fs::read_to_string("input.txt") {
+        Ok(input) => input.parse().expect("failed to parse heat map"),
+        Err(_) => {
+            println!("input.txt not found, benchmarking a generated heat map instead");
+            generate_heat_map(141)
+        }
+    }
+}
+
+fn heat_loss_benchmark(c: &mut Criterion) {
+    let heat_map = load_heat_map();
+
+    let mut group = c.benchmark_group("min_heat_loss");
+    group.sample_size(10);
+    for (name, rules) in [("crucible", CRUCIBLE), ("ultra_crucible", ULTRA_CRUCIBLE)] {
+        group.bench_function(format!("dijkstra_{name}"), |b| {
+            b.iter(|| heat_map.min_heat_loss(rules))
+        });
+        group.bench_function(format!("astar_{name}"), |b| {
+            b.iter(|| heat_map.min_heat_loss_astar(rules))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, heat_loss_benchmark);
+criterion_main!(benches);
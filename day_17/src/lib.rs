@@ -0,0 +1,417 @@
+#[cfg(feature = "render")]
+pub mod render;
+mod search;
+
+use std::str::FromStr;
+
+/// How far a crucible has to go in a straight line before it may turn or stop, and how far it can
+/// go before it has to turn
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CrucibleRules {
+    min_run: u8,
+    max_run: u8,
+}
+
+/// Checks the crucible can actually make its minimum run, and that counting one block past its
+/// maximum run still fits in a `u8`
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CrucibleRules {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(serde::Deserialize)]
+        struct CrucibleRulesData {
+            min_run: u8,
+            max_run: u8,
+        }
+
+        let CrucibleRulesData { min_run, max_run } = CrucibleRulesData::deserialize(deserializer)?;
+        if min_run > max_run {
+            return Err(D::Error::custom(format!(
+                "a minimum run of {min_run} is longer than the maximum run of {max_run}"
+            )));
+        }
+        if max_run == u8::MAX {
+            return Err(D::Error::custom(format!(
+                "the maximum run has to be below {}",
+                u8::MAX
+            )));
+        }
+        Ok(Self { min_run, max_run })
+    }
+}
+
+pub const CRUCIBLE: CrucibleRules = CrucibleRules {
+    min_run: 1,
+    max_run: 3,
+};
+
+pub const ULTRA_CRUCIBLE: CrucibleRules = CrucibleRules {
+    min_run: 4,
+    max_run: 10,
+};
+
+/// How much heat is lost entering each city block, stored row by row in one flat list
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HeatMap {
+    width: usize,
+    height: usize,
+    heat_loss: Vec<u8>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseHeatMapError;
+
+impl FromStr for HeatMap {
+    type Err = ParseHeatMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let width = s.lines().next().map_or(0, str::len);
+        let mut heat_loss = vec![];
+        let mut height = 0;
+        for line in s.lines() {
+            if line.len() != width {
+                return Err(ParseHeatMapError);
+            }
+            for c in line.chars() {
+                let heat = c.to_digit(10).ok_or(ParseHeatMapError)?;
+                heat_loss.push(heat as u8);
+            }
+            height += 1;
+        }
+        if heat_loss.is_empty() {
+            return Err(ParseHeatMapError);
+        }
+
+        Ok(Self {
+            width,
+            height,
+            heat_loss,
+        })
+    }
+}
+
+/// Checks the map has blocks, that they fill out its rows and that each loses a single digit of
+/// heat, like parsing does
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for HeatMap {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(serde::Deserialize)]
+        struct HeatMapData {
+            width: usize,
+            height: usize,
+            heat_loss: Vec<u8>,
+        }
+
+        let HeatMapData {
+            width,
+            height,
+            heat_loss,
+        } = HeatMapData::deserialize(deserializer)?;
+        if heat_loss.is_empty() || width.checked_mul(height) != Some(heat_loss.len()) {
+            return Err(D::Error::custom(format!(
+                "{} blocks don't make a {width}x{height} map",
+                heat_loss.len()
+            )));
+        }
+        if let Some(heat) = heat_loss.iter().find(|&&heat| heat > 9) {
+            return Err(D::Error::custom(format!(
+                "a block loses {heat} heat, more than a single digit"
+            )));
+        }
+        Ok(Self {
+            width,
+            height,
+            heat_loss,
+        })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// The two directions a crucible can turn to, since it can't reverse
+    fn turns(self) -> [Direction; 2] {
+        match self {
+            Direction::Up | Direction::Down => [Direction::Left, Direction::Right],
+            Direction::Left | Direction::Right => [Direction::Up, Direction::Down],
+        }
+    }
+}
+
+/// Where a crucible is, which way it's heading and how many blocks it has gone that way in a row
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone)]
+struct CrucibleState {
+    pos: (usize, usize),
+    direction: Direction,
+    run: u8,
+}
+
+impl HeatMap {
+    /// The block one step from `pos` towards `direction`, or `None` past the edge of the city
+    fn step(&self, (row, col): (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        let next = match direction {
+            Direction::Up => (row.checked_sub(1)?, col),
+            Direction::Down => (row + 1, col),
+            Direction::Left => (row, col.checked_sub(1)?),
+            Direction::Right => (row, col + 1),
+        };
+        (next.0 < self.height && next.1 < self.width).then_some(next)
+    }
+
+    fn heat_loss_at(&self, (row, col): (usize, usize)) -> u32 {
+        self.heat_loss[row * self.width + col] as u32
+    }
+
+    /// Every state a crucible following `rules` can move to from `state`, with the heat lost
+    /// entering the new block
+    fn crucible_moves(
+        &self,
+        rules: CrucibleRules,
+        state: &CrucibleState,
+    ) -> Vec<(CrucibleState, u32)> {
+        let mut moves = vec![];
+        for direction in [state.direction].into_iter().chain(state.direction.turns()) {
+            let run = if direction == state.direction {
+                state.run + 1
+            } else if state.run >= rules.min_run {
+                1
+            } else {
+                continue;
+            };
+            if run > rules.max_run {
+                continue;
+            }
+            if let Some(pos) = self.step(state.pos, direction) {
+                let next = CrucibleState {
+                    pos,
+                    direction,
+                    run,
+                };
+                moves.push((next, self.heat_loss_at(pos)));
+            }
+        }
+        moves
+    }
+
+    /// The least heat a crucible following `rules` can lose going from the top left block to the
+    /// bottom right one, where it has to have gone far enough in a straight line to stop. The
+    /// search runs over crucible states rather than blocks, since how a crucible got to a block
+    /// changes where it can go next.
+    pub fn min_heat_loss(&self, rules: CrucibleRules) -> Option<u32> {
+        let goal = (self.height - 1, self.width - 1);
+        search::dijkstra(
+            Self::crucible_starts(),
+            |state| self.crucible_moves(rules, state),
+            |state| state.pos == goal && state.run >= rules.min_run,
+        )
+    }
+
+    /// The crucible sits in the top left block to start with, and can head off either way
+    fn crucible_starts() -> [CrucibleState; 2] {
+        [Direction::Right, Direction::Down].map(|direction| CrucibleState {
+            pos: (0, 0),
+            direction,
+            run: 0,
+        })
+    }
+
+    /// Same as [HeatMap::min_heat_loss], searching with A* instead. Every block between a crucible
+    /// and the factory loses at least as much heat as the coolest block on the map, so that
+    /// times the Manhattan distance left never overestimates.
+    pub fn min_heat_loss_astar(&self, rules: CrucibleRules) -> Option<u32> {
+        let goal = (self.height - 1, self.width - 1);
+        let coolest = self.coolest();
+        search::astar(
+            self.crucible_state_count(rules),
+            |state| self.crucible_state_index(rules, state),
+            Self::crucible_starts(),
+            |state| self.crucible_moves(rules, state),
+            |state| Self::heat_loss_estimate(goal, coolest, state),
+            |state| state.pos == goal && state.run >= rules.min_run,
+        )
+    }
+
+    /// Same as [HeatMap::min_heat_loss_astar], along with every block the crucible passes through
+    /// on the way, starting with the top left one
+    pub fn min_heat_loss_path(&self, rules: CrucibleRules) -> Option<(u32, Vec<(usize, usize)>)> {
+        let goal = (self.height - 1, self.width - 1);
+        let coolest = self.coolest();
+        let (heat_loss, states) = search::astar_path(
+            self.crucible_state_count(rules),
+            |state| self.crucible_state_index(rules, state),
+            Self::crucible_starts(),
+            |state| self.crucible_moves(rules, state),
+            |state| Self::heat_loss_estimate(goal, coolest, state),
+            |state| state.pos == goal && state.run >= rules.min_run,
+        )?;
+        Some((heat_loss, states.iter().map(|state| state.pos).collect()))
+    }
+
+    fn coolest(&self) -> u32 {
+        self.heat_loss.iter().min().copied().unwrap_or(0) as u32
+    }
+
+    fn crucible_state_count(&self, rules: CrucibleRules) -> usize {
+        self.heat_loss.len() * 4 * (rules.max_run as usize + 1)
+    }
+
+    /// A different number below [HeatMap::crucible_state_count] for every crucible state
+    fn crucible_state_index(&self, rules: CrucibleRules, state: &CrucibleState) -> usize {
+        let (row, col) = state.pos;
+        let runs = rules.max_run as usize + 1;
+        ((row * self.width + col) * 4 + state.direction as usize) * runs + state.run as usize
+    }
+
+    fn heat_loss_estimate(goal: (usize, usize), coolest: u32, state: &CrucibleState) -> u32 {
+        let (row, col) = state.pos;
+        ((goal.0 - row) + (goal.1 - col)) as u32 * coolest
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "2413432311323\n\
+                              3215453535623\n\
+                              3255245654254\n\
+                              3446585845452\n\
+                              4546657867536\n\
+                              1438598798454\n\
+                              4457876987766\n\
+                              3637877979653\n\
+                              4654967986887\n\
+                              4564679986453\n\
+                              1224686865563\n\
+                              2546548887735\n\
+                              4322674655533\n";
+
+    #[test]
+    fn parse_heat_map() {
+        let heat_map = "12\n34".parse::<HeatMap>().unwrap();
+        assert_eq!(heat_map.heat_loss, vec![1, 2, 3, 4]);
+        assert_eq!("12\n3".parse::<HeatMap>(), Err(ParseHeatMapError));
+        assert_eq!("1x".parse::<HeatMap>(), Err(ParseHeatMapError));
+        assert_eq!("".parse::<HeatMap>(), Err(ParseHeatMapError));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let heat_map = "12\n34".parse::<HeatMap>().unwrap();
+        let json = serde_json::to_value(&heat_map).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"width": 2, "height": 2, "heat_loss": [1, 2, 3, 4]})
+        );
+        assert_eq!(
+            serde_json::from_value::<HeatMap>(json.clone()).unwrap(),
+            heat_map
+        );
+
+        let mut json = json;
+        json["heat_loss"][3] = serde_json::json!(10);
+        assert!(serde_json::from_value::<HeatMap>(json.clone()).is_err());
+        json["width"] = serde_json::json!(0);
+        json["heat_loss"] = serde_json::json!([]);
+        assert!(serde_json::from_value::<HeatMap>(json).is_err());
+
+        let json = serde_json::to_string(&ULTRA_CRUCIBLE).unwrap();
+        assert_eq!(json, r#"{"min_run":4,"max_run":10}"#);
+        assert_eq!(
+            serde_json::from_str::<CrucibleRules>(&json).unwrap(),
+            ULTRA_CRUCIBLE
+        );
+        assert!(serde_json::from_str::<CrucibleRules>(r#"{"min_run":4,"max_run":3}"#).is_err());
+        assert!(serde_json::from_str::<CrucibleRules>(r#"{"min_run":1,"max_run":255}"#).is_err());
+    }
+
+    #[test]
+    fn min_heat_loss() {
+        let heat_map = TEST_INPUT.parse::<HeatMap>().unwrap();
+        assert_eq!(heat_map.min_heat_loss(CRUCIBLE), Some(102));
+        assert_eq!(heat_map.min_heat_loss_astar(CRUCIBLE), Some(102));
+
+        let (heat_loss, path) = heat_map.min_heat_loss_path(CRUCIBLE).unwrap();
+        assert_eq!(heat_loss, 102);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(12, 12)));
+        let path_loss: u32 = path[1..]
+            .iter()
+            .map(|&pos| heat_map.heat_loss_at(pos))
+            .sum();
+        assert_eq!(path_loss, 102);
+    }
+
+    #[test]
+    fn ultra_crucible_min_heat_loss() {
+        let heat_map = TEST_INPUT.parse::<HeatMap>().unwrap();
+        assert_eq!(heat_map.min_heat_loss(ULTRA_CRUCIBLE), Some(94));
+        assert_eq!(heat_map.min_heat_loss_astar(ULTRA_CRUCIBLE), Some(94));
+
+        // The cheap way round has to go the whole row before turning, and can't stop early
+        let heat_map = "111111111111\n\
+                        999999999991\n\
+                        999999999991\n\
+                        999999999991\n\
+                        999999999991"
+            .parse::<HeatMap>()
+            .unwrap();
+        assert_eq!(heat_map.min_heat_loss(ULTRA_CRUCIBLE), Some(71));
+        assert_eq!(heat_map.min_heat_loss_astar(ULTRA_CRUCIBLE), Some(71));
+    }
+
+    #[test]
+    fn ultra_crucible_stops_after_min_run() {
+        // Only three blocks fit in a line, too short a run to turn or stop after
+        let heat_map = "1111\n1111\n1111\n1111".parse::<HeatMap>().unwrap();
+        assert_eq!(heat_map.min_heat_loss(ULTRA_CRUCIBLE), None);
+        assert_eq!(heat_map.min_heat_loss(CRUCIBLE), Some(6));
+        assert_eq!(heat_map.min_heat_loss_astar(ULTRA_CRUCIBLE), None);
+        assert_eq!(heat_map.min_heat_loss_astar(CRUCIBLE), Some(6));
+    }
+
+    #[test]
+    fn straight_runs_are_limited() {
+        // Four blocks along the top and then one down would be cheapest, but after three the
+        // crucible has to turn down a block early and cross the expensive row
+        let heat_map = "11111\n99999".parse::<HeatMap>().unwrap();
+        assert_eq!(heat_map.min_heat_loss(CRUCIBLE), Some(1 + 1 + 1 + 9 + 9));
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+        for _ in 0..50 {
+            let (width, height) = (1 + rng.u64(..15), 1 + rng.u64(..15));
+            let rows: Vec<String> = (0..height)
+                .map(|_| {
+                    (0..width)
+                        .map(|_| char::from(b'0' + rng.u8(..10)))
+                        .collect()
+                })
+                .collect();
+            let heat_map = rows.join("\n").parse::<HeatMap>().unwrap();
+            for rules in [CRUCIBLE, ULTRA_CRUCIBLE] {
+                assert_eq!(
+                    heat_map.min_heat_loss_astar(rules),
+                    heat_map.min_heat_loss(rules),
+                    "{rules:?} on\n{}",
+                    rows.join("\n")
+                );
+            }
+        }
+    }
+}
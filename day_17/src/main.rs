@@ -0,0 +1,59 @@
+use day_17::{CrucibleRules, HeatMap, CRUCIBLE, ULTRA_CRUCIBLE};
+use std::{env, fs, process};
+
+fn main() {
+    let heat_map = fs::read_to_string("input.txt")
+        .expect("failed to open input file")
+        .parse::<HeatMap>()
+        .expect("failed to parse heat map");
+
+    match heat_map.min_heat_loss_astar(CRUCIBLE) {
+        Some(heat_loss) => println!("Part 1 result: {heat_loss}"),
+        None => println!("Part 1 result not found: the crucible can't reach the factory"),
+    }
+
+    match heat_map.min_heat_loss_astar(ULTRA_CRUCIBLE) {
+        Some(heat_loss) => println!("Part 2 result: {heat_loss}"),
+        None => println!("Part 2 result not found: the ultra crucible can't reach the factory"),
+    }
+
+    let args: Vec<String> = env::args().collect();
+    if let Some(i) = args.iter().position(|arg| arg == "--render") {
+        let Some(dir) = args.get(i + 1) else {
+            eprintln!("--render needs an output directory");
+            process::exit(1);
+        };
+        render_paths(
+            &heat_map,
+            &[("part_1.png", CRUCIBLE), ("part_2.png", ULTRA_CRUCIBLE)],
+            dir,
+        );
+    }
+}
+
+#[cfg(feature = "render")]
+fn render_paths(heat_map: &HeatMap, crucibles: &[(&str, CrucibleRules)], dir: &str) {
+    let dir = std::path::Path::new(dir);
+    if let Err(err) = fs::create_dir_all(dir) {
+        eprintln!("failed to create {}: {err}", dir.display());
+        process::exit(1);
+    }
+    for &(name, rules) in crucibles {
+        let Some((_, path)) = heat_map.min_heat_loss_path(rules) else {
+            println!("No path to render for {rules:?}");
+            continue;
+        };
+        let out = dir.join(name);
+        if let Err(err) = day_17::render::render_path(heat_map, &path, &out) {
+            eprintln!("failed to render the path: {err}");
+            process::exit(1);
+        }
+        println!("Wrote the path for {rules:?} to {}", out.display());
+    }
+}
+
+#[cfg(not(feature = "render"))]
+fn render_paths(_: &HeatMap, _: &[(&str, CrucibleRules)], _: &str) {
+    eprintln!("--render needs day_17 to be built with the `render` feature");
+    process::exit(1);
+}
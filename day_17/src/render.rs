@@ -0,0 +1,66 @@
+use crate::HeatMap;
+use aoc_viz::{Color, Frame, Palette, Png, RenderError, Renderer};
+use std::path::Path;
+
+/// How many pixels across each block is drawn
+const BLOCK_PIXELS: u32 = 4;
+
+/// Palette indices 0 to 9 shade blocks by their heat loss, darkest for the least. The path is
+/// drawn on top, with the blocks where the crucible turns picked out so its runs are easy to see.
+const PATH: u8 = 10;
+const TURN: u8 = 11;
+
+pub fn palette() -> Palette {
+    let mut palette = Palette::gradient(Color::BLACK, Color::from(0xC8C8C8), 10);
+    palette.push(Color::from(0xFF8C1A));
+    palette.push(Color::from(0xE0243B));
+    palette
+}
+
+/// Draws `heat_map` in grays with `path` over it in color, and saves it to `out`
+pub fn render_path(
+    heat_map: &HeatMap,
+    path: &[(usize, usize)],
+    out: &Path,
+) -> Result<(), RenderError> {
+    Png::single(out, palette())
+        .scale(BLOCK_PIXELS)
+        .render(&path_frame(heat_map, path))
+}
+
+/// Draws `heat_map` in grays with `path` over it, picking out the blocks where it turns
+pub fn path_frame(heat_map: &HeatMap, path: &[(usize, usize)]) -> Frame {
+    let mut frame = Frame::from_fn(heat_map.width, heat_map.height, |pos| {
+        heat_map.heat_loss_at(pos) as u8
+    });
+    for &pos in path {
+        frame.set(pos, PATH);
+    }
+    // A block is a turn when the step into it and the step out of it go different ways
+    for steps in path.windows(3) {
+        let [before, here, after] = [steps[0], steps[1], steps[2]];
+        let step = |(from_row, from_col): (usize, usize), (to_row, to_col): (usize, usize)| {
+            (
+                to_row as isize - from_row as isize,
+                to_col as isize - from_col as isize,
+            )
+        };
+        if step(before, here) != step(here, after) {
+            frame.set(here, TURN);
+        }
+    }
+    frame
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn path_over_heat_loss() {
+        let heat_map = "123\n456".parse::<HeatMap>().unwrap();
+        let frame = path_frame(&heat_map, &[(0, 0), (0, 1), (1, 1), (1, 2)]);
+        let rows = frame.rows().collect::<Vec<_>>();
+        assert_eq!(rows, vec![&[PATH, TURN, 3], &[4, TURN, PATH]]);
+    }
+}
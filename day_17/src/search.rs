@@ -0,0 +1,178 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// Finds the lowest total cost of getting from any of `starts` to a state `is_goal` accepts, with
+/// `successors` giving the states one move away from a state along with what that move costs.
+/// Gives `None` if no goal can be reached.
+pub fn dijkstra<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<u32>
+where
+    S: Hash + Eq + Ord + Clone,
+    I: IntoIterator<Item = (S, u32)>,
+{
+    let mut best = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        best.insert(start.clone(), 0);
+        queue.push(Reverse((0, start)));
+    }
+
+    while let Some(Reverse((cost, state))) = queue.pop() {
+        if is_goal(&state) {
+            return Some(cost);
+        }
+        if best.get(&state).is_some_and(|&known| known < cost) {
+            // Already reached more cheaply since this was queued
+            continue;
+        }
+
+        for (next, step_cost) in successors(&state) {
+            let next_cost = cost + step_cost;
+            if best.get(&next).is_none_or(|&known| next_cost < known) {
+                best.insert(next.clone(), next_cost);
+                queue.push(Reverse((next_cost, next)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Same as [dijkstra], but puts the states with the lowest `cost + heuristic(state)` first so it
+/// heads towards a goal rather than outwards in every direction. `heuristic` can't ever be more
+/// than what it really costs to get from a state to a goal, and can't drop by more than the cost
+/// of a move, or a worse path could be found first.
+///
+/// Costs are small whole numbers, so rather than a heap the queue is a list of buckets, one per
+/// estimated total cost. The estimates of the states taken out never go down, so the lowest bucket
+/// is always at or after the last one emptied. States are looked up by `index`, which has to give
+/// each one a different number below `states`, so the best costs are kept in a flat list.
+pub fn astar<S, I>(
+    states: usize,
+    index: impl Fn(&S) -> usize,
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> u32,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<u32>
+where
+    S: Clone,
+    I: IntoIterator<Item = (S, u32)>,
+{
+    astar_path(states, index, starts, successors, heuristic, is_goal).map(|(cost, _)| cost)
+}
+
+/// Same as [astar], but also gives the states along the cheapest path, from the start it left to
+/// the goal it reached
+pub fn astar_path<S, I>(
+    states: usize,
+    index: impl Fn(&S) -> usize,
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> u32,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<(u32, Vec<S>)>
+where
+    S: Clone,
+    I: IntoIterator<Item = (S, u32)>,
+{
+    let mut best = vec![u32::MAX; states];
+    // The state each one was last reached more cheaply from
+    let mut came_from: Vec<Option<S>> = vec![None; states];
+    let mut buckets: Vec<Vec<(S, u32)>> = vec![];
+    let push = |buckets: &mut Vec<Vec<_>>, state: S, cost: u32, estimate: u32| {
+        let bucket = (cost + estimate) as usize;
+        if bucket >= buckets.len() {
+            buckets.resize_with(bucket + 1, Vec::new);
+        }
+        buckets[bucket].push((state, cost));
+    };
+    for start in starts {
+        best[index(&start)] = 0;
+        let estimate = heuristic(&start);
+        push(&mut buckets, start, 0, estimate);
+    }
+
+    let mut current = 0;
+    while current < buckets.len() {
+        let Some((state, cost)) = buckets[current].pop() else {
+            current += 1;
+            continue;
+        };
+        if is_goal(&state) {
+            let mut path = vec![state];
+            while let Some(previous) = path
+                .last()
+                .and_then(|state| came_from[index(state)].clone())
+            {
+                path.push(previous);
+            }
+            path.reverse();
+            return Some((cost, path));
+        }
+        if best[index(&state)] < cost {
+            // Already reached more cheaply since this was queued
+            continue;
+        }
+
+        for (next, step_cost) in successors(&state) {
+            let next_cost = cost + step_cost;
+            let next_index = index(&next);
+            if next_cost < best[next_index] {
+                best[next_index] = next_cost;
+                came_from[next_index] = Some(state.clone());
+                let estimate = heuristic(&next);
+                push(&mut buckets, next, next_cost, estimate);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cheapest_path() {
+        // 0 -> 1 -> 3 costs 5, 0 -> 2 -> 3 costs 4, 0 -> 3 costs 10
+        let edges = |&node: &u32| match node {
+            0 => vec![(1, 1), (2, 3), (3, 10)],
+            1 => vec![(3, 4)],
+            2 => vec![(3, 1)],
+            _ => vec![],
+        };
+        assert_eq!(dijkstra([0], edges, |&node| node == 3), Some(4));
+        assert_eq!(dijkstra([3], edges, |&node| node == 0), None);
+        assert_eq!(dijkstra([1, 2], edges, |&node| node == 3), Some(1));
+    }
+
+    #[test]
+    fn cheapest_path_astar() {
+        // Same as above, with the fewest moves left to get to 3 as the heuristic
+        let edges = |&node: &u32| match node {
+            0 => vec![(1, 1), (2, 3), (3, 10)],
+            1 => vec![(3, 4)],
+            2 => vec![(3, 1)],
+            _ => vec![],
+        };
+        let index = |&node: &u32| node as usize;
+        let moves_left = |&node: &u32| u32::from(node != 3);
+        let astar = |start: u32, goal: u32| {
+            astar(4, index, [start], edges, moves_left, |&node| node == goal)
+        };
+        assert_eq!(astar(0, 3), Some(4));
+        assert_eq!(astar(3, 0), None);
+        assert_eq!(astar(1, 3), Some(4));
+
+        assert_eq!(
+            astar_path(4, index, [0], edges, moves_left, |&node| node == 3),
+            Some((4, vec![0, 2, 3]))
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use super::{LagoonVolume, LavaductLagoon};
+use std::collections::VecDeque;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum CompressedCell {
+    Trench,
+    /// Not reached from the border, so inside the lagoon once the fill is done
+    Unreached,
+    Outside,
+}
+
+/// The lagoon squeezed down to one cell for each stretch of rows and columns that no trench
+/// corner splits up. Column `i` covers the x positions from `xs[i]` up to but not including
+/// `xs[i + 1]`, and rows do the same with `ys`, so a whole run of identical cells is handled at
+/// once no matter how long it is.
+#[derive(Debug)]
+pub(crate) struct CompressedGrid {
+    xs: Vec<i64>,
+    ys: Vec<i64>,
+    cells: Vec<CompressedCell>,
+}
+
+impl LavaductLagoon {
+    /// Builds the [CompressedGrid] of the trench. Every corner starts a column and row of its own
+    /// and the next position starts another, so trenches land exactly on one-wide cells. An extra
+    /// ring of cells around the trench gives the fill somewhere outside to start from.
+    pub(crate) fn compressed_grid(&self) -> CompressedGrid {
+        let mut xs = vec![self.min_x - 1, self.max_x + 2];
+        let mut ys = vec![self.min_y - 1, self.max_y + 2];
+        for trench in &self.trench_segments {
+            xs.extend([trench.start.0, trench.start.0 + 1]);
+            ys.extend([trench.start.1, trench.start.1 + 1]);
+        }
+        xs.sort_unstable();
+        xs.dedup();
+        ys.sort_unstable();
+        ys.dedup();
+
+        let width = xs.len() - 1;
+        let mut cells = vec![CompressedCell::Unreached; width * (ys.len() - 1)];
+        for trench in &self.trench_segments {
+            let cols = xs.binary_search(&trench.min_x).unwrap()
+                ..xs.binary_search(&(trench.max_x + 1)).unwrap();
+            let rows = ys.binary_search(&trench.min_y).unwrap()
+                ..ys.binary_search(&(trench.max_y + 1)).unwrap();
+            for row in rows {
+                for col in cols.clone() {
+                    cells[row * width + col] = CompressedCell::Trench;
+                }
+            }
+        }
+
+        let mut grid = CompressedGrid { xs, ys, cells };
+        grid.fill_outside();
+        grid
+    }
+}
+
+impl CompressedGrid {
+    fn width(&self) -> usize {
+        self.xs.len() - 1
+    }
+
+    fn height(&self) -> usize {
+        self.ys.len() - 1
+    }
+
+    /// Marks every cell that can be reached from the top left corner without crossing the trench
+    fn fill_outside(&mut self) {
+        let (width, height) = (self.width(), self.height());
+        let mut fill_queue = VecDeque::from([(0, 0)]);
+        while let Some((col, row)) = fill_queue.pop_front() {
+            let cell = &mut self.cells[row * width + col];
+            if *cell != CompressedCell::Unreached {
+                continue;
+            }
+            *cell = CompressedCell::Outside;
+
+            if row > 0 {
+                fill_queue.push_back((col, row - 1));
+            }
+            if row + 1 < height {
+                fill_queue.push_back((col, row + 1));
+            }
+            if col > 0 {
+                fill_queue.push_back((col - 1, row));
+            }
+            if col + 1 < width {
+                fill_queue.push_back((col + 1, row));
+            }
+        }
+    }
+
+    /// Adds up how many positions the trench cells and the cells inside them stand for
+    pub(crate) fn volume(&self) -> LagoonVolume {
+        let width = self.width();
+        let mut trench_length = 0;
+        let mut interior = 0;
+        for (i, cell) in self.cells.iter().enumerate() {
+            let (col, row) = (i % width, i / width);
+            let cell_width = self.xs[col + 1] - self.xs[col];
+            let cell_height = self.ys[row + 1] - self.ys[row];
+            let size = (cell_width * cell_height) as u64;
+            match cell {
+                CompressedCell::Trench => trench_length += size,
+                CompressedCell::Unreached => interior += size,
+                CompressedCell::Outside => {}
+            }
+        }
+        LagoonVolume::new(trench_length, interior)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::TEST_INPUT;
+    use crate::DigInstruction;
+
+    #[test]
+    fn compressed_volume() {
+        let mut lavaduct_lagoon = LavaductLagoon::default();
+        let instructions = TEST_INPUT
+            .lines()
+            .map(|line| line.parse::<DigInstruction>().unwrap())
+            .collect::<Vec<_>>();
+        lavaduct_lagoon.dig_trenches(&instructions).unwrap();
+        let grid = lavaduct_lagoon.compressed_grid();
+        assert_eq!(grid.volume(), lavaduct_lagoon.lagoon_volume());
+
+        let mut hex_lagoon = LavaductLagoon::default();
+        let instructions = TEST_INPUT
+            .lines()
+            .map(|line| DigInstruction::from_hex_line(line).unwrap())
+            .collect::<Vec<_>>();
+        hex_lagoon.dig_trenches(&instructions).unwrap();
+        let grid = hex_lagoon.compressed_grid();
+        // Two columns and rows for every corner at most, plus the border
+        assert!(grid.width() <= 2 * instructions.len() + 2);
+        assert!(grid.height() <= 2 * instructions.len() + 2);
+        assert_eq!(grid.volume(), hex_lagoon.lagoon_volume());
+        assert_eq!(grid.volume().total, 952408144115);
+    }
+
+    #[test]
+    fn compressed_cells() {
+        // A U shape: the notch in the middle is outside, the rest is trench or lagoon
+        let mut u_lagoon = LavaductLagoon::default();
+        let instructions = ["R 2", "D 3", "R 2", "U 3", "R 2", "D 5", "L 6", "U 5"].map(|step| {
+            format!("{step} (#000000)")
+                .parse::<DigInstruction>()
+                .unwrap()
+        });
+        u_lagoon.dig_trenches(&instructions).unwrap();
+        let grid = u_lagoon.compressed_grid();
+
+        assert_eq!(grid.xs, vec![-1, 0, 1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(grid.ys, vec![-1, 0, 1, 3, 4, 5, 6, 7]);
+        let row = grid.ys.binary_search(&1).unwrap();
+        assert_eq!(grid.cells[row * grid.width() + 4], CompressedCell::Outside);
+        assert_eq!(
+            grid.cells[row * grid.width() + 2],
+            CompressedCell::Unreached
+        );
+        assert_eq!(grid.volume(), LagoonVolume::new(28, 11));
+    }
+}
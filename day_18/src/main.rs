@@ -1,13 +1,13 @@
-use image::{ImageBuffer, Rgb, RgbImage};
-use std::cell::RefCell;
-use std::cmp::Ordering;
-use std::collections::VecDeque;
-use std::fs;
-use std::ops::RangeInclusive;
-use std::rc::Rc;
+mod compressed_grid;
+#[cfg(feature = "render")]
+mod render;
+
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
+use std::{env, fs, process};
 
 #[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum Direction {
     Up,
     Down,
@@ -33,6 +33,8 @@ impl TryFrom<char> for Direction {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(not(feature = "render"), allow(dead_code))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Color {
     red: u8,
     green: u8,
@@ -56,12 +58,6 @@ impl From<u32> for Color {
     }
 }
 
-impl From<Color> for Rgb<u8> {
-    fn from(value: Color) -> Self {
-        Rgb([value.red, value.green, value.blue])
-    }
-}
-
 impl FromStr for Color {
     type Err = ParseColorError;
 
@@ -76,6 +72,7 @@ impl FromStr for Color {
 }
 
 #[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct DigInstruction {
     direction: Direction,
     length: u32,
@@ -114,40 +111,122 @@ impl FromStr for DigInstruction {
     }
 }
 
+impl DigInstruction {
+    /// Reads a line the way part 2 wants it: the first five hex digits of the color are the
+    /// length, and the last digit is the direction (0 is right, 1 down, 2 left and 3 up)
+    fn from_hex_line(s: &str) -> Result<Self, ParseDigInstructionError> {
+        let code = s
+            .split_whitespace()
+            .nth(2)
+            .and_then(|code| code.strip_prefix("(#"))
+            .and_then(|code| code.strip_suffix(')'))
+            .filter(|code| code.len() == 6)
+            .ok_or(ParseDigInstructionError)?;
+
+        let length = u32::from_str_radix(&code[..5], 16).or(Err(ParseDigInstructionError))?;
+        let direction = match &code[5..] {
+            "0" => Direction::Right,
+            "1" => Direction::Down,
+            "2" => Direction::Left,
+            "3" => Direction::Up,
+            _ => return Err(ParseDigInstructionError),
+        };
+        let color = u32::from_str_radix(code, 16)
+            .map(Color::from)
+            .or(Err(ParseDigInstructionError))?;
+
+        Ok(Self {
+            direction,
+            length,
+            color,
+        })
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct TrenchSegment {
-    start: (i32, i32),
-    end: (i32, i32),
-    max_x: i32,
-    min_x: i32,
-    max_y: i32,
-    min_y: i32,
+    start: (i64, i64),
+    end: (i64, i64),
+    max_x: i64,
+    min_x: i64,
+    max_y: i64,
+    min_y: i64,
     color: Color,
 }
 
-impl TrenchSegment {
-    fn shift(&mut self, x_shift: i32, y_shift: i32) {
-        self.start.0 += x_shift;
-        self.start.1 += y_shift;
-        self.end.0 += x_shift;
-        self.end.1 += y_shift;
+#[derive(Debug, Eq, PartialEq)]
+enum DigPlanError {
+    Empty,
+    /// The last instruction leaves the digger somewhere other than where it started
+    NotClosed {
+        index: usize,
+        end: (i64, i64),
+    },
+    /// The trenches dug by these two instructions run into each other
+    Crossing {
+        first: usize,
+        second: usize,
+    },
+}
 
-        self.min_x += x_shift;
-        self.max_x += x_shift;
-        self.min_y += y_shift;
-        self.max_y += y_shift;
+impl Display for DigPlanError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DigPlanError::Empty => write!(f, "the dig plan has no instructions"),
+            DigPlanError::NotClosed { index, end } => write!(
+                f,
+                "instruction {index} ends the trench at {end:?} instead of back at the start"
+            ),
+            DigPlanError::Crossing { first, second } => {
+                write!(f, "the trenches of instructions {first} and {second} cross")
+            }
+        }
     }
 }
 
+/// How much lava a lagoon holds, split into the trench dug around its edge and the cells inside
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct LagoonVolume {
+    trench_length: u64,
+    interior: u64,
+    total: u64,
+}
+
+impl LagoonVolume {
+    fn new(trench_length: u64, interior: u64) -> Self {
+        Self {
+            trench_length,
+            interior,
+            total: trench_length + interior,
+        }
+    }
+}
+
+impl Display for LagoonVolume {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} cubic meters: {} of trench and {} inside it",
+            self.total, self.trench_length, self.interior
+        )
+    }
+}
+
+/// The dug out trench, in coordinates relative to where digging started. Those go negative when
+/// the trench heads up or left of the start, so anything drawing it has to offset by `min_x` and
+/// `min_y` itself.
 #[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct LavaductLagoon {
-    width: u16,
-    height: u16,
-    min_x: i32,
-    max_x: i32,
-    min_y: i32,
-    max_y: i32,
-    dig_position: (i32, i32),
+    width: u64,
+    height: u64,
+    min_x: i64,
+    max_x: i64,
+    min_y: i64,
+    max_y: i64,
+    dig_position: (i64, i64),
     trench_segments: Vec<TrenchSegment>,
 }
 
@@ -156,26 +235,19 @@ impl Default for LavaductLagoon {
         Self {
             width: 0,
             height: 0,
-            min_x: i32::MAX,
-            max_x: i32::MIN,
-            min_y: i32::MAX,
-            max_y: i32::MIN,
+            min_x: i64::MAX,
+            max_x: i64::MIN,
+            min_y: i64::MAX,
+            max_y: i64::MIN,
             dig_position: (0, 0),
             trench_segments: vec![],
         }
     }
 }
 
-fn absolute_range(a: u32, b: u32) -> RangeInclusive<u32> {
-    match a.cmp(&b) {
-        Ordering::Less | Ordering::Equal => a..=b,
-        Ordering::Greater => b..=a,
-    }
-}
-
 impl LavaductLagoon {
     fn dig_trench(&mut self, dig_instruction: &DigInstruction) {
-        let length = dig_instruction.length as u16;
+        let length = dig_instruction.length as i64;
         let start_point = self.dig_position;
         let end_point;
         let max_x;
@@ -186,7 +258,7 @@ impl LavaductLagoon {
         match dig_instruction.direction {
             Direction::Up => {
                 let x = start_point.0;
-                let y = start_point.1 - (length as i32);
+                let y = start_point.1 - length;
 
                 end_point = (x, y);
                 max_x = end_point.0;
@@ -196,7 +268,7 @@ impl LavaductLagoon {
             }
             Direction::Down => {
                 let x = start_point.0;
-                let y = start_point.1 + (length as i32);
+                let y = start_point.1 + length;
 
                 end_point = (x, y);
                 max_x = end_point.0;
@@ -205,7 +277,7 @@ impl LavaductLagoon {
                 min_y = start_point.1;
             }
             Direction::Left => {
-                let x = start_point.0 - (length as i32);
+                let x = start_point.0 - length;
                 let y = start_point.1;
 
                 end_point = (x, y);
@@ -215,7 +287,7 @@ impl LavaductLagoon {
                 min_y = end_point.1;
             }
             Direction::Right => {
-                let x = start_point.0 + (length as i32);
+                let x = start_point.0 + length;
                 let y = start_point.1;
 
                 end_point = (x, y);
@@ -240,251 +312,302 @@ impl LavaductLagoon {
         self.trench_segments.push(segment);
     }
 
-    fn dig_trenches(&mut self, dig_instructions: &[DigInstruction]) {
+    /// Digs every trench of the plan and measures the lagoon they surround
+    fn dig_trenches(&mut self, dig_instructions: &[DigInstruction]) -> Result<(), DigPlanError> {
+        if dig_instructions.is_empty() {
+            return Err(DigPlanError::Empty);
+        }
+
         // Dig trenches
-        println!("Digging...");
         for instruction in dig_instructions {
             self.dig_trench(instruction);
         }
 
         // Determine dimensions
-        println!("Determining dimensions...");
-        let min_x = self
-            .trench_segments
-            .iter()
-            .min_by(|&a, &b| a.min_x.cmp(&b.min_x))
-            .unwrap()
-            .min_x;
-        let max_x = self
-            .trench_segments
-            .iter()
-            .max_by(|&a, &b| a.max_x.cmp(&b.max_x))
-            .unwrap()
-            .max_x;
-        let min_y = self
-            .trench_segments
-            .iter()
-            .min_by(|&a, &b| a.min_y.cmp(&b.min_y))
-            .unwrap()
-            .min_y;
-        let max_y = self
-            .trench_segments
-            .iter()
-            .max_by(|&a, &b| a.max_y.cmp(&b.max_y))
-            .unwrap()
-            .max_y;
+        let min_x = self.trench_segments.iter().map(|t| t.min_x).min().unwrap();
+        let max_x = self.trench_segments.iter().map(|t| t.max_x).max().unwrap();
+        let min_y = self.trench_segments.iter().map(|t| t.min_y).min().unwrap();
+        let max_y = self.trench_segments.iter().map(|t| t.max_y).max().unwrap();
 
         let width = (max_x - min_x) + 1;
         let height = (max_y - min_y) + 1;
 
-        self.width = width as u16;
-        self.height = height as u16;
+        self.width = width as u64;
+        self.height = height as u64;
         self.min_x = min_x;
         self.max_x = max_x;
         self.min_y = min_y;
         self.max_y = max_y;
-
-        println!("Remapping origin...");
-        self.update_origin();
+        Ok(())
     }
 
-    /// Updates the [LavaductLagoon] so that the origin is in the upper-left corner and all coordinates
-    /// of all contained elements are positive.
-    fn update_origin(&mut self) {
-        let x_add = self.min_x.abs();
-        let y_add = self.min_y.abs();
-        for trench in &mut self.trench_segments {
-            trench.shift(x_add, y_add);
+    /// Counts the cubic meters of lava the lagoon holds, trench included, straight from the
+    /// trench corners. The shoelace formula gives the area inside the path through the middle of
+    /// the trench; Pick's theorem turns that into the number of interior cells, and the trench's
+    /// own cells are added on top.
+    fn lagoon_volume(&self) -> LagoonVolume {
+        let mut double_area = 0i64;
+        let mut perimeter = 0i64;
+        for trench in &self.trench_segments {
+            let (x1, y1) = trench.start;
+            let (x2, y2) = trench.end;
+            double_area += x1 * y2 - x2 * y1;
+            perimeter += (x2 - x1).abs() + (y2 - y1).abs();
         }
 
-        self.min_x += x_add;
-        self.max_x += x_add;
-        self.min_y += y_add;
-        self.max_y += y_add;
-    }
-
-    fn make_grid(&self) -> ColorGrid {
-        let rows = self.height as usize;
-        let cols = self.width as usize;
-        let mut grid = Vec::with_capacity(rows);
-        for _ in 0..rows {
-            let mut row = Vec::with_capacity(cols);
-            for _ in 0..cols {
-                let color = Rc::new(RefCell::new(Color::from(0x000000)));
-                row.push(color);
-            }
-            grid.push(row);
+        let interior = (double_area.abs() - perimeter) / 2 + 1;
+        LagoonVolume::new(perimeter as u64, interior as u64)
+    }
+
+    /// Checks that the trench is a closed loop that never runs into itself, which every way of
+    /// measuring the lagoon relies on.
+    ///
+    /// Trenches dug one after the other only meet at the corner between them, unless the second
+    /// turns right around and runs back over the first. Any other two trenches may not share a
+    /// single cell.
+    fn validate(&self) -> Result<(), DigPlanError> {
+        let Some(last) = self.trench_segments.last() else {
+            return Err(DigPlanError::Empty);
+        };
+        if last.end != (0, 0) {
+            return Err(DigPlanError::NotClosed {
+                index: self.trench_segments.len() - 1,
+                end: last.end,
+            });
         }
 
-        for trench in &self.trench_segments {
-            let trench_color = &trench.color;
-            if trench.start.0 == trench.end.0 {
-                // trench is vertical
-                let x = trench.start.0 as usize;
-                let y_range = absolute_range(trench.start.1 as u32, trench.end.1 as u32);
-                for y in y_range {
-                    let mut target_color = grid[y as usize][x].borrow_mut();
-                    *target_color = trench_color.clone();
-                }
-            }
-            if trench.start.1 == trench.end.1 {
-                // trench is horizontal
-                let x_range = absolute_range(trench.start.0 as u32, trench.end.0 as u32);
-                let y = trench.start.1 as usize;
-                for x in x_range {
-                    let mut target_color = grid[y][x as usize].borrow_mut();
-                    *target_color = trench_color.clone();
+        let count = self.trench_segments.len();
+        for (first, a) in self.trench_segments.iter().enumerate() {
+            for (second, b) in self.trench_segments.iter().enumerate().skip(first + 1) {
+                let adjacent = second == first + 1 || (first == 0 && second == count - 1);
+                let touches = a.min_x <= b.max_x
+                    && b.min_x <= a.max_x
+                    && a.min_y <= b.max_y
+                    && b.min_y <= a.max_y;
+                let crosses = if adjacent {
+                    // Sharing more than the corner means they overlap along a row or column
+                    let overlap_x = a.max_x.min(b.max_x) - a.min_x.max(b.min_x);
+                    let overlap_y = a.max_y.min(b.max_y) - a.min_y.max(b.min_y);
+                    touches && (overlap_x > 0 || overlap_y > 0)
+                } else {
+                    touches
+                };
+                if crosses {
+                    return Err(DigPlanError::Crossing { first, second });
                 }
             }
         }
-
-        grid
+        Ok(())
     }
 
-    fn draw_to_image(&self) -> RgbImage {
-        let mut img: RgbImage = ImageBuffer::from_pixel(
-            self.width as u32,
-            self.height as u32,
-            Rgb([255u8, 255, 255]),
-        );
+    /// Counts the cells of trench, which all lie along the lagoon's edge
+    fn trench_length(&self) -> u64 {
+        self.trench_segments
+            .iter()
+            .map(|trench| (trench.max_x - trench.min_x + trench.max_y - trench.min_y) as u64)
+            .sum()
+    }
 
+    /// Finds the dug out cells of row `y` as sorted, non-overlapping `(first x, last x)` spans.
+    ///
+    /// A ray along the row crosses the vertical trenches that start on or above it and end below
+    /// it, so every cell between the first and second crossing, the third and fourth and so on
+    /// is inside the lagoon. The trench's own cells on the row are added to those.
+    fn row_spans(&self, y: i64) -> Vec<(i64, i64)> {
+        let mut crossings = vec![];
+        let mut spans = vec![];
         for trench in &self.trench_segments {
-            let color: Rgb<u8> = Rgb::from(trench.color.clone());
-            if trench.start.0 == trench.end.0 {
-                // trench is vertical
-                let x = trench.start.0 as u32;
-                let y_range = absolute_range(trench.start.1 as u32, trench.end.1 as u32);
-                for y in y_range {
-                    img.put_pixel(x, y, color);
-                }
+            if y < trench.min_y || y > trench.max_y {
+                continue;
             }
-            if trench.start.1 == trench.end.1 {
-                // trench is horizontal
-                let x_range = absolute_range(trench.start.0 as u32, trench.end.0 as u32);
-                let y = trench.start.1 as u32;
-                for x in x_range {
-                    img.put_pixel(x, y, color);
-                }
+            spans.push((trench.min_x, trench.max_x));
+            if trench.min_x == trench.max_x && y < trench.max_y {
+                crossings.push(trench.min_x);
             }
         }
 
-        img
-    }
-}
+        crossings.sort_unstable();
+        spans.extend(crossings.chunks_exact(2).map(|pair| (pair[0], pair[1])));
+        spans.sort_unstable();
 
-type ColorGrid = Vec<Vec<Rc<RefCell<Color>>>>;
-
-fn flood_fill(color_grid: &mut ColorGrid, fill_color: Color) {
-    let width = color_grid[0].len();
-    let height = color_grid.len();
-    let start_x = width / 2;
-    let start_y = height / 2;
-    let inside_color = color_grid[start_y][start_x].borrow().clone();
-
-    let mut fill_queue = VecDeque::new();
-    {
-        let node = color_grid[start_y][start_x].clone();
-        fill_queue.push_front((node, (start_x, start_y)));
-    }
-    while !fill_queue.is_empty() {
-        let (node, (node_x, node_y)) = fill_queue.pop_front().unwrap();
-        let mut node = node.borrow_mut();
-        if *node == inside_color {
-            *node = fill_color.clone();
-            {
-                // North node
-                let x = node_x;
-                let (y, did_overflow) = node_y.overflowing_sub(1);
-                if !did_overflow {
-                    let node = color_grid[y][x].clone();
-                    fill_queue.push_front((node, (x, y)));
-                }
-            }
-            {
-                // South node
-                let x = node_x;
-                let y = node_y + 1;
-                if y < height {
-                    let node = color_grid[y][x].clone();
-                    fill_queue.push_front((node, (x, y)));
-                }
-            }
-            {
-                // East node
-                let (x, did_overflow) = node_x.overflowing_sub(1);
-                let y = node_y;
-                if !did_overflow {
-                    let node = color_grid[y][x].clone();
-                    fill_queue.push_front((node, (x, y)));
-                }
-            }
-            {
-                // West node
-                let x = node_x + 1;
-                let y = node_y;
-                if x < width {
-                    let node = color_grid[y][x].clone();
-                    fill_queue.push_front((node, (x, y)));
-                }
+        let mut merged: Vec<(i64, i64)> = Vec::with_capacity(spans.len());
+        for (start, end) in spans {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
             }
         }
+        merged
     }
-}
 
-const TEST_INPUT: &str = "R 6 (#70c710)\n\
-                          D 5 (#0dc571)\n\
-                          L 2 (#5713f0)\n\
-                          D 2 (#d2c081)\n\
-                          R 2 (#59c680)\n\
-                          D 2 (#411b91)\n\
-                          L 5 (#8ceee2)\n\
-                          U 2 (#caa173)\n\
-                          L 1 (#1b58a2)\n\
-                          U 2 (#caa171)\n\
-                          R 2 (#7807d2)\n\
-                          U 3 (#a77fa3)\n\
-                          L 2 (#015232)\n\
-                          U 2 (#7a21e3)";
-
-const LAVA_ORANGE: u32 = 0xF76806;
+    /// Counts the same cells as [LavaductLagoon::lagoon_volume] one row at a time with
+    /// [LavaductLagoon::row_spans], keeping only a single row's crossings in memory. The spans
+    /// don't tell trench and interior apart, so the interior is whatever the trench leaves over.
+    fn scanline_volume(&self) -> LagoonVolume {
+        let total: u64 = (self.min_y..=self.max_y)
+            .flat_map(|y| self.row_spans(y))
+            .map(|(start, end)| (end - start + 1) as u64)
+            .sum();
+        let trench_length = self.trench_length();
+        LagoonVolume::new(trench_length, total - trench_length)
+    }
+}
 
 fn main() {
-    // let instructions = TEST_INPUT
-    //     .lines()
-    //     .filter_map(|line| DigInstruction::from_str(line).ok())
-    //     .collect::<Vec<_>>();
-    let instructions = fs::read_to_string("input.txt")
-        .expect("failed to open input file")
+    let input = fs::read_to_string("input.txt").expect("failed to open input file");
+    let args: Vec<String> = env::args().collect();
+    let verify = args.iter().any(|arg| arg == "--verify");
+
+    let instructions = parse_plan(&input, DigInstruction::from_str);
+    let lavaduct_lagoon = dig_or_exit(&instructions);
+
+    let hex_instructions = parse_plan(&input, DigInstruction::from_hex_line);
+    let hex_lagoon = dig_or_exit(&hex_instructions);
+
+    if args.iter().any(|arg| arg == "--json") {
+        print_json(&lavaduct_lagoon, &hex_lagoon);
+        return;
+    }
+
+    let volume = lavaduct_lagoon.lagoon_volume();
+    println!("Part 1 result: {}", volume.total);
+    println!("  {volume}");
+    if verify {
+        report_check("scanline fill", volume, lavaduct_lagoon.scanline_volume());
+        let compressed_volume = lavaduct_lagoon.compressed_grid().volume();
+        report_check("compressed grid", volume, compressed_volume);
+    }
+
+    let hex_volume = hex_lagoon.lagoon_volume();
+    println!("Part 2 result: {}", hex_volume.total);
+    println!("  {hex_volume}");
+    if verify {
+        let compressed_volume = hex_lagoon.compressed_grid().volume();
+        report_check("compressed grid", hex_volume, compressed_volume);
+    }
+
+    if let Some(i) = args.iter().position(|arg| arg == "--render") {
+        let Some(path) = args.get(i + 1) else {
+            eprintln!("--render needs an output path");
+            process::exit(1);
+        };
+        render_lagoon(&lavaduct_lagoon, path);
+    }
+    if args.iter().any(|arg| arg == "--live") {
+        play_fill_live(&lavaduct_lagoon);
+    }
+}
+
+/// Reads every line of the dig plan with `parse`, exiting with the index of the first line it
+/// can't read
+fn parse_plan(
+    input: &str,
+    parse: impl Fn(&str) -> Result<DigInstruction, ParseDigInstructionError>,
+) -> Vec<DigInstruction> {
+    input
         .lines()
-        .filter_map(|line| DigInstruction::from_str(line).ok())
-        .collect::<Vec<_>>();
+        .enumerate()
+        .map(|(index, line)| parse(line).map_err(|_| index))
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|index| {
+            eprintln!("invalid dig plan: line {index} isn't a dig instruction");
+            process::exit(1);
+        })
+}
+
+/// Digs out the lagoon and checks the trench, exiting if it isn't a valid loop
+fn dig_or_exit(instructions: &[DigInstruction]) -> LavaductLagoon {
     let mut lavaduct_lagoon = LavaductLagoon::default();
+    let dug = lavaduct_lagoon.dig_trenches(instructions);
+    if let Err(err) = dug.and_then(|()| lavaduct_lagoon.validate()) {
+        eprintln!("invalid dig plan: {err}");
+        process::exit(1);
+    }
+    lavaduct_lagoon
+}
+
+/// Prints whether another way of filling the lagoon came to the same `volume`
+fn report_check(method: &str, volume: LagoonVolume, found: LagoonVolume) {
+    if found == volume {
+        println!(
+            "Verified: the {method} also holds {} cubic meters",
+            volume.total
+        );
+    } else {
+        println!("Verification failed: the {method} holds {found}");
+    }
+}
 
-    lavaduct_lagoon.dig_trenches(&instructions);
-
-    println!("Creating color grid...");
-    let mut color_grid = lavaduct_lagoon.make_grid();
-    println!("Filling lagoon...");
-    flood_fill(&mut color_grid, Color::from(LAVA_ORANGE));
-
-    println!("Writing image to file...");
-    let out_img: RgbImage = ImageBuffer::from_fn(
-        lavaduct_lagoon.width as u32,
-        lavaduct_lagoon.height as u32,
-        |x, y| {
-            let color = color_grid[y as usize][x as usize].borrow().clone();
-            Rgb::from(color)
-        },
-    );
-    out_img
-        .save("out.png")
-        .expect("failed to write output image");
+/// Prints both parts' lagoons as JSON, along with how much lava each one holds
+#[cfg(feature = "serde")]
+fn print_json(lavaduct_lagoon: &LavaductLagoon, hex_lagoon: &LavaductLagoon) {
+    let part = |lagoon: &LavaductLagoon| {
+        serde_json::json!({
+            "lagoon": lagoon,
+            "volume": lagoon.lagoon_volume(),
+        })
+    };
+    let json = serde_json::json!({
+        "part_1": part(lavaduct_lagoon),
+        "part_2": part(hex_lagoon),
+    });
+    println!("{json:#}");
+}
+
+#[cfg(not(feature = "serde"))]
+fn print_json(_: &LavaductLagoon, _: &LavaductLagoon) {
+    eprintln!("--json needs day_18 to be built with the `serde` feature");
+    process::exit(1);
+}
+
+#[cfg(feature = "render")]
+fn render_lagoon(lavaduct_lagoon: &LavaductLagoon, path: &str) {
+    if let Err(err) = lavaduct_lagoon.render(path) {
+        eprintln!("failed to render the lagoon: {err}");
+        process::exit(1);
+    }
+    println!("Wrote the lagoon to {path}");
+}
+
+#[cfg(not(feature = "render"))]
+fn render_lagoon(_: &LavaductLagoon, _: &str) {
+    eprintln!("--render needs day_18 to be built with the `render` feature");
+    process::exit(1);
+}
+
+#[cfg(feature = "render")]
+fn play_fill_live(lavaduct_lagoon: &LavaductLagoon) {
+    if let Err(err) = lavaduct_lagoon.play_fill_live() {
+        eprintln!("failed to play the fill: {err}");
+        process::exit(1);
+    }
+}
+
+#[cfg(not(feature = "render"))]
+fn play_fill_live(_: &LavaductLagoon) {
+    eprintln!("--live needs day_18 to be built with the `render` feature");
+    process::exit(1);
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    pub(crate) const TEST_INPUT: &str = "R 6 (#70c710)\n\
+                                         D 5 (#0dc571)\n\
+                                         L 2 (#5713f0)\n\
+                                         D 2 (#d2c081)\n\
+                                         R 2 (#59c680)\n\
+                                         D 2 (#411b91)\n\
+                                         L 5 (#8ceee2)\n\
+                                         U 2 (#caa173)\n\
+                                         L 1 (#1b58a2)\n\
+                                         U 2 (#caa171)\n\
+                                         R 2 (#7807d2)\n\
+                                         U 3 (#a77fa3)\n\
+                                         L 2 (#015232)\n\
+                                         U 2 (#7a21e3)";
+
     #[test]
     fn parse_color_from_u32() {
         let input = 0x00ABCDEFu32;
@@ -536,14 +659,220 @@ mod test {
     fn dig_trenches() {
         let instructions = TEST_INPUT
             .lines()
-            .filter_map(|line| DigInstruction::from_str(line).ok())
+            .map(|line| DigInstruction::from_str(line).unwrap())
             .collect::<Vec<_>>();
         let expected_width = 7;
         let expected_height = 10;
         let mut lavaduct_lagoon = LavaductLagoon::default();
 
-        lavaduct_lagoon.dig_trenches(&instructions);
+        lavaduct_lagoon.dig_trenches(&instructions).unwrap();
         assert_eq!(lavaduct_lagoon.width, expected_width);
         assert_eq!(lavaduct_lagoon.height, expected_height);
+
+        // Positions stay relative to the start, even where they go negative
+        let instructions = ["L 3", "U 2", "R 3", "D 2"].map(|step| {
+            format!("{step} (#000000)")
+                .parse::<DigInstruction>()
+                .unwrap()
+        });
+        let mut lavaduct_lagoon = LavaductLagoon::default();
+        lavaduct_lagoon.dig_trenches(&instructions).unwrap();
+        assert_eq!((lavaduct_lagoon.min_x, lavaduct_lagoon.min_y), (-3, -2));
+        assert_eq!((lavaduct_lagoon.max_x, lavaduct_lagoon.max_y), (0, 0));
+        assert_eq!((lavaduct_lagoon.width, lavaduct_lagoon.height), (4, 3));
+        assert_eq!(lavaduct_lagoon.trench_segments[1].start, (-3, 0));
+        assert_eq!(lavaduct_lagoon.scanline_volume(), LagoonVolume::new(10, 2));
+    }
+
+    #[test]
+    fn parse_dig_instruction_from_hex_line() {
+        let expected = DigInstruction {
+            direction: Direction::Right,
+            length: 461937,
+            color: Color {
+                red: 0x70,
+                green: 0xC7,
+                blue: 0x10,
+            },
+        };
+        assert_eq!(DigInstruction::from_hex_line("R 6 (#70c710)"), Ok(expected));
+
+        let directions = TEST_INPUT
+            .lines()
+            .take(4)
+            .map(|line| DigInstruction::from_hex_line(line).unwrap().direction)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            directions,
+            vec![
+                Direction::Right,
+                Direction::Down,
+                Direction::Right,
+                Direction::Down
+            ]
+        );
+
+        assert_eq!(
+            DigInstruction::from_hex_line("R 6 (#70c714)"),
+            Err(ParseDigInstructionError)
+        );
+        assert_eq!(
+            DigInstruction::from_hex_line("R 6 (#70c7)"),
+            Err(ParseDigInstructionError)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let instruction = "R 6 (#70c710)".parse::<DigInstruction>().unwrap();
+        let json = serde_json::to_value(&instruction).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "direction": "Right",
+                "length": 6,
+                "color": {"red": 0x70, "green": 0xC7, "blue": 0x10},
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<DigInstruction>(json).unwrap(),
+            instruction
+        );
+
+        let instructions = TEST_INPUT
+            .lines()
+            .map(|line| line.parse::<DigInstruction>().unwrap())
+            .collect::<Vec<_>>();
+        let mut lavaduct_lagoon = LavaductLagoon::default();
+        lavaduct_lagoon.dig_trenches(&instructions).unwrap();
+        let json = serde_json::to_string(&lavaduct_lagoon).unwrap();
+        let read_back = serde_json::from_str::<LavaductLagoon>(&json).unwrap();
+        assert_eq!(read_back, lavaduct_lagoon);
+        assert_eq!(read_back.lagoon_volume().total, 62);
+    }
+
+    #[test]
+    fn lagoon_volume() {
+        let mut lavaduct_lagoon = LavaductLagoon::default();
+        let instructions = TEST_INPUT
+            .lines()
+            .map(|line| DigInstruction::from_str(line).unwrap())
+            .collect::<Vec<_>>();
+        lavaduct_lagoon.dig_trenches(&instructions).unwrap();
+        assert_eq!(lavaduct_lagoon.lagoon_volume(), LagoonVolume::new(38, 24));
+        assert_eq!(lavaduct_lagoon.lagoon_volume().total, 62);
+        assert_eq!(
+            lavaduct_lagoon.lagoon_volume().to_string(),
+            "62 cubic meters: 38 of trench and 24 inside it"
+        );
+
+        let mut hex_lagoon = LavaductLagoon::default();
+        let instructions = TEST_INPUT
+            .lines()
+            .map(|line| DigInstruction::from_hex_line(line).unwrap())
+            .collect::<Vec<_>>();
+        hex_lagoon.dig_trenches(&instructions).unwrap();
+        assert_eq!(hex_lagoon.lagoon_volume().total, 952408144115);
+    }
+
+    #[test]
+    fn scanline_volume() {
+        let mut lavaduct_lagoon = LavaductLagoon::default();
+        let instructions = TEST_INPUT
+            .lines()
+            .map(|line| DigInstruction::from_str(line).unwrap())
+            .collect::<Vec<_>>();
+        lavaduct_lagoon.dig_trenches(&instructions).unwrap();
+        assert_eq!(
+            lavaduct_lagoon.row_spans(lavaduct_lagoon.min_y),
+            vec![(0, 6)]
+        );
+        // The row "###.###" crosses the lagoon where two trenches meet it from above
+        assert_eq!(lavaduct_lagoon.row_spans(5), vec![(0, 6)]);
+        assert_eq!(lavaduct_lagoon.row_spans(6), vec![(0, 4)]);
+        assert_eq!(lavaduct_lagoon.scanline_volume(), LagoonVolume::new(38, 24));
+
+        // A U shape, so one row is split into two spans
+        let mut u_lagoon = LavaductLagoon::default();
+        let instructions = ["R 2", "D 3", "R 2", "U 3", "R 2", "D 5", "L 6", "U 5"].map(|step| {
+            format!("{step} (#000000)")
+                .parse::<DigInstruction>()
+                .unwrap()
+        });
+        u_lagoon.dig_trenches(&instructions).unwrap();
+        assert_eq!(u_lagoon.row_spans(1), vec![(0, 2), (4, 6)]);
+        assert_eq!(u_lagoon.scanline_volume(), u_lagoon.lagoon_volume());
+        assert_eq!(u_lagoon.scanline_volume().total, 39);
+    }
+
+    #[test]
+    fn validate_dig_plan() {
+        let dig = |steps: &[&str]| {
+            let instructions = steps
+                .iter()
+                .map(|step| {
+                    format!("{step} (#000000)")
+                        .parse::<DigInstruction>()
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            let mut lavaduct_lagoon = LavaductLagoon::default();
+            lavaduct_lagoon.dig_trenches(&instructions)?;
+            lavaduct_lagoon.validate()
+        };
+
+        let instructions = TEST_INPUT.lines().collect::<Vec<_>>();
+        let mut lavaduct_lagoon = LavaductLagoon::default();
+        lavaduct_lagoon
+            .dig_trenches(
+                &instructions
+                    .iter()
+                    .map(|line| line.parse::<DigInstruction>().unwrap())
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap();
+        assert_eq!(lavaduct_lagoon.validate(), Ok(()));
+
+        assert_eq!(dig(&[]), Err(DigPlanError::Empty));
+        assert_eq!(
+            dig(&["R 2", "D 2", "L 2"]),
+            Err(DigPlanError::NotClosed {
+                index: 2,
+                end: (0, 2)
+            })
+        );
+        // A figure eight, where the third trench runs through the first
+        assert_eq!(
+            dig(&["R 2", "D 2", "L 1", "U 4", "L 1", "D 2", "R 0"]),
+            Err(DigPlanError::Crossing {
+                first: 0,
+                second: 3
+            })
+        );
+        // Turning right around runs back over the trench just dug
+        assert_eq!(
+            dig(&["R 3", "L 1", "D 2", "L 2", "U 2"]),
+            Err(DigPlanError::Crossing {
+                first: 0,
+                second: 1
+            })
+        );
+        // Digging on past the start leaves the loop open
+        assert_eq!(
+            dig(&["R 2", "D 2", "L 2", "U 2", "R 1"]),
+            Err(DigPlanError::NotClosed {
+                index: 4,
+                end: (1, 0)
+            })
+        );
+        // Overshooting the start and coming back runs through the first trench
+        assert_eq!(
+            dig(&["D 2", "R 2", "U 2", "L 3", "R 1"]),
+            Err(DigPlanError::Crossing {
+                first: 0,
+                second: 3
+            })
+        );
     }
 }
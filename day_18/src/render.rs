@@ -0,0 +1,324 @@
+use super::{Color, LavaductLagoon};
+use aoc_viz::{Frame, Palette, Player, Png, Point, Renderer};
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+
+const LAVA_ORANGE: u32 = 0xF76806;
+
+/// Colors for [LavaductLagoon::play_fill]. The trench is last so it still shows if the player has
+/// to shrink the lagoon to fit.
+const GROUND: u8 = 0;
+const LAVA: u8 = 1;
+const FILL_FRONT: u8 = 2;
+const TRENCH: u8 = 3;
+
+#[derive(Debug)]
+pub(crate) enum RenderError {
+    /// The trench doesn't leave a single cell inside it to start filling from
+    NoInterior,
+    Viz(aoc_viz::RenderError),
+}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderError::NoInterior => write!(f, "the trench has no cells inside it to fill"),
+            RenderError::Viz(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl From<Color> for aoc_viz::Color {
+    fn from(value: Color) -> Self {
+        aoc_viz::Color::rgb(value.red, value.green, value.blue)
+    }
+}
+
+impl LavaductLagoon {
+    /// Moves a lagoon position into the frame, whose top left cell is the lagoon's top left cell
+    fn to_cell(&self, (x, y): (i64, i64)) -> Point {
+        ((y - self.min_y) as usize, (x - self.min_x) as usize)
+    }
+
+    /// The lagoon with each cell of the `i`th trench set to `trench_index(i)`, and the rest left as
+    /// [GROUND]
+    fn trench_frame(&self, trench_index: impl Fn(usize) -> u8) -> Frame {
+        let mut frame = Frame::new(self.width as usize, self.height as usize, GROUND);
+        for (i, trench) in self.trench_segments.iter().enumerate() {
+            // Every trench is either a single column or a single row of cells
+            for y in trench.min_y..=trench.max_y {
+                for x in trench.min_x..=trench.max_x {
+                    frame.set(self.to_cell((x, y)), trench_index(i));
+                }
+            }
+        }
+        frame
+    }
+
+    /// Finds a cell inside the lagoon that isn't part of the trench. Along each row, the cells
+    /// right after every odd vertical crossing are inside the loop; the first of those that isn't
+    /// dug out by a trench running along the row is taken.
+    fn interior_seed(&self) -> Option<(i64, i64)> {
+        for y in self.min_y..=self.max_y {
+            let mut crossings = self
+                .trench_segments
+                .iter()
+                .filter(|trench| trench.min_x == trench.max_x)
+                .filter(|trench| trench.min_y <= y && y < trench.max_y)
+                .map(|trench| trench.min_x)
+                .collect::<Vec<_>>();
+            crossings.sort_unstable();
+
+            for pair in crossings.chunks_exact(2) {
+                let on_trench = |x: i64| {
+                    self.trench_segments.iter().any(|trench| {
+                        (trench.min_x..=trench.max_x).contains(&x)
+                            && (trench.min_y..=trench.max_y).contains(&y)
+                    })
+                };
+                if let Some(x) = (pair[0] + 1..pair[1]).find(|&x| !on_trench(x)) {
+                    return Some((x, y));
+                }
+            }
+        }
+        None
+    }
+
+    /// The trench in its own colors with the lagoon inside it full of lava, along with the
+    /// palette to draw it with. The trench colors are merged into fewer if there are too many
+    /// for one palette.
+    fn filled_frame(&self) -> Result<(Frame, Palette), RenderError> {
+        let seed = self.interior_seed().ok_or(RenderError::NoInterior)?;
+        let mut palette = Palette::new([aoc_viz::Color::WHITE, aoc_viz::Color::from(LAVA_ORANGE)]);
+        let trench_colors: Vec<aoc_viz::Color> = self
+            .trench_segments
+            .iter()
+            .map(|trench| trench.color.clone().into())
+            .collect();
+        let trench_indices = palette
+            .push_quantized(&trench_colors)
+            .expect("there's room for the trench after the ground and the lava");
+
+        let mut frame = self.trench_frame(|i| trench_indices[i]);
+        flood_fill(&mut frame, self.to_cell(seed), LAVA);
+        Ok((frame, palette))
+    }
+
+    /// Draws the trench, fills the lagoon inside it and saves the picture to `path`
+    pub(crate) fn render(&self, path: &str) -> Result<(), RenderError> {
+        let (frame, palette) = self.filled_frame()?;
+        Png::single(path, palette)
+            .render(&frame)
+            .map_err(RenderError::Viz)
+    }
+
+    /// Plays the lagoon filling up from a cell inside the trench, one frame for each ring of
+    /// cells the fill spreads to. Stops early if `renderer` does.
+    pub(crate) fn play_fill(&self, renderer: &mut impl Renderer) -> Result<(), RenderError> {
+        let seed = self.interior_seed().ok_or(RenderError::NoInterior)?;
+        let mut frame = self.trench_frame(|_| TRENCH);
+
+        let mut front = vec![self.to_cell(seed)];
+        frame.set(front[0], FILL_FRONT);
+        while !front.is_empty() && !renderer.is_stopped() {
+            renderer.render(&frame).map_err(RenderError::Viz)?;
+            let mut next_front = vec![];
+            for &(row, col) in &front {
+                frame.set((row, col), LAVA);
+                // The lagoon's edge is all trench, so the fill never gets to the frame's edge
+                for next in [
+                    (row - 1, col),
+                    (row + 1, col),
+                    (row, col - 1),
+                    (row, col + 1),
+                ] {
+                    if frame.get(next) == Some(GROUND) {
+                        frame.set(next, FILL_FRONT);
+                        next_front.push(next);
+                    }
+                }
+            }
+            front = next_front;
+        }
+        if !renderer.is_stopped() {
+            renderer.render(&frame).map_err(RenderError::Viz)?;
+        }
+        Ok(())
+    }
+
+    /// Plays [LavaductLagoon::play_fill] in the terminal, with keys to pause and step through it
+    pub(crate) fn play_fill_live(&self) -> Result<(), RenderError> {
+        let palette = Palette::new([
+            aoc_viz::Color::from(0xF2EEE6),
+            aoc_viz::Color::from(LAVA_ORANGE),
+            aoc_viz::Color::from(0xFFD23F),
+            aoc_viz::Color::from(0x3B2F2F),
+        ]);
+        self.play_fill(&mut Player::stdout(palette).fps(30.0))
+    }
+}
+
+/// Fills the region of `frame` that holds the same index as the `seed` cell with `fill`
+fn flood_fill(frame: &mut Frame, seed: Point, fill: u8) {
+    let width = frame.width();
+    let height = frame.height();
+    let Some(inside) = frame.get(seed) else {
+        return;
+    };
+    if inside == fill {
+        return;
+    }
+
+    let mut fill_queue = VecDeque::new();
+    fill_queue.push_front(seed);
+    while let Some((row, col)) = fill_queue.pop_front() {
+        if frame.get((row, col)) != Some(inside) {
+            continue;
+        }
+        frame.set((row, col), fill);
+
+        // North, south, west and east
+        if row > 0 {
+            fill_queue.push_front((row - 1, col));
+        }
+        if row + 1 < height {
+            fill_queue.push_front((row + 1, col));
+        }
+        if col > 0 {
+            fill_queue.push_front((row, col - 1));
+        }
+        if col + 1 < width {
+            fill_queue.push_front((row, col + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::TEST_INPUT;
+    use crate::DigInstruction;
+    use aoc_viz::Recording;
+    use std::str::FromStr;
+
+    fn count(frame: &Frame, index: u8) -> usize {
+        frame
+            .rows()
+            .flatten()
+            .filter(|&&cell| cell == index)
+            .count()
+    }
+
+    #[test]
+    fn flood_fill_trench_frame() {
+        let mut lavaduct_lagoon = LavaductLagoon::default();
+        let instructions = TEST_INPUT
+            .lines()
+            .map(|line| DigInstruction::from_str(line).unwrap())
+            .collect::<Vec<_>>();
+        lavaduct_lagoon.dig_trenches(&instructions).unwrap();
+
+        let mut frame = lavaduct_lagoon.trench_frame(|_| TRENCH);
+        assert_eq!(count(&frame, TRENCH), 38);
+
+        let seed = lavaduct_lagoon.interior_seed().unwrap();
+        assert_eq!(seed, (1, 1));
+        flood_fill(&mut frame, lavaduct_lagoon.to_cell(seed), LAVA);
+        assert_eq!(count(&frame, TRENCH) + count(&frame, LAVA), 62);
+    }
+
+    #[test]
+    fn filled_frame_keeps_trench_colors() {
+        let mut lavaduct_lagoon = LavaductLagoon::default();
+        let instructions = TEST_INPUT
+            .lines()
+            .map(|line| DigInstruction::from_str(line).unwrap())
+            .collect::<Vec<_>>();
+        lavaduct_lagoon.dig_trenches(&instructions).unwrap();
+
+        let (frame, palette) = lavaduct_lagoon.filled_frame().unwrap();
+        assert_eq!(count(&frame, LAVA), 62 - 38);
+        assert_eq!(palette.get(GROUND), Some(aoc_viz::Color::WHITE));
+        // The first trench runs right from the start in #70c710, and there are few enough
+        // colors that none of them get merged
+        let first_trench = frame.get(lavaduct_lagoon.to_cell((3, 0))).unwrap();
+        assert_eq!(
+            palette.get(first_trench),
+            Some(aoc_viz::Color::from(0x70C710))
+        );
+    }
+
+    #[test]
+    fn fill_frames() {
+        let mut lavaduct_lagoon = LavaductLagoon::default();
+        let instructions = TEST_INPUT
+            .lines()
+            .map(|line| DigInstruction::from_str(line).unwrap())
+            .collect::<Vec<_>>();
+        lavaduct_lagoon.dig_trenches(&instructions).unwrap();
+
+        let mut recording = Recording::new();
+        lavaduct_lagoon.play_fill(&mut recording).unwrap();
+        let frames = recording.frames();
+        let first = &frames[0];
+        assert_eq!(count(first, TRENCH), 38);
+        assert_eq!(count(first, FILL_FRONT), 1);
+        assert_eq!(first.get((1, 1)), Some(FILL_FRONT));
+        // Everything inside ends up full of lava, with no front left
+        let last = frames.last().unwrap();
+        assert_eq!(count(last, LAVA), 62 - 38);
+        assert_eq!(count(last, FILL_FRONT), 0);
+        assert!(frames.len() > 2);
+    }
+
+    #[test]
+    fn interior_seed() {
+        // The center of this lagoon's bounding box is outside the trench
+        let mut l_lagoon = LavaductLagoon::default();
+        let instructions = ["R 2", "D 4", "R 4", "D 2", "L 6", "U 6"].map(|step| {
+            format!("{step} (#000000)")
+                .parse::<DigInstruction>()
+                .unwrap()
+        });
+        l_lagoon.dig_trenches(&instructions).unwrap();
+        let seed = l_lagoon.interior_seed().unwrap();
+        assert_eq!(seed, (1, 1));
+
+        let mut frame = l_lagoon.trench_frame(|_| TRENCH);
+        assert_eq!(frame.get((3, 3)), Some(GROUND));
+        flood_fill(&mut frame, l_lagoon.to_cell(seed), LAVA);
+        // Five cells down the narrow arm and four more along the bottom
+        assert_eq!(count(&frame, LAVA), 9);
+        assert_eq!(frame.get((3, 3)), Some(GROUND));
+
+        // Two cells wide, so there's nothing inside the trench
+        let mut thin_lagoon = LavaductLagoon::default();
+        let instructions = ["R 3", "D 1", "L 3", "U 1"].map(|step| {
+            format!("{step} (#000000)")
+                .parse::<DigInstruction>()
+                .unwrap()
+        });
+        thin_lagoon.dig_trenches(&instructions).unwrap();
+        assert_eq!(thin_lagoon.interior_seed(), None);
+    }
+
+    #[test]
+    fn draw_up_and_left_of_the_start() {
+        let mut lavaduct_lagoon = LavaductLagoon::default();
+        let instructions = ["L 3", "U 3", "R 3", "D 3"].map(|step| {
+            format!("{step} (#000000)")
+                .parse::<DigInstruction>()
+                .unwrap()
+        });
+        lavaduct_lagoon.dig_trenches(&instructions).unwrap();
+        assert_eq!(lavaduct_lagoon.to_cell((-3, -3)), (0, 0));
+        assert_eq!(lavaduct_lagoon.to_cell((0, 0)), (3, 3));
+
+        let mut frame = lavaduct_lagoon.trench_frame(|_| TRENCH);
+        assert_eq!((frame.width(), frame.height()), (4, 4));
+        let seed = lavaduct_lagoon.interior_seed().unwrap();
+        assert_eq!(seed, (-2, -2));
+        flood_fill(&mut frame, lavaduct_lagoon.to_cell(seed), LAVA);
+        assert_eq!(count(&frame, GROUND), 0);
+    }
+}
@@ -0,0 +1,21 @@
+use std::ops::Range;
+
+/// Splits `range` into the values below `at` and the values from `at` on. Either half comes out
+/// empty when `at` is outside the range.
+pub fn split_at(range: &Range<u64>, at: u64) -> (Range<u64>, Range<u64>) {
+    let mid = at.clamp(range.start, range.end);
+    (range.start..mid, mid..range.end)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn split_ranges() {
+        assert_eq!(split_at(&(1..10), 4), (1..4, 4..10));
+        assert_eq!(split_at(&(1..10), 0), (1..1, 1..10));
+        assert_eq!(split_at(&(1..10), 20), (1..10, 10..10));
+        assert_eq!(split_at(&(1..10), 10), (1..10, 10..10));
+    }
+}
@@ -0,0 +1,553 @@
+mod interval;
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::str::FromStr;
+use std::{fs, process};
+
+/// The workflow every part starts in
+const START_WORKFLOW: &str = "in";
+
+/// Every rating a part can have
+const RATING_RANGE: Range<u64> = 1..4001;
+
+/// One of the four ratings every part has
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Category {
+    /// Extremely cool looking
+    X,
+    /// Musical
+    M,
+    /// Aerodynamic
+    A,
+    /// Shiny
+    S,
+}
+
+impl Category {
+    /// Where this category's rating sits in a part's ratings
+    fn index(self) -> usize {
+        match self {
+            Category::X => 0,
+            Category::M => 1,
+            Category::A => 2,
+            Category::S => 3,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ParseCategoryError;
+
+impl TryFrom<char> for Category {
+    type Error = ParseCategoryError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'x' => Ok(Self::X),
+            'm' => Ok(Self::M),
+            'a' => Ok(Self::A),
+            's' => Ok(Self::S),
+            _ => Err(ParseCategoryError),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+struct Part {
+    /// Indexed by [Category::index]
+    ratings: [u64; 4],
+}
+
+impl Part {
+    fn rating(&self, category: Category) -> u64 {
+        self.ratings[category.index()]
+    }
+
+    fn total_rating(&self) -> u64 {
+        self.ratings.iter().sum()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ParsePartError;
+
+impl FromStr for Part {
+    type Err = ParsePartError;
+
+    /// Parses a part like `{x=787,m=2655,a=1222,s=2876}`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .ok_or(ParsePartError)?;
+
+        let mut ratings = [None; 4];
+        for field in fields.split(',') {
+            let (category, rating) = field.split_once('=').ok_or(ParsePartError)?;
+            let mut category_chars = category.chars();
+            let category = match (category_chars.next(), category_chars.next()) {
+                (Some(c), None) => Category::try_from(c).or(Err(ParsePartError))?,
+                _ => return Err(ParsePartError),
+            };
+            let rating = rating.parse::<u64>().or(Err(ParsePartError))?;
+            if ratings[category.index()].replace(rating).is_some() {
+                return Err(ParsePartError);
+            }
+        }
+
+        let [Some(x), Some(m), Some(a), Some(s)] = ratings else {
+            return Err(ParsePartError);
+        };
+        Ok(Self {
+            ratings: [x, m, a, s],
+        })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Comparison {
+    LessThan,
+    GreaterThan,
+}
+
+/// A test on one of a part's ratings, like `a<2006`
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+struct Condition {
+    category: Category,
+    comparison: Comparison,
+    value: u64,
+}
+
+impl Condition {
+    fn matches(&self, part: &Part) -> bool {
+        let rating = part.rating(self.category);
+        match self.comparison {
+            Comparison::LessThan => rating < self.value,
+            Comparison::GreaterThan => rating > self.value,
+        }
+    }
+}
+
+/// Every part whose ratings fall within the ranges, one for each category
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct PartRanges {
+    /// Indexed by [Category::index]
+    ratings: [Range<u64>; 4],
+}
+
+impl PartRanges {
+    /// How many different parts the ranges hold
+    fn combinations(&self) -> u64 {
+        self.ratings
+            .iter()
+            .map(|range| range.end.saturating_sub(range.start))
+            .product()
+    }
+
+    /// A copy with the range for `category` swapped for `range`
+    fn with_range(&self, category: Category, range: Range<u64>) -> Self {
+        let mut ranges = self.clone();
+        ranges.ratings[category.index()] = range;
+        ranges
+    }
+}
+
+impl Condition {
+    /// Splits `ranges` into the parts that match this condition and the parts that don't. Either
+    /// can end up with no parts in it.
+    fn split(&self, ranges: &PartRanges) -> (PartRanges, PartRanges) {
+        let range = &ranges.ratings[self.category.index()];
+        let (matching, rest) = match self.comparison {
+            Comparison::LessThan => interval::split_at(range, self.value),
+            Comparison::GreaterThan => match self.value.checked_add(1) {
+                Some(above) => {
+                    let (rest, matching) = interval::split_at(range, above);
+                    (matching, rest)
+                }
+                // No rating is above the largest one there is
+                None => (range.end..range.end, range.clone()),
+            },
+        };
+        (
+            ranges.with_range(self.category, matching),
+            ranges.with_range(self.category, rest),
+        )
+    }
+}
+
+impl FromStr for Condition {
+    type Err = ParseWorkflowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let category = chars
+            .next()
+            .and_then(|c| Category::try_from(c).ok())
+            .ok_or(ParseWorkflowError)?;
+        let comparison = match chars.next() {
+            Some('<') => Comparison::LessThan,
+            Some('>') => Comparison::GreaterThan,
+            _ => return Err(ParseWorkflowError),
+        };
+        let value = chars.as_str().parse().or(Err(ParseWorkflowError))?;
+
+        Ok(Self {
+            category,
+            comparison,
+            value,
+        })
+    }
+}
+
+/// Where a rule sends a part
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum Target {
+    Accept,
+    Reject,
+    Workflow(String),
+}
+
+impl From<&str> for Target {
+    fn from(value: &str) -> Self {
+        match value {
+            "A" => Target::Accept,
+            "R" => Target::Reject,
+            name => Target::Workflow(name.to_string()),
+        }
+    }
+}
+
+/// The rules of one workflow. A part goes to the target of the first rule whose condition it
+/// matches, or to `fallback` if it matches none of them.
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct Workflow {
+    rules: Vec<(Condition, Target)>,
+    fallback: Target,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ParseWorkflowError;
+
+/// Parses a workflow like `px{a<2006:qkq,m>2090:A,rfg}` into its name and rules
+fn parse_workflow(s: &str) -> Result<(String, Workflow), ParseWorkflowError> {
+    let (name, rules) = s
+        .strip_suffix('}')
+        .and_then(|s| s.split_once('{'))
+        .ok_or(ParseWorkflowError)?;
+    if name.is_empty() {
+        return Err(ParseWorkflowError);
+    }
+
+    let mut rules = rules.split(',').collect::<Vec<_>>();
+    let fallback = match rules.pop() {
+        Some(fallback) if !fallback.is_empty() && !fallback.contains(':') => Target::from(fallback),
+        _ => return Err(ParseWorkflowError),
+    };
+    let rules = rules
+        .into_iter()
+        .map(|rule| {
+            let (condition, target) = rule.split_once(':').ok_or(ParseWorkflowError)?;
+            if target.is_empty() {
+                return Err(ParseWorkflowError);
+            }
+            Ok((condition.parse()?, Target::from(target)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((name.to_string(), Workflow { rules, fallback }))
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum ParseSystemError {
+    /// There's no blank line between the workflows and the parts
+    MissingParts,
+    InvalidWorkflow(String),
+    InvalidPart(String),
+    /// A rule sends parts to a workflow that doesn't exist
+    UnknownWorkflow(String),
+}
+
+impl Display for ParseSystemError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseSystemError::MissingParts => write!(f, "there is no list of parts"),
+            ParseSystemError::InvalidWorkflow(line) => write!(f, "'{line}' is not a workflow"),
+            ParseSystemError::InvalidPart(line) => write!(f, "'{line}' is not a part"),
+            ParseSystemError::UnknownWorkflow(name) => {
+                write!(f, "there is no workflow named '{name}'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseSystemError {}
+
+/// The workflows along with the parts to sort through them
+#[derive(Debug, Eq, PartialEq)]
+struct System {
+    workflows: HashMap<String, Workflow>,
+    parts: Vec<Part>,
+}
+
+impl FromStr for System {
+    type Err = ParseSystemError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.replace("\r\n", "\n");
+        let (workflows, parts) = s
+            .trim()
+            .split_once("\n\n")
+            .ok_or(ParseSystemError::MissingParts)?;
+
+        let workflows = workflows
+            .lines()
+            .map(|line| {
+                parse_workflow(line)
+                    .map_err(|_| ParseSystemError::InvalidWorkflow(line.to_string()))
+            })
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        let parts = parts
+            .lines()
+            .map(|line| {
+                line.parse()
+                    .map_err(|_| ParseSystemError::InvalidPart(line.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let targets = workflows
+            .values()
+            .flat_map(|workflow| {
+                workflow
+                    .rules
+                    .iter()
+                    .map(|(_, target)| target)
+                    .chain([&workflow.fallback])
+            })
+            .filter_map(|target| match target {
+                Target::Workflow(name) => Some(name.as_str()),
+                _ => None,
+            });
+        for name in [START_WORKFLOW].into_iter().chain(targets) {
+            if !workflows.contains_key(name) {
+                return Err(ParseSystemError::UnknownWorkflow(name.to_string()));
+            }
+        }
+
+        Ok(Self { workflows, parts })
+    }
+}
+
+impl System {
+    /// Sends `part` through the workflows from `in` until it's accepted or rejected. The
+    /// workflows are assumed not to send a part round in a loop, which the puzzle input never does.
+    fn accepts(&self, part: &Part) -> bool {
+        let mut workflow = &self.workflows[START_WORKFLOW];
+        loop {
+            let target = workflow
+                .rules
+                .iter()
+                .find(|(condition, _)| condition.matches(part))
+                .map_or(&workflow.fallback, |(_, target)| target);
+            match target {
+                Target::Accept => return true,
+                Target::Reject => return false,
+                Target::Workflow(name) => workflow = &self.workflows[name],
+            }
+        }
+    }
+
+    /// Counts how many parts the workflows would accept out of every part with all four ratings in
+    /// [RATING_RANGE]. Rather than trying parts one by one, whole ranges of ratings are sent
+    /// through the workflows and split at each condition into the parts that match it and the
+    /// parts that go on to the next rule.
+    fn accepted_combinations(&self) -> u64 {
+        let all = PartRanges {
+            ratings: [(); 4].map(|_| RATING_RANGE),
+        };
+        let mut pending = vec![(all, &self.workflows[START_WORKFLOW])];
+        let mut accepted = 0;
+        while let Some((mut ranges, workflow)) = pending.pop() {
+            let mut targets = vec![];
+            for (condition, target) in &workflow.rules {
+                let (matching, rest) = condition.split(&ranges);
+                targets.push((matching, target));
+                ranges = rest;
+            }
+            // Each rule takes its matching parts out in turn, so the fallback gets what's left
+            targets.push((ranges, &workflow.fallback));
+
+            for (matching, target) in targets {
+                if matching.combinations() == 0 {
+                    continue;
+                }
+                match target {
+                    Target::Accept => accepted += matching.combinations(),
+                    Target::Reject => {}
+                    Target::Workflow(name) => pending.push((matching, &self.workflows[name])),
+                }
+            }
+        }
+        accepted
+    }
+
+    /// Adds up every rating of every part that ends up accepted
+    fn accepted_rating_sum(&self) -> u64 {
+        self.parts
+            .iter()
+            .filter(|part| self.accepts(part))
+            .map(Part::total_rating)
+            .sum()
+    }
+}
+
+fn main() {
+    let system = fs::read_to_string("input.txt")
+        .expect("failed to open input file")
+        .parse::<System>()
+        .unwrap_or_else(|err| {
+            eprintln!("invalid input: {err}");
+            process::exit(1);
+        });
+
+    println!("Part 1 result: {}", system.accepted_rating_sum());
+    println!("Part 2 result: {}", system.accepted_combinations());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "px{a<2006:qkq,m>2090:A,rfg}\n\
+                              pv{a>1716:R,A}\n\
+                              lnx{m>1548:A,A}\n\
+                              rfg{s<537:gd,x>2440:R,A}\n\
+                              qs{s>3448:A,lnx}\n\
+                              qkq{x<1416:A,crn}\n\
+                              crn{x>2662:A,R}\n\
+                              in{s<1351:px,qqz}\n\
+                              qqz{s>2770:qs,m<1801:hdj,R}\n\
+                              gd{a>3333:R,R}\n\
+                              hdj{m>838:A,pv}\n\
+                              \n\
+                              {x=787,m=2655,a=1222,s=2876}\n\
+                              {x=1679,m=44,a=2067,s=496}\n\
+                              {x=2036,m=264,a=79,s=2244}\n\
+                              {x=2461,m=1339,a=466,s=291}\n\
+                              {x=2127,m=1623,a=2188,s=1013}\n";
+
+    #[test]
+    fn parse_workflow_rules() {
+        let (name, workflow) = parse_workflow("px{a<2006:qkq,m>2090:A,rfg}").unwrap();
+        assert_eq!(name, "px");
+        assert_eq!(
+            workflow.rules,
+            vec![
+                (
+                    Condition {
+                        category: Category::A,
+                        comparison: Comparison::LessThan,
+                        value: 2006,
+                    },
+                    Target::Workflow("qkq".to_string())
+                ),
+                (
+                    Condition {
+                        category: Category::M,
+                        comparison: Comparison::GreaterThan,
+                        value: 2090,
+                    },
+                    Target::Accept
+                ),
+            ]
+        );
+        assert_eq!(workflow.fallback, Target::Workflow("rfg".to_string()));
+
+        for bad in [
+            "px{}",
+            "{A}",
+            "px{a<20:A}",
+            "px{q<20:A,R}",
+            "px{a=20:A,R}",
+            "px{a<:A,R}",
+        ] {
+            assert_eq!(parse_workflow(bad), Err(ParseWorkflowError), "{bad}");
+        }
+    }
+
+    #[test]
+    fn parse_part() {
+        let part = "{x=787,m=2655,a=1222,s=2876}".parse::<Part>().unwrap();
+        assert_eq!(part.ratings, [787, 2655, 1222, 2876]);
+        assert_eq!(part.rating(Category::A), 1222);
+
+        assert_eq!("{x=1,m=2,a=3}".parse::<Part>(), Err(ParsePartError));
+        assert_eq!("{x=1,m=2,a=3,x=4}".parse::<Part>(), Err(ParsePartError));
+        assert_eq!("x=1,m=2,a=3,s=4".parse::<Part>(), Err(ParsePartError));
+    }
+
+    #[test]
+    fn parse_system_errors() {
+        assert_eq!(
+            "in{A}".parse::<System>(),
+            Err(ParseSystemError::MissingParts)
+        );
+        assert_eq!(
+            "in{x<5:nope,A}\n\n{x=1,m=2,a=3,s=4}".parse::<System>(),
+            Err(ParseSystemError::UnknownWorkflow("nope".to_string()))
+        );
+        assert_eq!(
+            "px{A}\n\n{x=1,m=2,a=3,s=4}".parse::<System>(),
+            Err(ParseSystemError::UnknownWorkflow("in".to_string()))
+        );
+    }
+
+    #[test]
+    fn accepted_parts() {
+        let system = TEST_INPUT.parse::<System>().unwrap();
+        let accepted = system
+            .parts
+            .iter()
+            .map(|part| system.accepts(part))
+            .collect::<Vec<_>>();
+        assert_eq!(accepted, vec![true, false, true, false, true]);
+        assert_eq!(system.accepted_rating_sum(), 19114);
+    }
+
+    #[test]
+    fn condition_split() {
+        let ranges = PartRanges {
+            ratings: [1..11, 1..11, 1..11, 1..11],
+        };
+        let less = "x<4".parse::<Condition>().unwrap();
+        let (matching, rest) = less.split(&ranges);
+        assert_eq!(matching.ratings[0], 1..4);
+        assert_eq!(rest.ratings[0], 4..11);
+        assert_eq!(matching.combinations(), 3 * 10 * 10 * 10);
+
+        let greater = "s>4".parse::<Condition>().unwrap();
+        let (matching, rest) = greater.split(&ranges);
+        assert_eq!(matching.ratings[3], 5..11);
+        assert_eq!(rest.ratings[3], 1..5);
+
+        let outside = "m>20".parse::<Condition>().unwrap();
+        let (matching, rest) = outside.split(&ranges);
+        assert_eq!(matching.combinations(), 0);
+        assert_eq!(rest, ranges);
+
+        let above_max = format!("a>{}", u64::MAX).parse::<Condition>().unwrap();
+        let (matching, rest) = above_max.split(&ranges);
+        assert_eq!(matching.combinations(), 0);
+        assert_eq!(rest, ranges);
+    }
+
+    #[test]
+    fn accepted_combinations() {
+        let system = TEST_INPUT.parse::<System>().unwrap();
+        assert_eq!(system.accepted_combinations(), 167409079868000);
+
+        let accept_all = "in{A}\n\n{x=1,m=2,a=3,s=4}".parse::<System>().unwrap();
+        assert_eq!(accept_all.accepted_combinations(), 4000u64.pow(4));
+    }
+}
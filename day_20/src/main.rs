@@ -0,0 +1,570 @@
+mod math;
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::{fs, process};
+
+/// How many times part 1 pushes the button
+const BUTTON_PRESSES: usize = 1000;
+
+/// The module part 2 waits for a low pulse to reach
+const RX: &str = "rx";
+
+/// How many presses to watch for every feeder to send a high pulse twice before giving up
+const MAX_CYCLE_PRESSES: u64 = 100_000;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Pulse {
+    Low,
+    High,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum ModuleKind {
+    /// Passes every pulse on to all of its outputs
+    Broadcaster,
+    /// Ignores high pulses and flips on or off at each low one, sending high when it turns on and
+    /// low when it turns off
+    FlipFlop { on: bool },
+    /// Remembers the last pulse from each input, in the same order as [Module::inputs], and sends
+    /// low once they're all high and high otherwise
+    Conjunction { memory: Vec<Pulse> },
+    /// Only named as an output, so it takes pulses and does nothing with them
+    Untyped,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct Module {
+    name: String,
+    kind: ModuleKind,
+    /// Indices of the modules this one sends pulses to
+    outputs: Vec<usize>,
+    /// Indices of the modules that send pulses to this one
+    inputs: Vec<usize>,
+}
+
+impl Module {
+    /// Updates this module for `pulse` arriving from the module at index `from`, and gives the
+    /// pulse it sends on to its outputs, if any
+    fn receive(&mut self, from: usize, pulse: Pulse) -> Option<Pulse> {
+        match &mut self.kind {
+            ModuleKind::Broadcaster => Some(pulse),
+            ModuleKind::FlipFlop { on } => match pulse {
+                Pulse::High => None,
+                Pulse::Low => {
+                    *on = !*on;
+                    Some(if *on { Pulse::High } else { Pulse::Low })
+                }
+            },
+            ModuleKind::Conjunction { memory } => {
+                let slot = self.inputs.iter().position(|&input| input == from)?;
+                memory[slot] = pulse;
+                if memory.iter().all(|&last| last == Pulse::High) {
+                    Some(Pulse::Low)
+                } else {
+                    Some(Pulse::High)
+                }
+            }
+            ModuleKind::Untyped => None,
+        }
+    }
+}
+
+/// A pulse on its way from one module to another, both given by index
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+struct SentPulse {
+    from: usize,
+    to: usize,
+    pulse: Pulse,
+}
+
+/// How many pulses of each kind were sent
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+struct PulseCounts {
+    low: u64,
+    high: u64,
+}
+
+impl PulseCounts {
+    fn add(&mut self, pulse: Pulse) {
+        match pulse {
+            Pulse::Low => self.low += 1,
+            Pulse::High => self.high += 1,
+        }
+    }
+}
+
+/// Every module along with how they're wired together. Modules are referred to by their index
+/// in `modules`, and keep all their state in their [ModuleKind] so it can be looked at between
+/// button presses.
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct Network {
+    modules: Vec<Module>,
+    broadcaster: usize,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum ParseNetworkError {
+    InvalidLine(String),
+    /// Two lines describe a module with the same name
+    DuplicateModule(String),
+    MissingBroadcaster,
+}
+
+impl Display for ParseNetworkError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseNetworkError::InvalidLine(line) => write!(f, "'{line}' is not a module"),
+            ParseNetworkError::DuplicateModule(name) => {
+                write!(f, "module '{name}' is described more than once")
+            }
+            ParseNetworkError::MissingBroadcaster => write!(f, "there is no broadcaster"),
+        }
+    }
+}
+
+impl std::error::Error for ParseNetworkError {}
+
+/// The index of the module called `name`, adding it as an untyped module the first time it comes
+/// up
+fn module_index<'a>(
+    modules: &mut Vec<Module>,
+    indices: &mut HashMap<&'a str, usize>,
+    name: &'a str,
+) -> usize {
+    *indices.entry(name).or_insert_with(|| {
+        modules.push(Module {
+            name: name.to_string(),
+            kind: ModuleKind::Untyped,
+            outputs: vec![],
+            inputs: vec![],
+        });
+        modules.len() - 1
+    })
+}
+
+impl FromStr for Network {
+    type Err = ParseNetworkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modules: Vec<Module> = vec![];
+        let mut indices = HashMap::new();
+
+        let mut described = vec![];
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let invalid = || ParseNetworkError::InvalidLine(line.to_string());
+            let (module, outputs) = line.split_once(" -> ").ok_or_else(invalid)?;
+            let (kind, name) = if let Some(name) = module.strip_prefix('%') {
+                (ModuleKind::FlipFlop { on: false }, name)
+            } else if let Some(name) = module.strip_prefix('&') {
+                (ModuleKind::Conjunction { memory: vec![] }, name)
+            } else if module == "broadcaster" {
+                (ModuleKind::Broadcaster, module)
+            } else {
+                return Err(invalid());
+            };
+            if name.is_empty() || outputs.split(", ").any(str::is_empty) {
+                return Err(invalid());
+            }
+
+            let index = module_index(&mut modules, &mut indices, name);
+            if described.contains(&index) {
+                return Err(ParseNetworkError::DuplicateModule(name.to_string()));
+            }
+            described.push(index);
+            modules[index].kind = kind;
+            for output in outputs.split(", ") {
+                let output = module_index(&mut modules, &mut indices, output);
+                modules[index].outputs.push(output);
+                modules[output].inputs.push(index);
+            }
+        }
+
+        for module in &mut modules {
+            if let ModuleKind::Conjunction { memory } = &mut module.kind {
+                *memory = vec![Pulse::Low; module.inputs.len()];
+            }
+        }
+        let broadcaster = modules
+            .iter()
+            .position(|module| module.kind == ModuleKind::Broadcaster)
+            .ok_or(ParseNetworkError::MissingBroadcaster)?;
+
+        Ok(Self {
+            modules,
+            broadcaster,
+        })
+    }
+}
+
+/// Why the presses until `rx` gets a low pulse couldn't be worked out from the cycles of the
+/// modules feeding it. Each one is a way the network isn't shaped like the puzzle input.
+#[derive(Debug, Eq, PartialEq)]
+enum RxAnalysisError {
+    NoRx,
+    /// `rx` should have exactly one input
+    RxInputs(usize),
+    /// The module feeding `rx` should be a conjunction
+    ParentNotConjunction(String),
+    /// Nothing feeds the module feeding `rx`, so it never sends anything
+    ParentNoInputs(String),
+    /// Every module feeding the parent should be a conjunction
+    FeederNotConjunction(String),
+    /// The feeder never sent the parent a high pulse twice
+    NoCycle(String),
+    /// The feeder's second high pulse didn't come exactly twice as many presses in as its first,
+    /// so its cycle doesn't start at the first press
+    IrregularCycle {
+        feeder: String,
+        first: u64,
+        second: u64,
+    },
+}
+
+impl Display for RxAnalysisError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RxAnalysisError::NoRx => write!(f, "there is no '{RX}' module"),
+            RxAnalysisError::RxInputs(count) => {
+                write!(f, "'{RX}' has {count} inputs instead of one")
+            }
+            RxAnalysisError::ParentNotConjunction(name) => {
+                write!(f, "'{name}' feeds '{RX}' but is not a conjunction")
+            }
+            RxAnalysisError::ParentNoInputs(name) => {
+                write!(f, "'{name}' feeds '{RX}' but has no inputs")
+            }
+            RxAnalysisError::FeederNotConjunction(name) => {
+                write!(
+                    f,
+                    "'{name}' feeds the parent of '{RX}' but is not a conjunction"
+                )
+            }
+            RxAnalysisError::NoCycle(name) => write!(
+                f,
+                "'{name}' did not send two high pulses within {MAX_CYCLE_PRESSES} presses"
+            ),
+            RxAnalysisError::IrregularCycle {
+                feeder,
+                first,
+                second,
+            } => write!(
+                f,
+                "'{feeder}' sent high pulses at presses {first} and {second}, which is not a cycle \
+                 from the start"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RxAnalysisError {}
+
+impl Network {
+    /// The index of the module called `name`, if there is one
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.modules.iter().position(|module| module.name == name)
+    }
+
+    /// Works out how many presses it takes for `rx` to get a low pulse, without pressing that
+    /// many times.
+    ///
+    /// `rx` is fed by a single conjunction, which only sends low once its conjunction inputs have
+    /// all just sent it high during the same press. Each of those sends high on a fixed cycle of
+    /// presses, so the network is pressed until every cycle has been seen twice and the answer is
+    /// where they all line up. The shape of the network is checked first, and the cycles are
+    /// checked to start from the first press, since the shortcut is wrong otherwise.
+    fn presses_until_rx_low(&self) -> Result<u64, RxAnalysisError> {
+        let rx = self.index_of(RX).ok_or(RxAnalysisError::NoRx)?;
+        let parent = match self.modules[rx].inputs[..] {
+            [parent] => parent,
+            ref inputs => return Err(RxAnalysisError::RxInputs(inputs.len())),
+        };
+        if !matches!(self.modules[parent].kind, ModuleKind::Conjunction { .. }) {
+            let name = self.modules[parent].name.clone();
+            return Err(RxAnalysisError::ParentNotConjunction(name));
+        }
+        let feeders = &self.modules[parent].inputs;
+        if feeders.is_empty() {
+            let name = self.modules[parent].name.clone();
+            return Err(RxAnalysisError::ParentNoInputs(name));
+        }
+        for &feeder in feeders {
+            if !matches!(self.modules[feeder].kind, ModuleKind::Conjunction { .. }) {
+                let name = self.modules[feeder].name.clone();
+                return Err(RxAnalysisError::FeederNotConjunction(name));
+            }
+        }
+
+        // The presses at which each feeder sent the parent a high pulse, up to the first two
+        let mut high_presses = vec![vec![]; feeders.len()];
+        let mut network = self.clone();
+        for press in 1..=MAX_CYCLE_PRESSES {
+            network.push_button(|sent| {
+                if sent.to != parent || sent.pulse != Pulse::High {
+                    return;
+                }
+                let slot = feeders.iter().position(|&feeder| feeder == sent.from);
+                if let Some(presses) = slot.map(|slot| &mut high_presses[slot]) {
+                    if presses.len() < 2 && presses.last() != Some(&press) {
+                        presses.push(press);
+                    }
+                }
+            });
+            if high_presses.iter().all(|presses| presses.len() == 2) {
+                break;
+            }
+        }
+
+        let mut cycles = vec![];
+        for (&feeder, presses) in feeders.iter().zip(&high_presses) {
+            let feeder = self.modules[feeder].name.clone();
+            match presses[..] {
+                [first, second] if second == 2 * first => cycles.push(first),
+                [first, second] => {
+                    return Err(RxAnalysisError::IrregularCycle {
+                        feeder,
+                        first,
+                        second,
+                    })
+                }
+                _ => return Err(RxAnalysisError::NoCycle(feeder)),
+            }
+        }
+        Ok(cycles.into_iter().fold(1, math::lcm))
+    }
+
+    /// Pushes the button, sending a low pulse to the broadcaster, and handles pulses in the order
+    /// they were sent until none are left. `on_pulse` sees every pulse as it arrives, the one from
+    /// the button included, which comes from the broadcaster's own index.
+    fn push_button(&mut self, mut on_pulse: impl FnMut(&SentPulse)) -> PulseCounts {
+        let mut counts = PulseCounts::default();
+        let mut queue = VecDeque::from([SentPulse {
+            from: self.broadcaster,
+            to: self.broadcaster,
+            pulse: Pulse::Low,
+        }]);
+
+        while let Some(sent) = queue.pop_front() {
+            counts.add(sent.pulse);
+            on_pulse(&sent);
+
+            let module = &mut self.modules[sent.to];
+            if let Some(pulse) = module.receive(sent.from, sent.pulse) {
+                queue.extend(module.outputs.iter().map(|&to| SentPulse {
+                    from: sent.to,
+                    to,
+                    pulse,
+                }));
+            }
+        }
+
+        counts
+    }
+
+    /// Pushes the button `presses` times and multiplies the number of low pulses sent by the
+    /// number of high ones
+    fn pulse_product(&mut self, presses: usize) -> u64 {
+        let mut total = PulseCounts::default();
+        for _ in 0..presses {
+            let counts = self.push_button(|_| {});
+            total.low += counts.low;
+            total.high += counts.high;
+        }
+        total.low * total.high
+    }
+}
+
+fn main() {
+    let network = fs::read_to_string("input.txt")
+        .expect("failed to open input file")
+        .parse::<Network>()
+        .unwrap_or_else(|err| {
+            eprintln!("invalid input: {err}");
+            process::exit(1);
+        });
+
+    println!(
+        "Part 1 result: {}",
+        network.clone().pulse_product(BUTTON_PRESSES)
+    );
+
+    match network.presses_until_rx_low() {
+        Ok(presses) => println!("Part 2 result: {presses}"),
+        Err(err) => println!("Part 2 result not found: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "broadcaster -> a, b, c\n\
+                              %a -> b\n\
+                              %b -> c\n\
+                              %c -> inv\n\
+                              &inv -> a\n";
+
+    const TEST_INPUT_2: &str = "broadcaster -> a\n\
+                                %a -> inv, con\n\
+                                &inv -> b\n\
+                                %b -> con\n\
+                                &con -> output\n";
+
+    /// Three counters built from flip-flops, with conjunctions feeding `par` that send it high
+    /// every 2, 4 and 8 presses
+    const RX_INPUT: &str = "broadcaster -> a1, b1, c1\n\
+                            %a1 -> fa\n\
+                            %b1 -> b2\n\
+                            %b2 -> fb\n\
+                            %c1 -> c2\n\
+                            %c2 -> c3\n\
+                            %c3 -> fc\n\
+                            &fa -> par\n\
+                            &fb -> par\n\
+                            &fc -> par\n\
+                            &par -> rx\n";
+
+    /// Presses the button until `name` gets a low pulse, one press at a time
+    fn presses_until_low(network: &Network, name: &str) -> u64 {
+        let mut network = network.clone();
+        let target = network.index_of(name).unwrap();
+        (1..)
+            .find(|_| {
+                let mut low = false;
+                network.push_button(|sent| low |= sent.to == target && sent.pulse == Pulse::Low);
+                low
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_network() {
+        let network = TEST_INPUT_2.parse::<Network>().unwrap();
+        let con = network.index_of("con").unwrap();
+        let output = network.index_of("output").unwrap();
+        assert_eq!(network.modules[con].inputs.len(), 2);
+        assert_eq!(
+            network.modules[con].kind,
+            ModuleKind::Conjunction {
+                memory: vec![Pulse::Low; 2]
+            }
+        );
+        assert_eq!(network.modules[output].kind, ModuleKind::Untyped);
+        assert_eq!(network.modules[output].inputs, vec![con]);
+
+        assert_eq!(
+            "%a -> b".parse::<Network>(),
+            Err(ParseNetworkError::MissingBroadcaster)
+        );
+        assert_eq!(
+            "broadcaster -> a\n%a -> b\n&a -> b".parse::<Network>(),
+            Err(ParseNetworkError::DuplicateModule("a".to_string()))
+        );
+        assert_eq!(
+            "broadcaster a".parse::<Network>(),
+            Err(ParseNetworkError::InvalidLine("broadcaster a".to_string()))
+        );
+    }
+
+    #[test]
+    fn single_press() {
+        let mut network = TEST_INPUT.parse::<Network>().unwrap();
+        let mut sent = vec![];
+        let counts = network.push_button(|pulse| sent.push(*pulse));
+        assert_eq!(counts, PulseCounts { low: 8, high: 4 });
+
+        let names = sent
+            .iter()
+            .skip(1)
+            .map(|sent| {
+                let from = &network.modules[sent.from].name;
+                let to = &network.modules[sent.to].name;
+                format!("{from} -{:?}-> {to}", sent.pulse).to_lowercase()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "broadcaster -low-> a",
+                "broadcaster -low-> b",
+                "broadcaster -low-> c",
+                "a -high-> b",
+                "b -high-> c",
+                "c -high-> inv",
+                "inv -low-> a",
+                "a -low-> b",
+                "b -low-> c",
+                "c -low-> inv",
+                "inv -high-> a",
+            ]
+        );
+    }
+
+    #[test]
+    fn state_between_presses() {
+        let mut network = TEST_INPUT_2.parse::<Network>().unwrap();
+        let a = network.index_of("a").unwrap();
+        let b = network.index_of("b").unwrap();
+
+        network.push_button(|_| {});
+        assert_eq!(network.modules[a].kind, ModuleKind::FlipFlop { on: true });
+        assert_eq!(network.modules[b].kind, ModuleKind::FlipFlop { on: true });
+        network.push_button(|_| {});
+        assert_eq!(network.modules[a].kind, ModuleKind::FlipFlop { on: false });
+        assert_eq!(network.modules[b].kind, ModuleKind::FlipFlop { on: true });
+    }
+
+    #[test]
+    fn rx_from_cycles() {
+        let network = RX_INPUT.parse::<Network>().unwrap();
+        assert_eq!(network.presses_until_rx_low(), Ok(8));
+        assert_eq!(presses_until_low(&network, "rx"), 8);
+    }
+
+    #[test]
+    fn rx_shape_checks() {
+        let network = TEST_INPUT.parse::<Network>().unwrap();
+        assert_eq!(network.presses_until_rx_low(), Err(RxAnalysisError::NoRx));
+
+        let network = "broadcaster -> a\n%a -> rx".parse::<Network>().unwrap();
+        assert_eq!(
+            network.presses_until_rx_low(),
+            Err(RxAnalysisError::ParentNotConjunction("a".to_string()))
+        );
+
+        let network = "broadcaster -> a\n%a -> b\n&par -> rx"
+            .parse::<Network>()
+            .unwrap();
+        assert_eq!(
+            network.presses_until_rx_low(),
+            Err(RxAnalysisError::ParentNoInputs("par".to_string()))
+        );
+
+        let network = "broadcaster -> a\n%a -> par\n&par -> rx"
+            .parse::<Network>()
+            .unwrap();
+        assert_eq!(
+            network.presses_until_rx_low(),
+            Err(RxAnalysisError::FeederNotConjunction("a".to_string()))
+        );
+
+        // Nothing sends `f` a pulse, so it never sends one on
+        let network = "broadcaster -> a\n%a -> b\n&f -> par\n&par -> rx"
+            .parse::<Network>()
+            .unwrap();
+        assert_eq!(
+            network.presses_until_rx_low(),
+            Err(RxAnalysisError::NoCycle("f".to_string()))
+        );
+    }
+
+    #[test]
+    fn pulse_products() {
+        let mut network = TEST_INPUT.parse::<Network>().unwrap();
+        assert_eq!(network.pulse_product(1000), 32000000);
+
+        let mut network = TEST_INPUT_2.parse::<Network>().unwrap();
+        assert_eq!(network.pulse_product(1000), 11687500);
+    }
+}
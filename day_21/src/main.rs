@@ -0,0 +1,436 @@
+#[cfg(feature = "render")]
+mod render;
+
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::{env, fs};
+
+/// How many steps the elf has left in part 1
+const STEPS: u32 = 64;
+/// How many steps the elf has left in part 2, where the garden repeats forever
+const TILED_STEPS: u64 = 26_501_365;
+/// The garden map, stored row by row in one flat list with `true` for every rock
+#[derive(Debug, Eq, PartialEq)]
+struct Garden {
+    width: usize,
+    height: usize,
+    rocks: Vec<bool>,
+    start: (usize, usize),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ParseGardenError;
+
+impl FromStr for Garden {
+    type Err = ParseGardenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let width = s.lines().next().map_or(0, str::len);
+        let mut rocks = vec![];
+        let mut start = None;
+        let mut height = 0;
+        for (row, line) in s.lines().enumerate() {
+            if line.len() != width {
+                return Err(ParseGardenError);
+            }
+            for (col, c) in line.chars().enumerate() {
+                match c {
+                    '#' => rocks.push(true),
+                    '.' => rocks.push(false),
+                    'S' if start.is_none() => {
+                        start = Some((row, col));
+                        rocks.push(false);
+                    }
+                    _ => return Err(ParseGardenError),
+                }
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            width,
+            height,
+            rocks,
+            start: start.ok_or(ParseGardenError)?,
+        })
+    }
+}
+
+/// Why the plots reachable on the tiled garden couldn't be extrapolated. Each one is an assumption
+/// the puzzle input meets that the extrapolation depends on.
+#[derive(Debug, Eq, PartialEq)]
+enum ExtrapolationError {
+    /// The garden has to be square with an odd side
+    NotOddSquare {
+        width: usize,
+        height: usize,
+    },
+    StartNotCentered,
+    /// The steps have to reach exactly to the edge of a tile, so half a tile plus a whole number
+    /// of tiles
+    StepsNotAligned(u64),
+}
+
+impl Display for ExtrapolationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtrapolationError::NotOddSquare { width, height } => {
+                write!(
+                    f,
+                    "the garden is {width}x{height}, not a square with an odd side"
+                )
+            }
+            ExtrapolationError::StartNotCentered => {
+                write!(f, "the start is not in the middle of the garden")
+            }
+            ExtrapolationError::StepsNotAligned(steps) => write!(
+                f,
+                "{steps} steps is not half the garden plus a whole number of gardens"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExtrapolationError {}
+
+impl Garden {
+    /// The garden plots next to `pos`, leaving out rocks and anything past the edge
+    fn neighbors(&self, (row, col): (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        [
+            row.checked_sub(1).map(|row| (row, col)),
+            Some((row + 1, col)),
+            col.checked_sub(1).map(|col| (row, col)),
+            Some((row, col + 1)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|&(row, col)| {
+            row < self.height && col < self.width && !self.rocks[row * self.width + col]
+        })
+    }
+
+    /// How few steps it takes to get from the start to each plot, row by row like `rocks`, with
+    /// `None` for rocks and plots that can't be reached at all
+    fn distances(&self) -> Vec<Option<u32>> {
+        let mut distances = vec![None; self.rocks.len()];
+        let (start_row, start_col) = self.start;
+        distances[start_row * self.width + start_col] = Some(0);
+
+        let mut queue = VecDeque::from([(self.start, 0)]);
+        while let Some((pos, distance)) = queue.pop_front() {
+            for (row, col) in self.neighbors(pos) {
+                let known = &mut distances[row * self.width + col];
+                if known.is_none() {
+                    *known = Some(distance + 1);
+                    queue.push_back(((row, col), distance + 1));
+                }
+            }
+        }
+
+        distances
+    }
+
+    fn is_rock_tiled(&self, (row, col): (i64, i64)) -> bool {
+        let row = row.rem_euclid(self.height as i64) as usize;
+        let col = col.rem_euclid(self.width as i64) as usize;
+        self.rocks[row * self.width + col]
+    }
+
+    /// Same as [Garden::reachable_in], but with the garden repeated forever in every direction.
+    /// Every plot within reach is visited one at a time, so this is only for small step counts
+    /// and for checking [Garden::reachable_in_tiled] against.
+    fn reachable_in_tiled_slow(&self, steps: u32) -> usize {
+        let start = (self.start.0 as i64, self.start.1 as i64);
+        let mut seen = HashSet::from([start]);
+        let mut frontier = vec![start];
+        let mut reachable = usize::from(steps.is_multiple_of(2));
+        for step in 1..=steps {
+            let mut next_frontier = vec![];
+            for (row, col) in frontier {
+                for next in [
+                    (row - 1, col),
+                    (row + 1, col),
+                    (row, col - 1),
+                    (row, col + 1),
+                ] {
+                    if !self.is_rock_tiled(next) && seen.insert(next) {
+                        next_frontier.push(next);
+                    }
+                }
+            }
+            if (steps - step).is_multiple_of(2) {
+                reachable += next_frontier.len();
+            }
+            frontier = next_frontier;
+        }
+        reachable
+    }
+
+    /// Counts the plots reachable in exactly `steps` steps on the garden repeated forever, for
+    /// step counts far too big to walk.
+    ///
+    /// With a square garden, the start in the middle and `steps` reaching exactly to the edge of
+    /// a tile, the count grows quadratically in the number of whole tiles walked. So it's walked
+    /// for half a tile plus 0, 1 and 2 tiles, and the quadratic through those three counts gives
+    /// the rest. That only holds when the start's row and column are clear of rocks, which the
+    /// puzzle input is built to be but isn't checked here.
+    fn reachable_in_tiled(&self, steps: u64) -> Result<u64, ExtrapolationError> {
+        let side = self.width;
+        if side != self.height || side.is_multiple_of(2) {
+            return Err(ExtrapolationError::NotOddSquare {
+                width: self.width,
+                height: self.height,
+            });
+        }
+        let half = side / 2;
+        if self.start != (half, half) {
+            return Err(ExtrapolationError::StartNotCentered);
+        }
+        let side = side as u64;
+        let half = half as u64;
+        if steps < half || !(steps - half).is_multiple_of(side) {
+            return Err(ExtrapolationError::StepsNotAligned(steps));
+        }
+
+        let [y0, y1, y2] = [0, 1, 2].map(|tiles| {
+            let sample_steps = (half + tiles * side) as u32;
+            self.reachable_in_tiled_slow(sample_steps) as i64
+        });
+        let n = ((steps - half) / side) as i64;
+        // Newton's form of the quadratic through (0, y0), (1, y1) and (2, y2)
+        let first_diff = y1 - y0;
+        let second_diff = y2 - 2 * y1 + y0;
+        Ok((y0 + n * first_diff + n * (n - 1) / 2 * second_diff) as u64)
+    }
+
+    /// Like [Garden::distances], but over `copies` copies of the garden either side of the one the
+    /// start is in, in every direction. Gives the width and height of the whole area along with
+    /// the distances. Paths that leave the area aren't followed, so plots near its edge might be
+    /// reached later than they would be on the endless garden.
+    #[cfg_attr(not(feature = "render"), allow(dead_code))]
+    fn tiled_distances(&self, copies: usize) -> (usize, usize, Vec<Option<u32>>) {
+        let width = self.width * (2 * copies + 1);
+        let height = self.height * (2 * copies + 1);
+        let mut distances = vec![None; width * height];
+        let start = (
+            self.start.0 + copies * self.height,
+            self.start.1 + copies * self.width,
+        );
+        distances[start.0 * width + start.1] = Some(0);
+
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some(((row, col), distance)) = queue.pop_front() {
+            let neighbors = [
+                row.checked_sub(1).map(|row| (row, col)),
+                Some((row + 1, col)),
+                col.checked_sub(1).map(|col| (row, col)),
+                Some((row, col + 1)),
+            ];
+            for (row, col) in neighbors.into_iter().flatten() {
+                if row >= height || col >= width || self.is_rock_tiled((row as i64, col as i64)) {
+                    continue;
+                }
+                let known = &mut distances[row * width + col];
+                if known.is_none() {
+                    *known = Some(distance + 1);
+                    queue.push_back(((row, col), distance + 1));
+                }
+            }
+        }
+
+        (width, height, distances)
+    }
+
+    /// Counts the plots the elf could be standing on after exactly `steps` steps. Stepping away
+    /// and back again wastes two steps, so that's every plot at most `steps` away whose distance
+    /// has the same parity as `steps`.
+    fn reachable_in(&self, steps: u32) -> usize {
+        self.distances()
+            .into_iter()
+            .flatten()
+            .filter(|&distance| distance <= steps && (steps - distance).is_multiple_of(2))
+            .count()
+    }
+}
+
+fn main() {
+    let garden = fs::read_to_string("input.txt")
+        .expect("failed to open input file")
+        .parse::<Garden>()
+        .expect("failed to parse garden map");
+    let args: Vec<String> = env::args().collect();
+
+    if let Some(i) = args.iter().position(|arg| arg == "--simulate") {
+        let steps = args
+            .get(i + 1)
+            .and_then(|steps| steps.parse::<u32>().ok())
+            .expect("--simulate needs a number of steps");
+        let simulated = garden.reachable_in_tiled_slow(steps);
+        println!("Simulated {steps} steps: {simulated}");
+        match garden.reachable_in_tiled(steps as u64) {
+            Ok(extrapolated) if extrapolated == simulated as u64 => {
+                println!("Verified: extrapolating gives {extrapolated} too")
+            }
+            Ok(extrapolated) => println!("Verification failed: extrapolating gives {extrapolated}"),
+            Err(err) => println!("Can't extrapolate to compare: {err}"),
+        }
+        return;
+    }
+
+    if let Some(i) = args.iter().position(|arg| arg == "--animate") {
+        let steps = args
+            .get(i + 1)
+            .and_then(|steps| steps.parse::<u32>().ok())
+            .expect("--animate needs a number of steps");
+        let copies = match args.iter().position(|arg| arg == "--copies") {
+            Some(i) => args
+                .get(i + 1)
+                .and_then(|copies| copies.parse::<usize>().ok())
+                .expect("--copies needs a number of gardens"),
+            None => 0,
+        };
+        let frames_dir = args
+            .iter()
+            .position(|arg| arg == "--frames")
+            .map(|i| args.get(i + 1).expect("--frames needs an output directory"));
+        animate(&garden, steps, copies, frames_dir);
+        return;
+    }
+
+    println!("Part 1 result: {}", garden.reachable_in(STEPS));
+
+    match garden.reachable_in_tiled(TILED_STEPS) {
+        Ok(reachable) => println!("Part 2 result: {reachable}"),
+        Err(err) => println!("Part 2 result not found: {err}"),
+    }
+}
+
+/// Plays the steps in the terminal, or writes them to `frames_dir` as PNGs when it's given
+#[cfg(feature = "render")]
+fn animate(garden: &Garden, steps: u32, copies: usize, frames_dir: Option<&String>) {
+    match frames_dir {
+        Some(dir) => render::animate_to_files(garden, steps, copies, dir),
+        None => render::animate_live(garden, steps, copies),
+    }
+}
+
+#[cfg(not(feature = "render"))]
+fn animate(_: &Garden, _: u32, _: usize, _: Option<&String>) {
+    eprintln!("--animate needs day_21 to be built with the `render` feature");
+    std::process::exit(1);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "...........\n\
+                              .....###.#.\n\
+                              .###.##..#.\n\
+                              ..#.#...#..\n\
+                              ....#.#....\n\
+                              .##..S####.\n\
+                              .##..#...#.\n\
+                              .......##..\n\
+                              .##.#.####.\n\
+                              .##..##.##.\n\
+                              ...........\n";
+
+    #[test]
+    fn parse_garden() {
+        let garden = "S#\n..".parse::<Garden>().unwrap();
+        assert_eq!(garden.start, (0, 0));
+        assert_eq!(garden.rocks, vec![false, true, false, false]);
+
+        assert_eq!("..\n..".parse::<Garden>(), Err(ParseGardenError));
+        assert_eq!("S.\nS.".parse::<Garden>(), Err(ParseGardenError));
+        assert_eq!("S.\n.".parse::<Garden>(), Err(ParseGardenError));
+    }
+
+    #[test]
+    fn reachable_plots() {
+        let garden = TEST_INPUT.parse::<Garden>().unwrap();
+        assert_eq!(garden.reachable_in(1), 2);
+        assert_eq!(garden.reachable_in(2), 4);
+        assert_eq!(garden.reachable_in(3), 6);
+        assert_eq!(garden.reachable_in(6), 16);
+    }
+
+    #[test]
+    fn reachable_tiled_plots() {
+        let garden = TEST_INPUT.parse::<Garden>().unwrap();
+        for steps in [1, 2, 3, 6] {
+            assert_eq!(
+                garden.reachable_in_tiled_slow(steps),
+                garden.reachable_in(steps)
+            );
+        }
+        assert_eq!(garden.reachable_in_tiled_slow(10), 50);
+        assert_eq!(garden.reachable_in_tiled_slow(50), 1594);
+        assert_eq!(garden.reachable_in_tiled_slow(100), 6536);
+    }
+
+    #[test]
+    fn extrapolate_tiled_plots() {
+        // With no rocks the plots within reach make a diamond, every other one reachable
+        let open = ".....\n.....\n..S..\n.....\n....."
+            .parse::<Garden>()
+            .unwrap();
+        assert_eq!(open.reachable_in_tiled(2 + 5 * 6), Ok(33 * 33));
+
+        let garden = ".....\n.#.#.\n..S..\n.#.#.\n....."
+            .parse::<Garden>()
+            .unwrap();
+        for tiles in [0, 1, 2, 3, 7, 10] {
+            let steps = 2 + 5 * tiles;
+            assert_eq!(
+                garden.reachable_in_tiled(steps),
+                Ok(garden.reachable_in_tiled_slow(steps as u32) as u64),
+                "{steps} steps"
+            );
+        }
+
+        assert_eq!(
+            garden.reachable_in_tiled(10),
+            Err(ExtrapolationError::StepsNotAligned(10))
+        );
+        let garden = "S..\n...\n...".parse::<Garden>().unwrap();
+        assert_eq!(
+            garden.reachable_in_tiled(1),
+            Err(ExtrapolationError::StartNotCentered)
+        );
+        let garden = "S..\n...".parse::<Garden>().unwrap();
+        assert_eq!(
+            garden.reachable_in_tiled(1),
+            Err(ExtrapolationError::NotOddSquare {
+                width: 3,
+                height: 2
+            })
+        );
+    }
+
+    #[test]
+    fn tiled_distances_match_slow_walk() {
+        let garden = TEST_INPUT.parse::<Garden>().unwrap();
+        let tiled = garden.tiled_distances(2);
+        assert_eq!((tiled.0, tiled.1), (55, 55));
+        for steps in [6, 10, 16] {
+            let reachable = tiled
+                .2
+                .iter()
+                .flatten()
+                .filter(|&&distance| distance <= steps && (steps - distance).is_multiple_of(2))
+                .count();
+            assert_eq!(reachable, garden.reachable_in_tiled_slow(steps));
+        }
+    }
+
+    #[test]
+    fn walled_off_plots() {
+        // The plot in the corner is close, but there's no way round the rocks to it
+        let garden = "S.#\n.#.".parse::<Garden>().unwrap();
+        assert_eq!(garden.reachable_in(2), 1);
+        assert_eq!(garden.reachable_in(1), 2);
+    }
+}
@@ -0,0 +1,111 @@
+use super::Garden;
+use aoc_viz::{Color, Frame, Palette, Player, Png, Renderer};
+use std::process;
+
+/// How many frames of `--animate` play each second in the terminal
+const FRAMES_PER_SECOND: f64 = 12.5;
+
+/// Palette indices for the animation. Plots alternate between two colors from one copy of the
+/// garden to the next so the copies can be told apart. Plots already reached are split by
+/// whether the elf could be standing on them after the current step, which flips every step.
+const PLOT: u8 = 0;
+const PLOT_OTHER_COPY: u8 = 1;
+const ROCK: u8 = 2;
+const REACHABLE: u8 = 3;
+const OTHER_PARITY: u8 = 4;
+const FRONTIER: u8 = 5;
+
+fn palette() -> Palette {
+    Palette::new([
+        Color::from(0x2E4A2E),
+        Color::from(0x385A38),
+        Color::from(0x5C5C5C),
+        Color::from(0xF2D16B),
+        Color::from(0x8A7A3A),
+        Color::WHITE,
+    ])
+}
+
+/// Draws the plots reached on each step from 0 to `steps` over `copies` copies of the garden
+/// either side of the start's, one frame per step
+fn animate(garden: &Garden, steps: u32, copies: usize, renderer: &mut impl Renderer) {
+    let distances = garden.tiled_distances(copies);
+    for step in 0..=steps {
+        if renderer.is_stopped() {
+            break;
+        }
+        if let Err(err) = renderer.render(&garden.step_frame(&distances, step)) {
+            eprintln!("failed to draw step {step}: {err}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Writes each step to `dir` as its own PNG
+pub(crate) fn animate_to_files(garden: &Garden, steps: u32, copies: usize, dir: &str) {
+    let mut png = Png::sequence(dir, palette()).scale(2);
+    animate(garden, steps, copies, &mut png);
+    println!("Wrote {} frames to {dir}", steps + 1);
+}
+
+/// Plays the steps in the terminal, with keys to pause and step through them
+pub(crate) fn animate_live(garden: &Garden, steps: u32, copies: usize) {
+    let mut player = Player::stdout(palette())
+        .cell_width(1)
+        .fps(FRAMES_PER_SECOND);
+    animate(garden, steps, copies, &mut player);
+}
+
+impl Garden {
+    /// Draws the plots reached within `step` steps over the area from [Garden::tiled_distances]
+    fn step_frame(
+        &self,
+        (width, height, distances): &(usize, usize, Vec<Option<u32>>),
+        step: u32,
+    ) -> Frame {
+        Frame::from_fn(*width, *height, |(row, col)| {
+            match distances[row * width + col] {
+                Some(distance) if distance == step => FRONTIER,
+                Some(distance) if distance < step && (step - distance).is_multiple_of(2) => {
+                    REACHABLE
+                }
+                Some(distance) if distance < step => OTHER_PARITY,
+                _ if self.is_rock_tiled((row as i64, col as i64)) => ROCK,
+                _ if (row / self.height + col / self.width).is_multiple_of(2) => PLOT,
+                _ => PLOT_OTHER_COPY,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn step_frames() {
+        let garden = "...\n.S#\n...".parse::<Garden>().unwrap();
+        let tiled = garden.tiled_distances(0);
+        let frame = garden.step_frame(&tiled, 1);
+        let rows = frame.rows().collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                &[PLOT, FRONTIER, PLOT],
+                &[FRONTIER, OTHER_PARITY, ROCK],
+                &[PLOT, FRONTIER, PLOT]
+            ]
+        );
+
+        // The next step the start can be stood on again, and the first step's plots can't
+        let frame = garden.step_frame(&tiled, 2);
+        assert_eq!(frame.get((1, 1)), Some(REACHABLE));
+        assert_eq!(frame.get((0, 1)), Some(OTHER_PARITY));
+        assert_eq!(frame.get((0, 0)), Some(FRONTIER));
+
+        let tiled = garden.tiled_distances(1);
+        let frame = garden.step_frame(&tiled, 0);
+        assert_eq!(frame.get((0, 0)), Some(PLOT));
+        assert_eq!(frame.get((0, 3)), Some(PLOT_OTHER_COPY));
+    }
+}
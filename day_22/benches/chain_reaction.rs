@@ -0,0 +1,52 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_22::{Brick, SettledBricks};
+use std::fs;
+
+/// Builds `count` bricks of 1 to 4 cubes over a 10x10 area, starting anywhere up to `count` high
+/// so they stack up deeply once settled
+fn generate_bricks(count: usize) -> Vec<Brick> {
+    let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+    (0..count)
+        .map(|_| {
+            let (x, y, z) = (rng.u64(..10), rng.u64(..10), 1 + rng.u64(..count as u64));
+            let (dx, dy, dz) = match rng.u64(..3) {
+                0 => (rng.u64(..4), 0, 0),
+                1 => (0, rng.u64(..4), 0),
+                _ => (0, 0, rng.u64(..4)),
+            };
+            format!("{x},{y},{z}~{},{},{}", x + dx, y + dy, z + dz)
+                .parse()
+                .expect("generated brick should parse")
+        })
+        .collect()
+}
+
+fn load_bricks() -> Vec<Brick> {
+    match fs::read_to_string("input.txt") {
+        Ok(input) => input
+            .lines()
+            .map(|line| line.parse().expect("failed to parse bricks"))
+            .collect(),
+        Err(_) => {
+            println!("input.txt not found, benchmarking generated bricks instead");
+            generate_bricks(1500)
+        }
+    }
+}
+
+fn chain_reaction_benchmark(c: &mut Criterion) {
+    let bricks = load_bricks();
+    let settled = SettledBricks::settle(bricks.clone());
+
+    let mut group = c.benchmark_group("chain_reaction");
+    group.bench_function("settle", |b| {
+        b.iter(|| SettledBricks::settle(bricks.clone()))
+    });
+    group.bench_function("naive", |b| b.iter(|| settled.total_falling_naive()));
+    group.bench_function("dominators", |b| b.iter(|| settled.total_falling()));
+    group.finish();
+}
+
+criterion_group!(benches, chain_reaction_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,112 @@
+//! Writes the settled bricks out as a 3D model, to look at the stack in Blender or an online
+//! viewer rather than picking through coordinates.
+
+use crate::SettledBricks;
+use std::io::{self, Write};
+
+/// The corners of a unit cube, numbered so bit 0 picks the x side, bit 1 the y side and bit 2
+/// the z side
+const CORNERS: [(u32, u32, u32); 8] = [
+    (0, 0, 0),
+    (1, 0, 0),
+    (0, 1, 0),
+    (1, 1, 0),
+    (0, 0, 1),
+    (1, 0, 1),
+    (0, 1, 1),
+    (1, 1, 1),
+];
+
+/// The six faces of a cube as corners from [CORNERS], each going counterclockwise when looked at
+/// from outside
+const FACES: [[usize; 4]; 6] = [
+    [0, 2, 3, 1],
+    [4, 5, 7, 6],
+    [0, 1, 5, 4],
+    [2, 6, 7, 3],
+    [0, 4, 6, 2],
+    [1, 3, 7, 5],
+];
+
+/// Safe bricks are green, and the rest go from yellow to red the more bricks fall without them
+const SAFE: [f64; 3] = [0.30, 0.69, 0.31];
+const FEW_FALL: [f64; 3] = [1.00, 0.76, 0.03];
+const MOST_FALL: [f64; 3] = [0.83, 0.18, 0.18];
+
+/// Writes `settled` as a Wavefront OBJ model with one box per brick. Each brick is colored by how
+/// many other bricks fall if it's taken out, using vertex colors, which Blender and most viewers
+/// read.
+///
+/// OBJ models are normally Y up, so the puzzle's z axis becomes y. The puzzle's y axis points
+/// away from the viewer to keep the boxes the right way out.
+pub fn write_obj(settled: &SettledBricks, out: &mut impl Write) -> io::Result<()> {
+    let falling = settled.falling_counts();
+    let most_falling = falling.iter().copied().max().unwrap_or(0).max(1);
+
+    writeln!(out, "# {} settled bricks", settled.bricks.len())?;
+    for (index, brick) in settled.bricks.iter().enumerate() {
+        let color = match falling[index] {
+            0 => SAFE,
+            count => {
+                let t = (count - 1) as f64 / (most_falling - 1).max(1) as f64;
+                [0, 1, 2].map(|i| FEW_FALL[i] + (MOST_FALL[i] - FEW_FALL[i]) * t)
+            }
+        };
+        let [red, green, blue] = color;
+
+        writeln!(out, "o brick_{index}")?;
+        let (min, max) = (brick.0.min, brick.0.max);
+        for (dx, dy, dz) in CORNERS {
+            // The brick covers whole cubes, so its far side is one past its max coordinates
+            let x = if dx == 0 { min.x } else { max.x + 1 };
+            let y = if dy == 0 { min.y } else { max.y + 1 };
+            let z = if dz == 0 { min.z } else { max.z + 1 };
+            let y = -(y as i64);
+            writeln!(out, "v {x} {z} {y} {red:.3} {green:.3} {blue:.3}")?;
+        }
+        // OBJ counts vertices from 1 across the whole file
+        let first = index * CORNERS.len() + 1;
+        for face in FACES {
+            let [a, b, c, d] = face.map(|corner| first + corner);
+            writeln!(out, "f {a} {b} {c} {d}")?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Brick;
+
+    #[test]
+    fn boxes_for_bricks() {
+        let bricks = ["0,0,1~1,0,1", "0,0,3~0,0,3"]
+            .iter()
+            .map(|line| line.parse::<Brick>().unwrap())
+            .collect();
+        let settled = SettledBricks::settle(bricks);
+        let mut obj = vec![];
+        write_obj(&settled, &mut obj).unwrap();
+        let obj = String::from_utf8(obj).unwrap();
+        let lines = obj.lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            lines.iter().filter(|line| line.starts_with("v ")).count(),
+            16
+        );
+        assert_eq!(
+            lines.iter().filter(|line| line.starts_with("f ")).count(),
+            12
+        );
+        // The bottom brick holds up the top one, which has fallen onto it
+        assert_eq!(lines[1], "o brick_0");
+        assert_eq!(lines[2], "v 0 1 0 1.000 0.760 0.030");
+        assert_eq!(lines[9], "v 2 2 -1 1.000 0.760 0.030");
+        assert_eq!(lines[10], "f 1 3 4 2");
+        assert_eq!(lines[16], "o brick_1");
+        assert_eq!(lines[17], "v 0 2 0 0.300 0.690 0.310");
+        assert_eq!(lines[24], "v 1 3 -1 0.300 0.690 0.310");
+        assert_eq!(lines[25], "f 9 11 12 10");
+    }
+}
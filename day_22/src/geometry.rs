@@ -0,0 +1,99 @@
+use std::str::FromStr;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point3 {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParsePointError;
+
+impl FromStr for Point3 {
+    type Err = ParsePointError;
+
+    /// Parses a point like `1,0,1`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut coords = s.split(',').map(|coord| coord.trim().parse::<u32>());
+        match (coords.next(), coords.next(), coords.next(), coords.next()) {
+            (Some(Ok(x)), Some(Ok(y)), Some(Ok(z)), None) => Ok(Self { x, y, z }),
+            _ => Err(ParsePointError),
+        }
+    }
+}
+
+/// A box of whole cubes, covering every point from `min` to `max` on each axis, inclusive
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cuboid {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl Cuboid {
+    /// The cuboid with `a` and `b` at opposite corners, whichever way round they're given
+    pub fn new(a: Point3, b: Point3) -> Self {
+        Self {
+            min: Point3 {
+                x: a.x.min(b.x),
+                y: a.y.min(b.y),
+                z: a.z.min(b.z),
+            },
+            max: Point3 {
+                x: a.x.max(b.x),
+                y: a.y.max(b.y),
+                z: a.z.max(b.z),
+            },
+        }
+    }
+
+    pub fn height(&self) -> u32 {
+        self.max.z - self.min.z + 1
+    }
+
+    /// Every `(x, y)` column the cuboid covers, seen from above
+    pub fn footprint(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        (self.min.x..=self.max.x).flat_map(move |x| (self.min.y..=self.max.y).map(move |y| (x, y)))
+    }
+
+    /// The same cuboid moved up or down so its bottom is at `z`
+    pub fn with_bottom(&self, z: u32) -> Self {
+        let height = self.height();
+        Self {
+            min: Point3 { z, ..self.min },
+            max: Point3 {
+                z: z + height - 1,
+                ..self.max
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_point() {
+        assert_eq!("1,0,12".parse(), Ok(Point3 { x: 1, y: 0, z: 12 }));
+        assert_eq!("1,0".parse::<Point3>(), Err(ParsePointError));
+        assert_eq!("1,0,1,2".parse::<Point3>(), Err(ParsePointError));
+        assert_eq!("1,-1,2".parse::<Point3>(), Err(ParsePointError));
+    }
+
+    #[test]
+    fn cuboid_shape() {
+        let cuboid = Cuboid::new(Point3 { x: 2, y: 0, z: 5 }, Point3 { x: 0, y: 0, z: 6 });
+        assert_eq!(cuboid.min, Point3 { x: 0, y: 0, z: 5 });
+        assert_eq!(cuboid.height(), 2);
+        assert_eq!(
+            cuboid.footprint().collect::<Vec<_>>(),
+            vec![(0, 0), (1, 0), (2, 0)]
+        );
+        let dropped = cuboid.with_bottom(1);
+        assert_eq!((dropped.min.z, dropped.max.z), (1, 2));
+        assert_eq!(dropped.max.x, 2);
+    }
+}
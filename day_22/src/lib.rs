@@ -0,0 +1,385 @@
+pub mod export;
+mod geometry;
+
+use geometry::{Cuboid, Point3};
+use std::str::FromStr;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Brick(Cuboid);
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseBrickError;
+
+impl FromStr for Brick {
+    type Err = ParseBrickError;
+
+    /// Parses a brick like `1,0,1~1,2,1`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (a, b) = s.split_once('~').ok_or(ParseBrickError)?;
+        let a = a.parse::<Point3>().or(Err(ParseBrickError))?;
+        let b = b.parse::<Point3>().or(Err(ParseBrickError))?;
+        // The ground is at z = 0, so every brick starts above it
+        if a.z == 0 || b.z == 0 {
+            return Err(ParseBrickError);
+        }
+        Ok(Self(Cuboid::new(a, b)))
+    }
+}
+
+/// Checks the corners are the right way round and the brick is above the ground, like parsing does
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Brick {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cuboid = Cuboid::deserialize(deserializer)?;
+        let (min, max) = (cuboid.min, cuboid.max);
+        if min.x > max.x || min.y > max.y || min.z > max.z || min.z == 0 {
+            return Err(serde::de::Error::custom(format!(
+                "{cuboid:?} isn't a brick above the ground"
+            )));
+        }
+        Ok(Self(cuboid))
+    }
+}
+
+/// The bricks once they've all fallen as far as they can, along with which bricks rest on which
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SettledBricks {
+    bricks: Vec<Brick>,
+    /// For each brick, the bricks resting directly on top of it
+    supports: Vec<Vec<usize>>,
+    /// For each brick, the bricks it rests directly on. Empty for bricks on the ground.
+    supported_by: Vec<Vec<usize>>,
+}
+
+/// Checks every brick only rests on bricks before it, and that `supports` and `supported_by`
+/// agree with each other, since counting the falling bricks relies on both
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SettledBricks {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(serde::Deserialize)]
+        struct SettledBricksData {
+            bricks: Vec<Brick>,
+            supports: Vec<Vec<usize>>,
+            supported_by: Vec<Vec<usize>>,
+        }
+
+        let SettledBricksData {
+            bricks,
+            supports,
+            supported_by,
+        } = SettledBricksData::deserialize(deserializer)?;
+        if supports.len() != bricks.len() || supported_by.len() != bricks.len() {
+            return Err(D::Error::custom(format!(
+                "{} bricks need as many lists of supports",
+                bricks.len()
+            )));
+        }
+        for (above, belows) in supported_by.iter().enumerate() {
+            if let Some(below) = belows
+                .iter()
+                .find(|&&below| below >= above || !supports[below].contains(&above))
+            {
+                return Err(D::Error::custom(format!(
+                    "brick {above} can't rest on brick {below}"
+                )));
+            }
+        }
+        for (below, aboves) in supports.iter().enumerate() {
+            if let Some(above) = aboves.iter().find(|&&above| {
+                supported_by
+                    .get(above)
+                    .is_none_or(|belows| !belows.contains(&below))
+            }) {
+                return Err(D::Error::custom(format!(
+                    "brick {below} can't hold up brick {above}"
+                )));
+            }
+        }
+
+        Ok(Self {
+            bricks,
+            supports,
+            supported_by,
+        })
+    }
+}
+
+impl SettledBricks {
+    /// Drops every brick as far as it goes. Going through them from the lowest up means every
+    /// brick a brick could land on has already settled, so a height map of the tallest brick in
+    /// each column is all that's needed to see where it stops.
+    ///
+    /// The bricks stay in that order, so a brick always comes after every brick under it.
+    pub fn settle(mut bricks: Vec<Brick>) -> Self {
+        bricks.sort_by_key(|brick| brick.0.min.z);
+        let width = bricks
+            .iter()
+            .map(|brick| brick.0.max.x + 1)
+            .max()
+            .unwrap_or(0) as usize;
+        let depth = bricks
+            .iter()
+            .map(|brick| brick.0.max.y + 1)
+            .max()
+            .unwrap_or(0) as usize;
+        // The top of the tallest settled brick in each column and which brick that is
+        let mut height_map: Vec<(u32, Option<usize>)> = vec![(0, None); width * depth];
+        let column = |(x, y): (u32, u32)| y as usize * width + x as usize;
+
+        let mut supports = vec![vec![]; bricks.len()];
+        let mut supported_by = vec![vec![]; bricks.len()];
+        for (index, brick) in bricks.iter_mut().enumerate() {
+            let top = brick
+                .0
+                .footprint()
+                .map(|pos| height_map[column(pos)].0)
+                .max()
+                .unwrap_or(0);
+            brick.0 = brick.0.with_bottom(top + 1);
+
+            for pos in brick.0.footprint() {
+                let (height, below) = height_map[column(pos)];
+                if let Some(below) = below.filter(|_| height == top) {
+                    if !supported_by[index].contains(&below) {
+                        supported_by[index].push(below);
+                        supports[below].push(index);
+                    }
+                }
+                height_map[column(pos)] = (brick.0.max.z, Some(index));
+            }
+        }
+
+        Self {
+            bricks,
+            supports,
+            supported_by,
+        }
+    }
+
+    /// Whether taking out brick `index` leaves every brick on it still held up by another one
+    fn is_safe_to_disintegrate(&self, index: usize) -> bool {
+        self.supports[index]
+            .iter()
+            .all(|&above| self.supported_by[above].len() > 1)
+    }
+
+    pub fn safe_to_disintegrate(&self) -> usize {
+        (0..self.bricks.len())
+            .filter(|&index| self.is_safe_to_disintegrate(index))
+            .count()
+    }
+
+    /// How many other bricks fall if brick `index` is taken out, found by going up through the
+    /// bricks above it and dropping each one whose supports have all fallen
+    pub fn falling_bricks_naive(&self, index: usize) -> usize {
+        let mut fallen = vec![false; self.bricks.len()];
+        fallen[index] = true;
+        let mut count = 0;
+        for above in index + 1..self.bricks.len() {
+            let supports = &self.supported_by[above];
+            if !supports.is_empty() && supports.iter().all(|&below| fallen[below]) {
+                fallen[above] = true;
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Adds up [SettledBricks::falling_bricks_naive] for every brick, which goes through all the
+    /// bricks above each one in turn
+    pub fn total_falling_naive(&self) -> usize {
+        (0..self.bricks.len())
+            .map(|index| self.falling_bricks_naive(index))
+            .sum()
+    }
+
+    /// The immediate dominator of each brick in the support graph, with the ground at the root:
+    /// the closest brick that every path of supports from it down to the ground goes through, or
+    /// `None` if there isn't one. Taking out a brick drops exactly the bricks it dominates.
+    ///
+    /// Every brick comes after all the bricks under it, so a brick's dominator is the lowest
+    /// common ancestor of its supports in the tree built so far.
+    fn dominators(&self) -> Vec<Option<usize>> {
+        let mut dominators: Vec<Option<usize>> = vec![None; self.bricks.len()];
+        // How far each brick is from the ground in the dominator tree
+        let mut depths = vec![0; self.bricks.len()];
+        let depth = |depths: &[usize], brick: Option<usize>| brick.map_or(0, |brick| depths[brick]);
+
+        for index in 0..self.bricks.len() {
+            let mut supports = self.supported_by[index].iter().map(|&below| Some(below));
+            let first = supports.next().flatten();
+            let dominator = supports.fold(first, |mut a, mut b| {
+                while a != b {
+                    if depth(&depths, a) >= depth(&depths, b) {
+                        a = a.and_then(|a| dominators[a]);
+                    } else {
+                        b = b.and_then(|b| dominators[b]);
+                    }
+                }
+                a
+            });
+            dominators[index] = dominator;
+            depths[index] = depth(&depths, dominator) + 1;
+        }
+
+        dominators
+    }
+
+    /// How many other bricks fall if each brick is taken out, which is how many bricks each one
+    /// dominates. Counting those up the dominator tree only looks at each brick once, besides
+    /// finding the dominators.
+    pub fn falling_counts(&self) -> Vec<usize> {
+        let dominators = self.dominators();
+        // Every brick dominates itself, which isn't counted as falling
+        let mut subtree_sizes = vec![1; self.bricks.len()];
+        for index in (0..self.bricks.len()).rev() {
+            if let Some(dominator) = dominators[index] {
+                subtree_sizes[dominator] += subtree_sizes[index];
+            }
+        }
+        subtree_sizes.iter().map(|size| size - 1).collect()
+    }
+
+    /// Adds up [SettledBricks::falling_counts] over every brick
+    pub fn total_falling(&self) -> usize {
+        self.falling_counts().iter().sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "1,0,1~1,2,1\n\
+                              0,0,2~2,0,2\n\
+                              0,2,3~2,2,3\n\
+                              0,0,4~0,2,4\n\
+                              2,0,5~2,2,5\n\
+                              0,1,6~2,1,6\n\
+                              1,1,8~1,1,9\n";
+
+    fn parse_bricks(s: &str) -> Vec<Brick> {
+        s.lines().map(|line| line.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn parse_brick() {
+        let brick = "2,2,2~2,2,1".parse::<Brick>().unwrap();
+        assert_eq!(brick.0.min, Point3 { x: 2, y: 2, z: 1 });
+        assert_eq!(brick.0.height(), 2);
+
+        assert_eq!("1,0,1-1,2,1".parse::<Brick>(), Err(ParseBrickError));
+        assert_eq!("1,0,0~1,2,0".parse::<Brick>(), Err(ParseBrickError));
+        assert_eq!("1,0~1,2,1".parse::<Brick>(), Err(ParseBrickError));
+    }
+
+    #[test]
+    fn settle_bricks() {
+        let settled = SettledBricks::settle(parse_bricks(TEST_INPUT));
+        let bottoms = settled
+            .bricks
+            .iter()
+            .map(|brick| brick.0.min.z)
+            .collect::<Vec<_>>();
+        assert_eq!(bottoms, vec![1, 2, 2, 3, 3, 4, 5]);
+
+        // A is under B and C, which are both under D and E, which are both under F, under G
+        assert_eq!(settled.supports[0], vec![1, 2]);
+        assert_eq!(settled.supported_by[3], vec![1, 2]);
+        assert_eq!(settled.supported_by[5], vec![3, 4]);
+        assert_eq!(settled.supports[5], vec![6]);
+        assert!(settled.supports[6].is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let brick = "1,0,1~1,2,1".parse::<Brick>().unwrap();
+        let json = serde_json::to_value(brick).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"min": {"x": 1, "y": 0, "z": 1}, "max": {"x": 1, "y": 2, "z": 1}})
+        );
+        assert_eq!(serde_json::from_value::<Brick>(json).unwrap(), brick);
+
+        let settled = SettledBricks::settle(parse_bricks(TEST_INPUT));
+        let json = serde_json::to_value(&settled).unwrap();
+        assert_eq!(json["supports"][0], serde_json::json!([1, 2]));
+        assert_eq!(
+            serde_json::from_value::<SettledBricks>(json.clone()).unwrap(),
+            settled
+        );
+
+        let mut broken = json.clone();
+        broken["supports"][6] = serde_json::json!([7]);
+        assert!(serde_json::from_value::<SettledBricks>(broken).is_err());
+        let mut broken = json.clone();
+        broken["supported_by"][1] = serde_json::json!([0, 2]);
+        assert!(serde_json::from_value::<SettledBricks>(broken).is_err());
+        let mut broken = json;
+        broken["bricks"][0]["min"]["z"] = serde_json::json!(0);
+        assert!(serde_json::from_value::<SettledBricks>(broken).is_err());
+    }
+
+    #[test]
+    fn safe_bricks() {
+        let settled = SettledBricks::settle(parse_bricks(TEST_INPUT));
+        let safe = (0..settled.bricks.len())
+            .filter(|&index| settled.is_safe_to_disintegrate(index))
+            .collect::<Vec<_>>();
+        assert_eq!(safe, vec![1, 2, 3, 4, 6]);
+        assert_eq!(settled.safe_to_disintegrate(), 5);
+    }
+
+    #[test]
+    fn chain_reactions() {
+        let settled = SettledBricks::settle(parse_bricks(TEST_INPUT));
+        let falling = (0..settled.bricks.len())
+            .map(|index| settled.falling_bricks_naive(index))
+            .collect::<Vec<_>>();
+        assert_eq!(falling, vec![6, 0, 0, 0, 0, 1, 0]);
+        assert_eq!(settled.total_falling_naive(), 7);
+        assert_eq!(
+            settled.dominators(),
+            vec![None, Some(0), Some(0), Some(0), Some(0), Some(0), Some(5)]
+        );
+        assert_eq!(settled.total_falling(), 7);
+    }
+
+    #[test]
+    fn dominators_match_naive() {
+        let mut rng = fastrand::Rng::with_seed(0x9E3779B97F4A7C15);
+
+        for _ in 0..50 {
+            // Small footprints packed into a narrow tower, so the bricks pile up on each other
+            let bricks = (0..1 + rng.u32(..60))
+                .map(|_| {
+                    let (x, y, z) = (rng.u32(..4), rng.u32(..4), 1 + rng.u32(..100));
+                    let (dx, dy, dz) = match rng.u32(..3) {
+                        0 => (rng.u32(..3), 0, 0),
+                        1 => (0, rng.u32(..3), 0),
+                        _ => (0, 0, rng.u32(..3)),
+                    };
+                    format!("{x},{y},{z}~{},{},{}", x + dx, y + dy, z + dz)
+                        .parse::<Brick>()
+                        .unwrap()
+                })
+                .collect();
+            let settled = SettledBricks::settle(bricks);
+            assert_eq!(settled.total_falling(), settled.total_falling_naive());
+        }
+    }
+
+    #[test]
+    fn bricks_only_touching_at_the_side() {
+        // The second brick lands next to the first instead of on it, so neither holds up anything
+        let settled = SettledBricks::settle(parse_bricks("0,0,1~0,0,3\n1,0,5~1,0,5"));
+        assert_eq!(settled.bricks[1].0.min.z, 1);
+        assert!(settled.supported_by[1].is_empty());
+        assert_eq!(settled.safe_to_disintegrate(), 2);
+    }
+}
@@ -0,0 +1,35 @@
+use day_22::{export, Brick, SettledBricks};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::{env, fs, process};
+
+fn main() {
+    let bricks = fs::read_to_string("input.txt")
+        .expect("failed to open input file")
+        .lines()
+        .map(|line| line.parse::<Brick>())
+        .collect::<Result<Vec<_>, _>>()
+        .expect("failed to parse bricks");
+    let settled = SettledBricks::settle(bricks);
+
+    println!("Part 1 result: {}", settled.safe_to_disintegrate());
+    println!("Part 2 result: {}", settled.total_falling());
+
+    let args: Vec<String> = env::args().collect();
+    if let Some(i) = args.iter().position(|arg| arg == "--export") {
+        let Some(path) = args.get(i + 1) else {
+            eprintln!("--export needs an output path");
+            process::exit(1);
+        };
+        let written = File::create(path).and_then(|file| {
+            let mut out = BufWriter::new(file);
+            export::write_obj(&settled, &mut out)?;
+            out.flush()
+        });
+        if let Err(err) = written {
+            eprintln!("failed to export the bricks: {err}");
+            process::exit(1);
+        }
+        println!("Wrote the settled bricks to {path}");
+    }
+}
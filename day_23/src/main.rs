@@ -0,0 +1,489 @@
+#[cfg(feature = "render")]
+mod render;
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::{env, fs, process};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Tile {
+    Path,
+    Forest,
+    /// Icy, so the next step from here has to be downhill in this direction
+    Slope(Direction),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ParseTileError;
+
+impl TryFrom<char> for Tile {
+    type Error = ParseTileError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '.' => Ok(Self::Path),
+            '#' => Ok(Self::Forest),
+            '^' => Ok(Self::Slope(Direction::Up)),
+            'v' => Ok(Self::Slope(Direction::Down)),
+            '<' => Ok(Self::Slope(Direction::Left)),
+            '>' => Ok(Self::Slope(Direction::Right)),
+            _ => Err(ParseTileError),
+        }
+    }
+}
+
+/// The map of the trails, stored row by row in one flat list. The hike starts at the only path
+/// tile in the top row and ends at the only one in the bottom row.
+#[derive(Debug, Eq, PartialEq)]
+struct TrailMap {
+    width: usize,
+    height: usize,
+    tiles: Vec<Tile>,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ParseTrailMapError;
+
+impl FromStr for TrailMap {
+    type Err = ParseTrailMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let width = s.lines().next().map_or(0, str::len);
+        let mut tiles = vec![];
+        let mut height = 0;
+        for line in s.lines() {
+            if line.len() != width {
+                return Err(ParseTrailMapError);
+            }
+            for c in line.chars() {
+                tiles.push(Tile::try_from(c).or(Err(ParseTrailMapError))?);
+            }
+            height += 1;
+        }
+
+        // The only path tile in a row, or `None` if there isn't exactly one
+        let only_path = |row: usize| {
+            let mut paths = (row * width..(row + 1) * width).filter(|&i| tiles[i] == Tile::Path);
+            match (paths.next(), paths.next()) {
+                (Some(i), None) => Some(i),
+                _ => None,
+            }
+        };
+        if height < 2 {
+            return Err(ParseTrailMapError);
+        }
+        let start = only_path(0).ok_or(ParseTrailMapError)?;
+        let end = only_path(height - 1).ok_or(ParseTrailMapError)?;
+
+        Ok(Self {
+            width,
+            height,
+            tiles,
+            start,
+            end,
+        })
+    }
+}
+
+impl TrailMap {
+    /// The tile one step from the tile at index `pos` towards `direction`, unless that's past the
+    /// edge of the map or into the forest
+    fn step(&self, pos: usize, direction: Direction) -> Option<usize> {
+        let (row, col) = (pos / self.width, pos % self.width);
+        let next = match direction {
+            Direction::Up => pos.checked_sub(self.width)?,
+            Direction::Down if row + 1 < self.height => pos + self.width,
+            Direction::Left if col > 0 => pos - 1,
+            Direction::Right if col + 1 < self.width => pos + 1,
+            _ => return None,
+        };
+        (self.tiles[next] != Tile::Forest).then_some(next)
+    }
+
+    /// The tiles a hiker can step to from `pos`. Slopes only let them go downhill when `icy`, and
+    /// are walked like any other path otherwise.
+    fn moves(&self, pos: usize, icy: bool) -> impl Iterator<Item = usize> + '_ {
+        let directions: &[Direction] = match &self.tiles[pos] {
+            Tile::Slope(direction) if icy => std::slice::from_ref(direction),
+            _ => &DIRECTIONS,
+        };
+        directions
+            .iter()
+            .filter_map(move |&direction| self.step(pos, direction))
+    }
+
+    /// The most steps a hike from the start to the end can take without stepping on any tile
+    /// twice, or `None` if the end can't be reached. Slopes are only one way when `icy`.
+    ///
+    /// Every possible hike is tried with a depth-first search. The search keeps its own stack of
+    /// the tiles on the current hike and the moves still to try from each, since a hike can be
+    /// thousands of tiles long. With the slopes dry there are far too many hikes for this on the
+    /// real map, which is what [TrailMap::junction_graph] is for.
+    fn longest_hike(&self, icy: bool) -> Option<usize> {
+        let mut on_hike = vec![false; self.tiles.len()];
+        on_hike[self.start] = true;
+        let mut hike = vec![(self.start, self.moves(self.start, icy).collect::<Vec<_>>())];
+        let mut longest = None;
+
+        loop {
+            let depth = hike.len();
+            let Some((pos, untried)) = hike.last_mut() else {
+                break;
+            };
+            let pos = *pos;
+            let next = if pos == self.end {
+                longest = longest.max(Some(depth - 1));
+                None
+            } else {
+                untried.pop()
+            };
+
+            match next {
+                Some(next) if !on_hike[next] => {
+                    on_hike[next] = true;
+                    hike.push((next, self.moves(next, icy).collect()));
+                }
+                Some(_) => {}
+                None => {
+                    on_hike[pos] = false;
+                    hike.pop();
+                }
+            }
+        }
+
+        longest
+    }
+
+    /// Squeezes the map down to its junctions, ignoring slopes. The start, the end and every tile
+    /// with more than two ways out are junctions, and each corridor between two of them becomes
+    /// an edge as long as the corridor. Corridors that come to a dead end are left out.
+    fn junction_graph(&self) -> Result<JunctionGraph, TooManyJunctions> {
+        let junction_tiles = (0..self.tiles.len())
+            .filter(|&pos| {
+                pos == self.start
+                    || pos == self.end
+                    || (self.tiles[pos] != Tile::Forest && self.moves(pos, false).count() > 2)
+            })
+            .collect::<Vec<_>>();
+        if junction_tiles.len() > MAX_JUNCTIONS {
+            return Err(TooManyJunctions(junction_tiles.len()));
+        }
+        let junction_at = |pos: usize| junction_tiles.binary_search(&pos).ok();
+
+        let mut edges = vec![vec![]; junction_tiles.len()];
+        let mut corridors = vec![vec![]; junction_tiles.len()];
+        for (from, &junction) in junction_tiles.iter().enumerate() {
+            for first in self.moves(junction, false) {
+                let (mut prev, mut pos) = (junction, first);
+                let mut corridor = vec![];
+                let to = loop {
+                    if let Some(to) = junction_at(pos) {
+                        break Some(to);
+                    }
+                    corridor.push(pos);
+                    let Some(next) = self.moves(pos, false).find(|&next| next != prev) else {
+                        break None;
+                    };
+                    (prev, pos) = (pos, next);
+                };
+                if let Some(to) = to {
+                    edges[from].push((to, corridor.len() + 1));
+                    corridors[from].push(corridor);
+                }
+            }
+        }
+
+        Ok(JunctionGraph {
+            start: junction_at(self.start).unwrap(),
+            end: junction_at(self.end).unwrap(),
+            edges,
+            corridors,
+            tiles: junction_tiles,
+        })
+    }
+}
+
+/// The most junctions a [JunctionGraph] can have, so a set of them fits in a `u64`
+const MAX_JUNCTIONS: usize = u64::BITS as usize;
+
+#[derive(Debug, Eq, PartialEq)]
+struct TooManyJunctions(usize);
+
+impl Display for TooManyJunctions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the map has {} junctions, more than the {MAX_JUNCTIONS} that can be searched",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for TooManyJunctions {}
+
+/// The trail map with every corridor squeezed into a single edge between the junctions at its
+/// ends. Junctions are referred to by index.
+#[derive(Debug, Eq, PartialEq)]
+struct JunctionGraph {
+    /// For each junction, the junctions a corridor leads to from it and how long the corridor is
+    edges: Vec<Vec<(usize, usize)>>,
+    /// The tiles along each corridor in `edges`, in the order they're walked, leaving out the
+    /// junctions at either end
+    corridors: Vec<Vec<Vec<usize>>>,
+    /// The tile each junction is at
+    tiles: Vec<usize>,
+    start: usize,
+    end: usize,
+}
+
+impl JunctionGraph {
+    /// The longest hike from the start to the end that doesn't visit a junction twice, or `None`
+    /// if the end can't be reached
+    fn longest_hike(&self) -> Option<usize> {
+        self.longest_route().map(|(length, _)| length)
+    }
+
+    /// Same as [JunctionGraph::longest_hike], along with the junctions the hike goes through in
+    /// order. Every hike is tried, with the junctions on the current hike kept as bits of a `u64`.
+    fn longest_route(&self) -> Option<(usize, Vec<usize>)> {
+        let mut route = vec![self.start];
+        let mut longest = None;
+        self.search_routes(self.start, 1 << self.start, 0, &mut route, &mut longest);
+        longest
+    }
+
+    fn search_routes(
+        &self,
+        junction: usize,
+        visited: u64,
+        length: usize,
+        route: &mut Vec<usize>,
+        longest: &mut Option<(usize, Vec<usize>)>,
+    ) {
+        if junction == self.end {
+            if longest.as_ref().is_none_or(|&(best, _)| length > best) {
+                *longest = Some((length, route.clone()));
+            }
+            return;
+        }
+        for &(next, corridor) in &self.edges[junction] {
+            if visited & (1 << next) == 0 {
+                route.push(next);
+                self.search_routes(
+                    next,
+                    visited | (1 << next),
+                    length + corridor,
+                    route,
+                    longest,
+                );
+                route.pop();
+            }
+        }
+    }
+
+    /// Every tile a hike through the junctions in `route` steps on, taking the longest corridor
+    /// wherever two junctions have more than one between them
+    #[cfg_attr(not(feature = "render"), allow(dead_code))]
+    fn route_tiles(&self, route: &[usize]) -> Vec<usize> {
+        let mut tiles = route
+            .first()
+            .map(|&start| vec![self.tiles[start]])
+            .unwrap_or_default();
+        for pair in route.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let corridor = self.edges[from]
+                .iter()
+                .zip(&self.corridors[from])
+                .filter(|((next, _), _)| *next == to)
+                .max_by_key(|((_, length), _)| *length)
+                .map(|(_, corridor)| corridor);
+            if let Some(corridor) = corridor {
+                tiles.extend(corridor);
+                tiles.push(self.tiles[to]);
+            }
+        }
+        tiles
+    }
+}
+
+fn main() {
+    let trail_map = fs::read_to_string("input.txt")
+        .expect("failed to open input file")
+        .parse::<TrailMap>()
+        .expect("failed to parse trail map");
+
+    match trail_map.longest_hike(true) {
+        Some(steps) => println!("Part 1 result: {steps}"),
+        None => println!("Part 1 result not found: there's no way to the end"),
+    }
+
+    let graph = trail_map.junction_graph();
+    match graph.as_ref().map(|graph| graph.longest_hike()) {
+        Ok(Some(steps)) => println!("Part 2 result: {steps}"),
+        Ok(None) => println!("Part 2 result not found: there's no way to the end"),
+        Err(err) => println!("Part 2 result not found: {err}"),
+    }
+
+    let args: Vec<String> = env::args().collect();
+    if let Some(i) = args.iter().position(|arg| arg == "--render") {
+        let Some(path) = args.get(i + 1) else {
+            eprintln!("--render needs an output path");
+            process::exit(1);
+        };
+        match &graph {
+            Ok(graph) => render_hike(&trail_map, graph, path),
+            Err(err) => println!("No hike to render: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "render")]
+fn render_hike(trail_map: &TrailMap, graph: &JunctionGraph, path: &str) {
+    let Some((_, route)) = graph.longest_route() else {
+        println!("No hike to render: there's no way to the end");
+        return;
+    };
+    let hike = graph.route_tiles(&route);
+    if let Err(err) = render::render_hike(trail_map, graph, &hike, std::path::Path::new(path)) {
+        eprintln!("failed to render the hike: {err}");
+        process::exit(1);
+    }
+    println!("Wrote the longest dry hike to {path}");
+    let junctions = route
+        .iter()
+        .map(|junction| junction.to_string())
+        .collect::<Vec<_>>();
+    println!("Junctions along it: {}", junctions.join(" -> "));
+}
+
+#[cfg(not(feature = "render"))]
+fn render_hike(_: &TrailMap, _: &JunctionGraph, _: &str) {
+    eprintln!("--render needs day_23 to be built with the `render` feature");
+    process::exit(1);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "#.#####################\n\
+                              #.......#########...###\n\
+                              #######.#########.#.###\n\
+                              ###.....#.>.>.###.#.###\n\
+                              ###v#####.#v#.###.#.###\n\
+                              ###.>...#.#.#.....#...#\n\
+                              ###v###.#.#.#########.#\n\
+                              ###...#.#.#.......#...#\n\
+                              #####.#.#.#######.#.###\n\
+                              #.....#.#.#.......#...#\n\
+                              #.#####.#.#.#########v#\n\
+                              #.#...#...#...###...>.#\n\
+                              #.#.#v#######v###.###v#\n\
+                              #...#.>.#...>.>.#.###.#\n\
+                              #####v#.#.###v#.#.###.#\n\
+                              #.....#...#...#.#.#...#\n\
+                              #.#########.###.#.#.###\n\
+                              #...###...#...#...#.###\n\
+                              ###.###.#.###v#####v###\n\
+                              #...#...#.#.>.>.#.>.###\n\
+                              #.###.###.#.###.#.#v###\n\
+                              #.....###...###...#...#\n\
+                              #####################.#\n";
+
+    #[test]
+    fn parse_trail_map() {
+        let trail_map = "#.#\n#v#\n#.#".parse::<TrailMap>().unwrap();
+        assert_eq!((trail_map.start, trail_map.end), (1, 7));
+        assert_eq!(trail_map.tiles[4], Tile::Slope(Direction::Down));
+
+        assert_eq!("#.#\n#x#\n#.#".parse::<TrailMap>(), Err(ParseTrailMapError));
+        assert_eq!("...\n#.#".parse::<TrailMap>(), Err(ParseTrailMapError));
+        assert_eq!("#.#".parse::<TrailMap>(), Err(ParseTrailMapError));
+    }
+
+    #[test]
+    fn longest_hike() {
+        let trail_map = TEST_INPUT.parse::<TrailMap>().unwrap();
+        assert_eq!(trail_map.longest_hike(true), Some(94));
+    }
+
+    #[test]
+    fn junction_graph() {
+        let trail_map = TEST_INPUT.parse::<TrailMap>().unwrap();
+        let graph = trail_map.junction_graph().unwrap();
+        // The start, the end and seven crossroads
+        assert_eq!(graph.edges.len(), 9);
+        assert_eq!(graph.edges[graph.start].len(), 1);
+        assert_eq!(graph.edges[graph.start][0].1, 15);
+        // Every corridor can be walked both ways
+        for (from, edges) in graph.edges.iter().enumerate() {
+            for &(to, length) in edges {
+                assert!(graph.edges[to].contains(&(from, length)));
+            }
+        }
+
+        // A dead end off a corridor makes a junction but no edge
+        let trail_map = "#.###\n#...#\n#.###".parse::<TrailMap>().unwrap();
+        let graph = trail_map.junction_graph().unwrap();
+        assert_eq!(graph.edges.len(), 3);
+        assert_eq!(graph.edges[1], vec![(0, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn longest_dry_hike() {
+        let trail_map = TEST_INPUT.parse::<TrailMap>().unwrap();
+        let graph = trail_map.junction_graph().unwrap();
+        assert_eq!(graph.longest_hike(), Some(154));
+        assert_eq!(trail_map.longest_hike(false), Some(154));
+
+        // The route steps from tile to tile without going back on itself
+        let (_, route) = graph.longest_route().unwrap();
+        assert_eq!(route.first(), Some(&graph.start));
+        assert_eq!(route.last(), Some(&graph.end));
+        let tiles = graph.route_tiles(&route);
+        assert_eq!(tiles.len(), 154 + 1);
+        assert_eq!((tiles[0], tiles[154]), (trail_map.start, trail_map.end));
+        for step in tiles.windows(2) {
+            assert!(trail_map.moves(step[0], false).any(|next| next == step[1]));
+        }
+        let mut unique = tiles.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), tiles.len());
+
+        let trail_map = "#.#\n#^#\n#.#".parse::<TrailMap>().unwrap();
+        assert_eq!(trail_map.junction_graph().unwrap().longest_hike(), Some(2));
+    }
+
+    #[test]
+    fn slopes_are_one_way() {
+        // The slope points back up at the start, so the end can't be reached
+        let trail_map = "#.#\n#^#\n#.#".parse::<TrailMap>().unwrap();
+        assert_eq!(trail_map.longest_hike(true), None);
+
+        // Going round the loop would be longer, but coming into the slope from the right it
+        // sends hikers straight back, and from the left it's a dead end
+        let trail_map = "#.###\n#...#\n#.#.#\n#.>.#\n#.###"
+            .parse::<TrailMap>()
+            .unwrap();
+        assert_eq!(trail_map.longest_hike(true), Some(4));
+        assert_eq!(trail_map.longest_hike(false), Some(8));
+    }
+}
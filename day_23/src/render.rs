@@ -0,0 +1,145 @@
+use crate::{JunctionGraph, Tile, TrailMap};
+use aoc_viz::{Color, Frame, Palette, Png, RenderError, Renderer};
+use std::path::Path;
+
+/// How many cells across each tile is drawn, enough to fit a two digit junction label inside it
+const TILE_CELLS: usize = 8;
+
+const FOREST: u8 = 0;
+const TRAIL: u8 = 1;
+const HIKE: u8 = 2;
+const JUNCTION: u8 = 3;
+const JUNCTION_ON_HIKE: u8 = 4;
+const LABEL: u8 = 5;
+
+/// Three by five cell glyphs for the digits, one row of three bits per row of the glyph
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b011, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+fn palette() -> Palette {
+    Palette::new([
+        Color::from(0x1E3A1E),
+        Color::from(0xC8B48C),
+        Color::from(0xE0243B),
+        Color::from(0x2A5DB0),
+        Color::from(0x7A1FA2),
+        Color::WHITE,
+    ])
+}
+
+/// Draws `trail_map` with the tiles of `hike` picked out and every junction of `graph` labeled
+/// with its index, and saves it to `out`
+pub(crate) fn render_hike(
+    trail_map: &TrailMap,
+    graph: &JunctionGraph,
+    hike: &[usize],
+    out: &Path,
+) -> Result<(), RenderError> {
+    Png::single(out, palette()).render(&hike_frame(trail_map, graph, hike))
+}
+
+fn hike_frame(trail_map: &TrailMap, graph: &JunctionGraph, hike: &[usize]) -> Frame {
+    let mut on_hike = vec![false; trail_map.tiles.len()];
+    for &pos in hike {
+        on_hike[pos] = true;
+    }
+    let mut colors = trail_map
+        .tiles
+        .iter()
+        .zip(&on_hike)
+        .map(|(tile, &on_hike)| match (tile, on_hike) {
+            (Tile::Forest, _) => FOREST,
+            (_, true) => HIKE,
+            (_, false) => TRAIL,
+        })
+        .collect::<Vec<_>>();
+    for &pos in &graph.tiles {
+        colors[pos] = if on_hike[pos] {
+            JUNCTION_ON_HIKE
+        } else {
+            JUNCTION
+        };
+    }
+
+    let mut frame = Frame::from_fn(
+        trail_map.width * TILE_CELLS,
+        trail_map.height * TILE_CELLS,
+        |(row, col)| colors[row / TILE_CELLS * trail_map.width + col / TILE_CELLS],
+    );
+    for (junction, &pos) in graph.tiles.iter().enumerate() {
+        let (row, col) = (pos / trail_map.width, pos % trail_map.width);
+        draw_label(&mut frame, (row * TILE_CELLS, col * TILE_CELLS), junction);
+    }
+    frame
+}
+
+/// Writes `number` centered in the tile whose top left cell is at `top` and `left`. Only the last two digits
+/// fit, which covers every junction a `u64` of visited junctions can hold.
+fn draw_label(frame: &mut Frame, (top, left): (usize, usize), number: usize) {
+    let digits = if number < 10 {
+        vec![number]
+    } else {
+        vec![number / 10 % 10, number % 10]
+    };
+    let width = digits.len() * 4 - 1;
+    let (top, left) = (top + (TILE_CELLS - 5) / 2, left + (TILE_CELLS - width) / 2);
+    for (i, &digit) in digits.iter().enumerate() {
+        for (row, bits) in DIGITS[digit].iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) != 0 {
+                    frame.set((top + row, left + i * 4 + col), LABEL);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn labeled_junctions() {
+        let trail_map = "#.###\n#...#\n#.#.#\n#...#\n###.#"
+            .parse::<TrailMap>()
+            .unwrap();
+        let graph = trail_map.junction_graph().unwrap();
+        let (_, route) = graph.longest_route().unwrap();
+        let hike = graph.route_tiles(&route);
+        let frame = hike_frame(&trail_map, &graph, &hike);
+        assert_eq!(frame.width(), 5 * TILE_CELLS);
+        assert_eq!(frame.height(), 5 * TILE_CELLS);
+
+        // The corner of each tile is never covered by a label
+        let corner = |pos: usize| {
+            let (row, col) = (pos / trail_map.width, pos % trail_map.width);
+            frame.get((row * TILE_CELLS, col * TILE_CELLS))
+        };
+        assert_eq!(corner(0), Some(FOREST));
+        assert_eq!(corner(trail_map.start), Some(JUNCTION_ON_HIKE));
+        assert_eq!(corner(trail_map.end), Some(JUNCTION_ON_HIKE));
+
+        // One side of the loop is left off the hike
+        let off_hike = (0..trail_map.tiles.len())
+            .filter(|&pos| trail_map.tiles[pos] != Tile::Forest && !hike.contains(&pos))
+            .collect::<Vec<_>>();
+        assert_eq!(off_hike.len(), 3);
+        assert!(off_hike.iter().all(|&pos| corner(pos) == Some(TRAIL)));
+
+        // The start is junction 0, drawn with a gap down the middle of the 0
+        let (top, left) = (1, TILE_CELLS + 2);
+        assert_eq!(frame.get((top, left)), Some(LABEL));
+        assert_eq!(frame.get((top + 2, left + 1)), Some(JUNCTION_ON_HIKE));
+        assert_eq!(frame.get((top + 2, left + 2)), Some(LABEL));
+    }
+}
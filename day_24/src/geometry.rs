@@ -0,0 +1,93 @@
+use std::ops::RangeInclusive;
+
+/// A path on the X and Y axes, starting at `position` and moving by `velocity` every nanosecond
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Ray2 {
+    pub position: [i128; 2],
+    pub velocity: [i128; 2],
+}
+
+/// Where two paths cross, kept exactly as `x / denominator` and `y / denominator`. The
+/// denominator is always positive.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Crossing {
+    pub x: i128,
+    pub y: i128,
+    pub denominator: i128,
+}
+
+impl Ray2 {
+    /// Where the paths of `self` and `other` cross at a point neither has already passed, or
+    /// `None` if they don't. Parallel paths never cross, even if they're the same line.
+    pub fn crossing(&self, other: &Ray2) -> Option<Crossing> {
+        let [px, py] = self.position;
+        let [vx, vy] = self.velocity;
+        let [qx, qy] = other.position;
+        let [ux, uy] = other.velocity;
+
+        // Solving p + t * v = q + s * u for the times t and s, both over `denominator`
+        let mut denominator = vx * uy - vy * ux;
+        if denominator == 0 {
+            return None;
+        }
+        let (dx, dy) = (qx - px, qy - py);
+        let mut t = dx * uy - dy * ux;
+        let mut s = dx * vy - dy * vx;
+        if denominator < 0 {
+            (denominator, t, s) = (-denominator, -t, -s);
+        }
+        if t < 0 || s < 0 {
+            return None;
+        }
+
+        // The crossing is at p + v * t / denominator
+        Some(Crossing {
+            x: px * denominator + vx * t,
+            y: py * denominator + vy * t,
+            denominator,
+        })
+    }
+
+    /// The span of time the path spends inside `area` on both axes, from when it's first inside
+    /// to when it last is, or `None` if it never goes in. Only times from 0 on count.
+    pub fn times_within(&self, area: &RangeInclusive<i64>) -> Option<(f64, f64)> {
+        let (min, max) = (*area.start() as f64, *area.end() as f64);
+        let (mut from, mut to) = (0.0, f64::INFINITY);
+        for axis in 0..2 {
+            let (p, v) = (self.position[axis] as f64, self.velocity[axis] as f64);
+            if v == 0.0 {
+                if p < min || p > max {
+                    return None;
+                }
+                continue;
+            }
+            let (a, b) = ((min - p) / v, (max - p) / v);
+            from = f64::max(from, a.min(b));
+            to = f64::min(to, a.max(b));
+        }
+        (from <= to).then_some((from, to))
+    }
+
+    /// Where the path is after `time` nanoseconds
+    pub fn at(&self, time: f64) -> (f64, f64) {
+        let [px, py] = self.position.map(|p| p as f64);
+        let [vx, vy] = self.velocity.map(|v| v as f64);
+        (px + vx * time, py + vy * time)
+    }
+}
+
+impl Crossing {
+    /// Whether the crossing is inside `area` on both axes, compared by multiplying the area
+    /// through by the denominator so there's no rounding
+    pub fn is_within(&self, area: &RangeInclusive<i64>) -> bool {
+        let min = i128::from(*area.start()) * self.denominator;
+        let max = i128::from(*area.end()) * self.denominator;
+        (min..=max).contains(&self.x) && (min..=max).contains(&self.y)
+    }
+
+    /// The crossing as floats, close enough to plot
+    pub fn to_f64(self) -> (f64, f64) {
+        let denominator = self.denominator as f64;
+        (self.x as f64 / denominator, self.y as f64 / denominator)
+    }
+}
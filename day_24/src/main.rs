@@ -0,0 +1,357 @@
+mod geometry;
+mod plot;
+
+use geometry::Ray2;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::{env, process};
+
+/// Where part 1 looks for the hailstones' paths crossing, on both the X and Y axes
+const TEST_AREA: RangeInclusive<i64> = 200_000_000_000_000..=400_000_000_000_000;
+
+/// The fastest the rock is tried being thrown along the X and Y axes, either way. Hailstones in
+/// the puzzle input move a few hundred units a nanosecond at most, and so does the rock.
+const MAX_ROCK_SPEED: i64 = 1000;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+struct Hailstone {
+    /// `[x, y, z]` at time 0
+    position: [i64; 3],
+    /// How far it moves along each axis every nanosecond
+    velocity: [i64; 3],
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ParseHailstoneError;
+
+/// Parses three numbers like `19, 13, 30`, ignoring extra spaces
+fn parse_triple(s: &str) -> Result<[i64; 3], ParseHailstoneError> {
+    let mut values = s.split(',').map(|value| value.trim().parse::<i64>());
+    match (values.next(), values.next(), values.next(), values.next()) {
+        (Some(Ok(x)), Some(Ok(y)), Some(Ok(z)), None) => Ok([x, y, z]),
+        _ => Err(ParseHailstoneError),
+    }
+}
+
+impl FromStr for Hailstone {
+    type Err = ParseHailstoneError;
+
+    /// Parses a hailstone like `19, 13, 30 @ -2,  1, -2`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (position, velocity) = s.split_once('@').ok_or(ParseHailstoneError)?;
+        Ok(Self {
+            position: parse_triple(position)?,
+            velocity: parse_triple(velocity)?,
+        })
+    }
+}
+
+impl Hailstone {
+    /// The hailstone's path on the X and Y axes
+    fn path(&self) -> Ray2 {
+        let [x, y, _] = self.position.map(i128::from);
+        let [vx, vy, _] = self.velocity.map(i128::from);
+        Ray2 {
+            position: [x, y],
+            velocity: [vx, vy],
+        }
+    }
+
+    /// Whether the paths of `self` and `other` cross inside `area` on the X and Y axes, at a
+    /// point neither hailstone has already passed. Only where the paths go matters, not whether
+    /// the hailstones are there at the same time.
+    ///
+    /// Everything is worked out exactly with `i128`s, so there's no rounding to worry about.
+    /// Parallel paths never count, even if they're the same line.
+    fn paths_cross_within(&self, other: &Hailstone, area: &RangeInclusive<i64>) -> bool {
+        self.path()
+            .crossing(&other.path())
+            .is_some_and(|crossing| crossing.is_within(area))
+    }
+}
+
+/// Divides `a` by `b` if it comes out whole
+fn exact_div(a: i128, b: i128) -> Option<i128> {
+    (b != 0 && a % b == 0).then(|| a / b)
+}
+
+/// A throw that hits every hailstone, given the same way as a hailstone
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+struct Rock {
+    position: [i128; 3],
+    velocity: [i128; 3],
+}
+
+impl Rock {
+    /// The rock's path on the X and Y axes
+    fn path(&self) -> Ray2 {
+        Ray2 {
+            position: [self.position[0], self.position[1]],
+            velocity: [self.velocity[0], self.velocity[1]],
+        }
+    }
+}
+
+/// Finds the throw that hits every hailstone at a whole number of nanoseconds, trying every
+/// rock velocity along X and Y up to `max_speed` either way.
+///
+/// Seen from the rock, each hailstone moves at its own velocity minus the rock's, and every one
+/// of them has to pass through the rock's starting point. So for each velocity tried, the
+/// starting X and Y are where the first two of those paths that aren't parallel cross, exactly
+/// like in part 1, and the rest are checked against it. The times each hailstone gets there then
+/// pin down the Z velocity and starting Z. Everything stays in whole `i128`s.
+fn find_rock(hailstones: &[Hailstone], max_speed: i64) -> Option<Rock> {
+    let stones = hailstones
+        .iter()
+        .map(|hailstone| {
+            (
+                hailstone.position.map(i128::from),
+                hailstone.velocity.map(i128::from),
+            )
+        })
+        .collect::<Vec<_>>();
+    let (first, rest) = stones.split_first()?;
+    let max_speed = i128::from(max_speed);
+
+    for rock_vx in -max_speed..=max_speed {
+        for rock_vy in -max_speed..=max_speed {
+            let relative = |(_, v): &([i128; 3], [i128; 3])| (v[0] - rock_vx, v[1] - rock_vy);
+
+            // The rock's X and Y, from the first hailstone whose path isn't parallel to the first
+            let (p, (vx, vy)) = (first.0, relative(first));
+            let start = rest.iter().find_map(|other| {
+                let (q, (ux, uy)) = (other.0, relative(other));
+                let denominator = vx * uy - vy * ux;
+                if denominator == 0 {
+                    return None;
+                }
+                let (dx, dy) = (q[0] - p[0], q[1] - p[1]);
+                Some(
+                    exact_div(dx * uy - dy * ux, denominator)
+                        .map(|t| (p[0] + vx * t, p[1] + vy * t)),
+                )
+            });
+            let Some(Some((x, y))) = start else {
+                continue;
+            };
+
+            // When each hailstone reaches the rock's X and Y, or `None` if it never does at a
+            // whole time. Hailstones sitting still there could be hit at any time, so are left
+            // out of the times.
+            let mut times = vec![];
+            let all_hit = stones.iter().all(|stone| {
+                let (q, (ux, uy)) = (stone.0, relative(stone));
+                let t = match (ux, uy) {
+                    (0, 0) => return (q[0], q[1]) == (x, y),
+                    (0, _) => (q[0] == x).then(|| exact_div(y - q[1], uy)).flatten(),
+                    _ => exact_div(x - q[0], ux).filter(|&t| q[1] + uy * t == y),
+                };
+                match t {
+                    Some(t) if t >= 0 => {
+                        times.push((t, q[2], stone.1[2]));
+                        true
+                    }
+                    _ => false,
+                }
+            });
+            if !all_hit {
+                continue;
+            }
+
+            // Two hits at different times give the Z velocity, and then the starting Z
+            let Some(&(t0, z0, vz0)) = times.first() else {
+                continue;
+            };
+            let Some(&(t1, z1, vz1)) = times.iter().find(|&&(t, _, _)| t != t0) else {
+                continue;
+            };
+            let Some(rock_vz) = exact_div(z0 + vz0 * t0 - z1 - vz1 * t1, t0 - t1) else {
+                continue;
+            };
+            let z = z0 + (vz0 - rock_vz) * t0;
+            if times
+                .iter()
+                .all(|&(t, stone_z, stone_vz)| stone_z + (stone_vz - rock_vz) * t == z)
+            {
+                return Some(Rock {
+                    position: [x, y, z],
+                    velocity: [rock_vx, rock_vy, rock_vz],
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Counts the pairs of hailstones whose paths cross inside `area`
+fn crossings_within(hailstones: &[Hailstone], area: &RangeInclusive<i64>) -> usize {
+    hailstones
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            hailstones[i + 1..]
+                .iter()
+                .filter(|b| a.paths_cross_within(b, area))
+                .count()
+        })
+        .sum()
+}
+
+fn main() {
+    let hailstones = fs::read_to_string("input.txt")
+        .expect("failed to open input file")
+        .lines()
+        .map(|line| line.parse::<Hailstone>())
+        .collect::<Result<Vec<_>, _>>()
+        .expect("failed to parse hailstones");
+
+    println!(
+        "Part 1 result: {}",
+        crossings_within(&hailstones, &TEST_AREA)
+    );
+
+    let rock = find_rock(&hailstones, MAX_ROCK_SPEED);
+    match rock {
+        Some(rock) => println!("Part 2 result: {}", rock.position.iter().sum::<i128>()),
+        None => println!("Part 2 result not found: no throw up to {MAX_ROCK_SPEED} hits them all"),
+    }
+
+    let args: Vec<String> = env::args().collect();
+    if let Some(i) = args.iter().position(|arg| arg == "--plot") {
+        let Some(path) = args.get(i + 1) else {
+            eprintln!("--plot needs an output path");
+            process::exit(1);
+        };
+        let written = File::create(path).and_then(|file| {
+            let mut out = BufWriter::new(file);
+            plot::write_svg(&hailstones, &TEST_AREA, rock.as_ref(), &mut out)?;
+            out.flush()
+        });
+        if let Err(err) = written {
+            eprintln!("failed to plot the hailstones: {err}");
+            process::exit(1);
+        }
+        println!("Wrote the hailstone paths to {path}");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "19, 13, 30 @ -2,  1, -2\n\
+                              18, 19, 22 @ -1, -1, -2\n\
+                              20, 25, 34 @ -2, -2, -4\n\
+                              12, 31, 28 @ -1, -2, -1\n\
+                              20, 19, 15 @  1, -5, -3\n";
+
+    const EXAMPLE_AREA: RangeInclusive<i64> = 7..=27;
+
+    fn parse_hailstones(s: &str) -> Vec<Hailstone> {
+        s.lines().map(|line| line.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn parse_hailstone() {
+        let hailstone = "19, 13, 30 @ -2,  1, -2".parse::<Hailstone>().unwrap();
+        assert_eq!(hailstone.position, [19, 13, 30]);
+        assert_eq!(hailstone.velocity, [-2, 1, -2]);
+
+        assert_eq!(
+            "19, 13 @ -2, 1".parse::<Hailstone>(),
+            Err(ParseHailstoneError)
+        );
+        assert_eq!(
+            "19, 13, 30 -2, 1, -2".parse::<Hailstone>(),
+            Err(ParseHailstoneError)
+        );
+    }
+
+    #[test]
+    fn path_crossings() {
+        let hailstones = parse_hailstones(TEST_INPUT);
+        let [a, b, c, d, e] = hailstones[..] else {
+            panic!("expected five hailstones");
+        };
+        // Inside the area
+        assert!(a.paths_cross_within(&b, &EXAMPLE_AREA));
+        assert!(a.paths_cross_within(&c, &EXAMPLE_AREA));
+        // Outside it
+        assert!(!a.paths_cross_within(&d, &EXAMPLE_AREA));
+        // In the past for the first hailstone
+        assert!(!a.paths_cross_within(&e, &EXAMPLE_AREA));
+        // Parallel
+        assert!(!b.paths_cross_within(&c, &EXAMPLE_AREA));
+        // In the past for both
+        assert!(!d.paths_cross_within(&e, &EXAMPLE_AREA));
+
+        assert_eq!(crossings_within(&hailstones, &EXAMPLE_AREA), 2);
+    }
+
+    #[test]
+    fn rock_throw() {
+        let hailstones = parse_hailstones(TEST_INPUT);
+        let rock = find_rock(&hailstones, 10).unwrap();
+        assert_eq!(
+            rock,
+            Rock {
+                position: [24, 13, 10],
+                velocity: [-3, 1, 2],
+            }
+        );
+        assert_eq!(rock.position.iter().sum::<i128>(), 47);
+
+        // Too slow to catch them all
+        assert_eq!(find_rock(&hailstones, 2), None);
+    }
+
+    #[test]
+    fn rock_throw_at_puzzle_scale() {
+        // Hailstones made up to be hit by a rock thrown from far away, each at its own time
+        let rock = Rock {
+            position: [
+                287_430_900_705_823,
+                451_620_998_712_421,
+                260_730_677_041_648,
+            ],
+            velocity: [-191, -100, 73],
+        };
+        let hits = [(5_000_000_000, [11, -7, 2]), (8_600_000_000, [-40, 3, -19])];
+        let more = [(913_000_000_000, [25, 9, 4]), (2_400_000_000, [0, 0, 1])];
+        let hailstones = hits
+            .iter()
+            .chain(&more)
+            .map(|&(t, velocity): &(i128, [i128; 3])| {
+                let position = [0, 1, 2].map(|i| {
+                    let hit = rock.position[i] + rock.velocity[i] * t;
+                    (hit - velocity[i] * t) as i64
+                });
+                Hailstone {
+                    position,
+                    velocity: velocity.map(|v| v as i64),
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(find_rock(&hailstones, 200), Some(rock));
+    }
+
+    #[test]
+    fn exact_at_puzzle_scale() {
+        // Paths crossing exactly on the edge of the real test area still count
+        let a = "200000000000000, 0, 0 @ 0, 1, 0"
+            .parse::<Hailstone>()
+            .unwrap();
+        let b = "0, 400000000000000, 0 @ 1, 0, 0"
+            .parse::<Hailstone>()
+            .unwrap();
+        assert!(a.paths_cross_within(&b, &TEST_AREA));
+
+        let b = "0, 400000000000001, 0 @ 1, 0, 0"
+            .parse::<Hailstone>()
+            .unwrap();
+        assert!(!a.paths_cross_within(&b, &TEST_AREA));
+    }
+}
@@ -0,0 +1,124 @@
+//! Plots the hailstones' paths across the test area as an SVG, to see which crossings part 1
+//! counts and where the rock from part 2 goes.
+
+use crate::geometry::Ray2;
+use crate::{Hailstone, Rock};
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+
+/// How wide and tall the plot is, the test area filling all of it
+const SIZE: f64 = 1000.0;
+
+/// Writes the part of each hailstone's path that's inside `area`, with a dot wherever two of
+/// them cross inside it and, if there is one, the path of `rock` drawn over the top. Paths that
+/// start inside the area get a dot where they start.
+pub fn write_svg(
+    hailstones: &[Hailstone],
+    area: &RangeInclusive<i64>,
+    rock: Option<&Rock>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let (min, max) = (*area.start() as f64, *area.end() as f64);
+    // The Y axis goes up, so it's flipped to draw
+    let to_plot = |(x, y): (f64, f64)| {
+        let scale = SIZE / (max - min).max(1.0);
+        ((x - min) * scale, SIZE - (y - min) * scale)
+    };
+    let write_path = |out: &mut dyn Write, path: &Ray2| -> io::Result<()> {
+        let Some((from, to)) = path.times_within(area) else {
+            return Ok(());
+        };
+        let (x1, y1) = to_plot(path.at(from));
+        let (x2, y2) = to_plot(path.at(to));
+        writeln!(
+            out,
+            r#"  <line x1="{x1:.1}" y1="{y1:.1}" x2="{x2:.1}" y2="{y2:.1}"/>"#
+        )?;
+        if from == 0.0 {
+            writeln!(out, r#"  <circle cx="{x1:.1}" cy="{y1:.1}" r="3"/>"#)?;
+        }
+        Ok(())
+    };
+
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {SIZE} {SIZE}">"#
+    )?;
+    writeln!(
+        out,
+        r##"<rect width="{SIZE}" height="{SIZE}" fill="#0F1419"/>"##
+    )?;
+
+    writeln!(
+        out,
+        r##"<g stroke="#5C87B8" fill="#5C87B8" stroke-width="1" stroke-opacity="0.6">"##
+    )?;
+    for hailstone in hailstones {
+        write_path(out, &hailstone.path())?;
+    }
+    writeln!(out, "</g>")?;
+
+    writeln!(out, r##"<g fill="#E0243B">"##)?;
+    for (i, a) in hailstones.iter().enumerate() {
+        for b in &hailstones[i + 1..] {
+            let Some(crossing) = a.path().crossing(&b.path()) else {
+                continue;
+            };
+            if crossing.is_within(area) {
+                let (x, y) = to_plot(crossing.to_f64());
+                writeln!(out, r#"  <circle cx="{x:.1}" cy="{y:.1}" r="2"/>"#)?;
+            }
+        }
+    }
+    writeln!(out, "</g>")?;
+
+    if let Some(rock) = rock {
+        writeln!(
+            out,
+            r##"<g stroke="#FF8C1A" fill="#FF8C1A" stroke-width="4">"##
+        )?;
+        write_path(out, &rock.path())?;
+        writeln!(out, "</g>")?;
+    }
+
+    writeln!(out, "</svg>")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn example_plot() {
+        let hailstones = ["19, 13, 30 @ -2,  1, -2", "18, 19, 22 @ -1, -1, -2"]
+            .iter()
+            .map(|line| line.parse::<Hailstone>().unwrap())
+            .collect::<Vec<_>>();
+        let rock = Rock {
+            position: [24, 13, 10],
+            velocity: [-3, 1, 2],
+        };
+        let mut svg = vec![];
+        write_svg(&hailstones, &(7..=27), Some(&rock), &mut svg).unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        let lines = svg.lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            lines.iter().filter(|line| line.contains("<line")).count(),
+            3
+        );
+        // Both hailstones and the rock start inside the area, and the hailstones cross once
+        assert_eq!(
+            lines.iter().filter(|line| line.contains("<circle")).count(),
+            4
+        );
+        // The first hailstone goes up and left until it leaves the area at x = 7
+        assert_eq!(
+            lines[3],
+            r#"  <line x1="600.0" y1="700.0" x2="0.0" y2="400.0"/>"#
+        );
+        // The paths cross at 14.333, 15.333
+        assert!(svg.contains(r#"<circle cx="366.7" cy="583.3" r="2"/>"#));
+        assert_eq!(lines.last(), Some(&"</svg>"));
+    }
+}
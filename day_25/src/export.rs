@@ -0,0 +1,64 @@
+//! Writes the wiring diagram out as a Graphviz graph, to check the cut by eye: the two groups of
+//! components come out in different colors, with the wires between them picked out.
+
+use crate::WiringDiagram;
+use std::io::{self, Write};
+
+const GROUP_COLORS: [&str; 2] = ["#5C87B8", "#8CB85C"];
+const CUT_COLOR: &str = "#E0243B";
+
+/// Writes `diagram` as DOT, coloring each component by whether `group` has it in the first group
+/// and drawing the wires that cross between the groups thick and red. The graph asks for the
+/// `sfdp` layout, since `dot`'s layered one is no good for a puzzle-sized diagram.
+pub fn write_dot(diagram: &WiringDiagram, group: &[bool], out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "graph wiring {{")?;
+    writeln!(out, "  layout=sfdp;")?;
+    writeln!(out, "  overlap=false;")?;
+    writeln!(out, "  node [shape=point, width=0.1];")?;
+    for (name, &in_first) in diagram.names.iter().zip(group) {
+        let color = GROUP_COLORS[usize::from(!in_first)];
+        writeln!(out, r#"  "{name}" [color="{color}"];"#)?;
+    }
+    for (a, b) in diagram.wires() {
+        let (a_name, b_name) = (&diagram.names[a], &diagram.names[b]);
+        if group[a] == group[b] {
+            writeln!(out, r#"  "{a_name}" -- "{b_name}";"#)?;
+        } else {
+            writeln!(
+                out,
+                r#"  "{a_name}" -- "{b_name}" [color="{CUT_COLOR}", penwidth=4, label="{a_name}/{b_name}"];"#
+            )?;
+        }
+    }
+    writeln!(out, "}}")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cut_wires_highlighted() {
+        let diagram = "a: b c\nb: c\nc: d\nd: e f\ne: f"
+            .parse::<WiringDiagram>()
+            .unwrap();
+        let group = diagram.find_cut(1).unwrap();
+        let mut dot = vec![];
+        write_dot(&diagram, &group, &mut dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        let lines = dot.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[4], r##"  "a" [color="#5C87B8"];"##);
+        assert_eq!(lines[7], r##"  "d" [color="#8CB85C"];"##);
+        assert_eq!(lines.iter().filter(|line| line.contains(" -- ")).count(), 7);
+        let cut = lines
+            .iter()
+            .filter(|line| line.contains(CUT_COLOR))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            cut,
+            vec![&r##"  "c" -- "d" [color="#E0243B", penwidth=4, label="c/d"];"##]
+        );
+        assert_eq!(lines.last(), Some(&"}"));
+    }
+}
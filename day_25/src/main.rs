@@ -0,0 +1,272 @@
+mod export;
+
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process::{self, Command, Stdio};
+use std::str::FromStr;
+
+/// How many wires have to be disconnected to split the components into two groups
+const WIRES_TO_CUT: usize = 3;
+
+/// The components and the wires between them. Each wire is stored as a pair of arcs, one each
+/// way, at indices `2 * i` and `2 * i + 1`, so an arc's reverse is always at `arc ^ 1`.
+#[derive(Debug, Eq, PartialEq)]
+struct WiringDiagram {
+    /// The arcs leaving each component, as indices into `heads`
+    arcs: Vec<Vec<usize>>,
+    /// The component each arc leads to
+    heads: Vec<usize>,
+    /// The name of each component
+    names: Vec<String>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ParseWiringError;
+
+impl FromStr for WiringDiagram {
+    type Err = ParseWiringError;
+
+    /// Parses lines like `jqt: rhn xhk nvd`, each listing some of a component's connections
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut indices = HashMap::new();
+        let mut diagram = Self {
+            arcs: vec![],
+            heads: vec![],
+            names: vec![],
+        };
+        let mut index_of = |name: &str, diagram: &mut Self| {
+            *indices.entry(name.to_string()).or_insert_with(|| {
+                diagram.arcs.push(vec![]);
+                diagram.names.push(name.to_string());
+                diagram.arcs.len() - 1
+            })
+        };
+
+        for line in s.lines() {
+            let (name, others) = line.split_once(':').ok_or(ParseWiringError)?;
+            let name = name.trim();
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(ParseWiringError);
+            }
+            let from = index_of(name, &mut diagram);
+            for other in others.split_whitespace() {
+                let to = index_of(other, &mut diagram);
+                diagram.connect(from, to);
+            }
+        }
+
+        Ok(diagram)
+    }
+}
+
+impl WiringDiagram {
+    fn connect(&mut self, from: usize, to: usize) {
+        self.arcs[from].push(self.heads.len());
+        self.heads.push(to);
+        self.arcs[to].push(self.heads.len());
+        self.heads.push(from);
+    }
+
+    /// Pushes one more unit of flow from `source` to `sink` along a shortest path with room left,
+    /// returning whether there was one. `flow` holds the flow along each arc, and every wire
+    /// carries at most one unit either way.
+    ///
+    /// Whether it pushes anything or not, `reached` is left marking every component `source` can
+    /// still get flow to.
+    fn augment(&self, source: usize, sink: usize, flow: &mut [i8], reached: &mut [bool]) -> bool {
+        reached.fill(false);
+        reached[source] = true;
+        // The arc each component was first reached through
+        let mut via = vec![None; self.arcs.len()];
+        let mut queue = VecDeque::from([source]);
+        while let Some(component) = queue.pop_front() {
+            for &arc in &self.arcs[component] {
+                let next = self.heads[arc];
+                if flow[arc] < 1 && !reached[next] {
+                    reached[next] = true;
+                    via[next] = Some(arc);
+                    queue.push_back(next);
+                }
+            }
+        }
+        if !reached[sink] {
+            return false;
+        }
+
+        let mut component = sink;
+        while let Some(arc) = via[component] {
+            flow[arc] += 1;
+            flow[arc ^ 1] -= 1;
+            component = self.heads[arc ^ 1];
+        }
+        true
+    }
+
+    /// Finds a way to split the components into two groups with exactly `wires` wires between
+    /// them, returning which components are in the same group as the first one.
+    ///
+    /// The first component has to end up in one of the groups, so this tries every other
+    /// component as the one in the other group. Between those two, the fewest wires that
+    /// separate them is the most flow that can get from one to the other with every wire carrying
+    /// one unit, so it pushes flow until there's no more room or it's gone past `wires`. If it
+    /// stops at exactly `wires`, the components that flow can still reach are one of the groups.
+    fn find_cut(&self, wires: usize) -> Option<Vec<bool>> {
+        let source = 0;
+        let mut reached = vec![false; self.arcs.len()];
+        for sink in 1..self.arcs.len() {
+            let mut flow = vec![0; self.heads.len()];
+            let mut pushed = 0;
+            while pushed <= wires && self.augment(source, sink, &mut flow, &mut reached) {
+                pushed += 1;
+            }
+            if pushed == wires {
+                return Some(reached);
+            }
+        }
+        None
+    }
+
+    /// Same as [WiringDiagram::find_cut], returning the size of each group
+    fn split_by_cut(&self, wires: usize) -> Option<(usize, usize)> {
+        let group = self.find_cut(wires)?;
+        let first = group.iter().filter(|&&in_group| in_group).count();
+        Some((first, group.len() - first))
+    }
+
+    /// Each wire as the two components it connects
+    fn wires(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.heads.chunks_exact(2).map(|arcs| (arcs[1], arcs[0]))
+    }
+}
+
+fn main() {
+    let diagram = fs::read_to_string("input.txt")
+        .expect("failed to open input file")
+        .parse::<WiringDiagram>()
+        .expect("failed to parse wiring diagram");
+
+    match diagram.split_by_cut(WIRES_TO_CUT) {
+        Some((first, second)) => println!("Part 1 result: {}", first * second),
+        None => {
+            println!("Part 1 result not found: no {WIRES_TO_CUT} wires split the components in two")
+        }
+    }
+
+    let args: Vec<String> = env::args().collect();
+    if let Some(i) = args.iter().position(|arg| arg == "--export") {
+        let Some(path) = args.get(i + 1) else {
+            eprintln!("--export needs an output path");
+            process::exit(1);
+        };
+        let Some(group) = diagram.find_cut(WIRES_TO_CUT) else {
+            println!("No cut to export");
+            return;
+        };
+        if let Err(err) = export_graph(&diagram, &group, Path::new(path)) {
+            eprintln!("failed to export the wiring diagram: {err}");
+            process::exit(1);
+        }
+        println!("Wrote the wiring diagram to {path}");
+    }
+}
+
+/// Writes the diagram as DOT, or as an SVG laid out by Graphviz if `path` ends in `.svg`
+fn export_graph(diagram: &WiringDiagram, group: &[bool], path: &Path) -> io::Result<()> {
+    if path.extension().is_some_and(|extension| extension == "svg") {
+        let mut dot = Command::new("dot")
+            .arg("-Tsvg")
+            .arg("-o")
+            .arg(path)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|err| io::Error::new(err.kind(), format!("couldn't run Graphviz: {err}")))?;
+        if let Some(stdin) = dot.stdin.take() {
+            let mut stdin = BufWriter::new(stdin);
+            export::write_dot(diagram, group, &mut stdin)?;
+            stdin.flush()?;
+        }
+        let status = dot.wait()?;
+        if !status.success() {
+            return Err(io::Error::other(format!("Graphviz exited with {status}")));
+        }
+        Ok(())
+    } else {
+        let mut out = BufWriter::new(File::create(path)?);
+        export::write_dot(diagram, group, &mut out)?;
+        out.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "jqt: rhn xhk nvd\n\
+                              rsh: frs pzl lsr\n\
+                              xhk: hfx\n\
+                              cmg: qnr nvd lhk bvb\n\
+                              rhn: xhk bvb hfx\n\
+                              bvb: xhk hfx\n\
+                              pzl: lsr hfx nvd\n\
+                              qnr: nvd\n\
+                              ntq: jqt hfx bvb xhk\n\
+                              nvd: lhk\n\
+                              lsr: lhk\n\
+                              rzs: qnr cmg lsr rsh\n\
+                              frs: qnr lhk lsr\n";
+
+    #[test]
+    fn parse_diagram() {
+        let diagram = "a: b c\nb: c".parse::<WiringDiagram>().unwrap();
+        assert_eq!(diagram.arcs, vec![vec![0, 2], vec![1, 4], vec![3, 5]]);
+        assert_eq!(diagram.heads, vec![1, 0, 2, 0, 2, 1]);
+        assert_eq!(diagram.names, vec!["a", "b", "c"]);
+        assert_eq!(
+            diagram.wires().collect::<Vec<_>>(),
+            vec![(0, 1), (0, 2), (1, 2)]
+        );
+
+        assert_eq!("a b c".parse::<WiringDiagram>(), Err(ParseWiringError));
+        assert_eq!(": b c".parse::<WiringDiagram>(), Err(ParseWiringError));
+
+        let diagram = TEST_INPUT.parse::<WiringDiagram>().unwrap();
+        assert_eq!(diagram.arcs.len(), 15);
+        assert_eq!(diagram.heads.len(), 2 * 33);
+    }
+
+    #[test]
+    fn split_components() {
+        let diagram = TEST_INPUT.parse::<WiringDiagram>().unwrap();
+        let (first, second) = diagram.split_by_cut(WIRES_TO_CUT).unwrap();
+        assert_eq!(first * second, 54);
+        assert_eq!(first + second, 15);
+
+        // The cut is the three wires from the puzzle
+        let group = diagram.find_cut(WIRES_TO_CUT).unwrap();
+        let mut cut = diagram
+            .wires()
+            .filter(|&(a, b)| group[a] != group[b])
+            .map(|(a, b)| {
+                let mut names = [&diagram.names[a], &diagram.names[b]];
+                names.sort();
+                format!("{}/{}", names[0], names[1])
+            })
+            .collect::<Vec<_>>();
+        cut.sort();
+        assert_eq!(cut, vec!["bvb/cmg", "hfx/pzl", "jqt/nvd"]);
+    }
+
+    #[test]
+    fn no_split() {
+        // Every component is wired to all the others, so the fewest wires that split them is
+        // the four around any one of them
+        let diagram = "a: b c d e\nb: c d e\nc: d e\nd: e"
+            .parse::<WiringDiagram>()
+            .unwrap();
+        assert_eq!(diagram.split_by_cut(3), None);
+        assert_eq!(diagram.split_by_cut(4), Some((1, 4)));
+    }
+}